@@ -2,6 +2,10 @@ pub mod password;
 
 pub mod sign;
 
+pub mod token;
+
+pub mod totp;
+
 pub use password::{hash_password, verify_password};
 
 pub fn new_rng() -> rand::rngs::StdRng {
@@ -19,3 +23,16 @@ pub fn decode_base64<T: AsRef<[u8]>>(data: T) -> Result<Vec<u8>, base64::DecodeE
 
     general_purpose::STANDARD_NO_PAD.decode(data.as_ref())
 }
+
+/// Encodes with the URL-safe alphabet, for data which ends up in links.
+pub fn encode_base64_url<T: AsRef<[u8]>>(data: T) -> String {
+    use base64::{engine::general_purpose, Engine as _};
+
+    general_purpose::URL_SAFE_NO_PAD.encode(data)
+}
+
+pub fn decode_base64_url<T: AsRef<[u8]>>(data: T) -> Result<Vec<u8>, base64::DecodeError> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    general_purpose::URL_SAFE_NO_PAD.decode(data.as_ref())
+}
@@ -0,0 +1,36 @@
+//! Random tokens which are sent to users, such as in password reset links, and stored hashed.
+
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// A new URL-safe token with 256 bits of randomness.
+pub fn new_token<R>(rng: &mut R) -> String
+where
+    R: CryptoRng + RngCore,
+{
+    let mut bytes = [0; 32];
+    rng.fill_bytes(&mut bytes);
+    crate::encode_base64_url(bytes)
+}
+
+/// Hash under which a token is stored.
+///
+/// Tokens are random rather than chosen by users, so a fast hash is enough to keep them from
+/// being usable by anyone who reads the database.
+pub fn hash_token<T: AsRef<str>>(token: T) -> String {
+    crate::encode_base64(Sha256::digest(token.as_ref().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_unique_and_hash_consistently() {
+        let mut rng = crate::new_rng();
+        let token = new_token(&mut rng);
+        assert_ne!(token, new_token(&mut rng));
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), token);
+    }
+}
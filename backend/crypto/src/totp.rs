@@ -0,0 +1,175 @@
+//! Time-based one-time passwords (RFC 6238), as generated by authenticator apps, and the recovery
+//! codes which stand in for them when the app is lost.
+
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand_core::{CryptoRng, RngCore};
+use sha1::Sha1;
+
+/// Seconds each code is valid for.
+pub const PERIOD: u64 = 30;
+/// Number of digits in a code.
+pub const DIGITS: u32 = 6;
+/// Codes from this many periods before or after the current one are accepted, to allow for
+/// clocks which are slightly off.
+const ALLOWED_DRIFT: u64 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid TOTP secret")]
+    InvalidSecret,
+}
+
+/// A new secret shared with the authenticator app, encoded as base32.
+pub fn new_secret<R>(rng: &mut R) -> String
+where
+    R: CryptoRng + RngCore,
+{
+    let mut bytes = [0; 20];
+    rng.fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+fn decode_secret(secret: &str) -> Result<Vec<u8>, Error> {
+    let secret = secret.trim_end_matches('=').to_ascii_uppercase();
+    BASE32_NOPAD
+        .decode(secret.as_bytes())
+        .map_err(|_| Error::InvalidSecret)
+}
+
+/// The period containing `unix_time`.
+pub fn step_at(unix_time: u64) -> u64 {
+    unix_time / PERIOD
+}
+
+fn code_for_step(key: &[u8], step: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    // dynamic truncation, RFC 4226 section 5.3
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+/// The code for `secret` at `unix_time`.
+pub fn code_at(secret: &str, unix_time: u64) -> Result<String, Error> {
+    let key = decode_secret(secret)?;
+    Ok(code_for_step(&key, step_at(unix_time)))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Checks `code` against `secret` at `unix_time`.
+///
+/// Returns the period the code belongs to, which callers should record so that a code can't be
+/// used twice.
+pub fn verify(secret: &str, code: &str, unix_time: u64) -> Result<Option<u64>, Error> {
+    let key = decode_secret(secret)?;
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let current = step_at(unix_time);
+
+    let first = current.saturating_sub(ALLOWED_DRIFT);
+    Ok((first..=current + ALLOWED_DRIFT)
+        .find(|&step| constant_time_eq(code_for_step(&key, step).as_bytes(), code.as_bytes())))
+}
+
+fn encode_uri_component(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// URI which authenticator apps use to add an account, usually scanned from a QR code.
+pub fn otpauth_uri(secret: &str, issuer: &str, account: &str) -> String {
+    let issuer = encode_uri_component(issuer);
+    let account = encode_uri_component(account);
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={PERIOD}"
+    )
+}
+
+/// New single-use recovery codes, formatted as `xxxx-xxxx-xxxx`.
+pub fn new_recovery_codes<R>(rng: &mut R, count: usize) -> Vec<String>
+where
+    R: CryptoRng + RngCore,
+{
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0; 8];
+            rng.fill_bytes(&mut bytes);
+            let code = BASE32_NOPAD.encode(&bytes).to_ascii_lowercase();
+            format!("{}-{}-{}", &code[0..4], &code[4..8], &code[8..12])
+        })
+        .collect()
+}
+
+/// Recovery code as it is hashed, ignoring case, spaces and dashes in what the user typed.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA1 secret "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn generates_rfc_6238_codes() {
+        assert_eq!(code_at(RFC_SECRET, 59).unwrap(), "287082");
+        assert_eq!(code_at(RFC_SECRET, 1111111109).unwrap(), "081804");
+        assert_eq!(code_at(RFC_SECRET, 1234567890).unwrap(), "005924");
+        assert_eq!(code_at(RFC_SECRET, 2000000000).unwrap(), "279037");
+    }
+
+    #[test]
+    fn verifies_codes_within_allowed_drift() {
+        let mut rng = crate::new_rng();
+        let secret = new_secret(&mut rng);
+        let now = 1_700_000_000;
+        let code = code_at(&secret, now).unwrap();
+
+        assert_eq!(verify(&secret, &code, now).unwrap(), Some(step_at(now)));
+        assert_eq!(
+            verify(&secret, &code, now + PERIOD).unwrap(),
+            Some(step_at(now))
+        );
+        assert_eq!(verify(&secret, &code, now + 3 * PERIOD).unwrap(), None);
+        assert_eq!(verify(&secret, "000000x", now).unwrap(), None);
+    }
+
+    #[test]
+    fn recovery_codes_are_normalized() {
+        let mut rng = crate::new_rng();
+        let codes = new_recovery_codes(&mut rng, 10);
+        assert_eq!(codes.len(), 10);
+        let code = &codes[0];
+        assert_eq!(code.len(), 14);
+        assert_eq!(
+            normalize_recovery_code(&code.to_uppercase().replace('-', " ")),
+            normalize_recovery_code(code)
+        );
+    }
+}
@@ -0,0 +1 @@
+DROP MATERIALIZED VIEW IF EXISTS public.trending_scores CASCADE;
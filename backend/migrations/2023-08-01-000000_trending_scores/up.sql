@@ -0,0 +1,37 @@
+-- Trending scores for recent public posts.
+--
+-- Interactions are weighted (boosts count double, dislikes count against) and decayed by the age
+-- of the post at the time of the last refresh. The view is refreshed periodically by the API
+-- server, so reading trending posts never aggregates reactions at request time.
+CREATE MATERIALIZED VIEW public.trending_scores AS
+SELECT
+  posts.id AS post_id,
+  posts.time_posted,
+  (
+    COALESCE(reactions.likes, 0)
+    - COALESCE(reactions.dislikes, 0)
+    + 2 * COALESCE(boosts.boosts, 0)
+  )::float8
+  / power(EXTRACT(EPOCH FROM (now() - posts.time_posted)) / 3600 + 2, 1.5) AS score
+FROM public.posts
+LEFT JOIN (
+  SELECT
+    post_id,
+    count(*) FILTER (WHERE like_status = 1) AS likes,
+    count(*) FILTER (WHERE like_status = -1) AS dislikes
+  FROM public.reactions
+  GROUP BY post_id
+) reactions ON reactions.post_id = posts.id
+LEFT JOIN (
+  SELECT post_id, count(*) AS boosts
+  FROM public.boosts
+  GROUP BY post_id
+) boosts ON boosts.post_id = posts.id
+WHERE posts.direct_message_to IS NULL
+  AND posts.time_posted <= now()
+  AND posts.time_posted > now() - interval '7 days';
+
+-- required for REFRESH MATERIALIZED VIEW CONCURRENTLY
+CREATE UNIQUE INDEX trending_scores_post_id_index ON public.trending_scores (post_id);
+
+CREATE INDEX trending_scores_score_index ON public.trending_scores (score DESC, post_id DESC);
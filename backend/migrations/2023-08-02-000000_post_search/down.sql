@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS public.post_search_index CASCADE;
+DROP FUNCTION IF EXISTS public.post_search_vector(jsonb) CASCADE;
+DROP FUNCTION IF EXISTS public.post_search_text(jsonb) CASCADE;
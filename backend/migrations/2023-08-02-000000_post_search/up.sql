@@ -0,0 +1,24 @@
+-- Searchable text of a post. Mirrors the JSON layout of `uchat_endpoint::post::Content`.
+CREATE FUNCTION public.post_search_text(content jsonb) RETURNS text
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+  SELECT concat_ws(' ',
+    content #>> '{Chat,headline}',
+    content #>> '{Chat,message}',
+    content #>> '{Image,caption}',
+    content #>> '{Poll,headline}',
+    (
+      SELECT string_agg(choice ->> 'description', ' ')
+      FROM jsonb_array_elements(content #> '{Poll,choices}') AS choice
+    )
+  )
+$$;
+
+CREATE FUNCTION public.post_search_vector(content jsonb) RETURNS tsvector
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+  SELECT to_tsvector('english'::regconfig, public.post_search_text(content))
+$$;
+
+CREATE INDEX post_search_index ON public.posts
+USING gin (public.post_search_vector(content));
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS public.users_display_name_trgm_index CASCADE;
+DROP INDEX IF EXISTS public.users_handle_trgm_index CASCADE;
@@ -0,0 +1,8 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- serve both prefix (ILIKE 'abc%') and fuzzy (%) matching
+CREATE INDEX users_handle_trgm_index ON public.users
+USING gin (handle gin_trgm_ops);
+
+CREATE INDEX users_display_name_trgm_index ON public.users
+USING gin (display_name gin_trgm_ops);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS public.posts_direct_message_index CASCADE;
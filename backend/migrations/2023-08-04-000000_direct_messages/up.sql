@@ -0,0 +1,8 @@
+-- messages received by a user; messages sent are covered by post_pagination_index
+CREATE INDEX posts_direct_message_index ON public.posts
+USING btree
+(
+  direct_message_to,
+  time_posted
+)
+WHERE direct_message_to IS NOT NULL;
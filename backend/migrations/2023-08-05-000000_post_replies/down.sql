@@ -0,0 +1 @@
+DROP INDEX IF EXISTS public.posts_reply_index CASCADE;
@@ -0,0 +1,9 @@
+-- replies to a post, walked by the thread queries
+CREATE INDEX posts_reply_index ON public.posts
+USING btree
+(
+  reply_to,
+  time_posted,
+  id
+)
+WHERE reply_to IS NOT NULL;
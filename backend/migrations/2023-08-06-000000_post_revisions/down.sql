@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS public.post_revisions CASCADE;
+ALTER TABLE public.posts DROP COLUMN IF EXISTS edited_at;
@@ -0,0 +1,21 @@
+ALTER TABLE public.posts ADD COLUMN edited_at timestamptz;
+
+-- previous content of edited posts
+CREATE TABLE public.post_revisions (
+  id uuid NOT NULL,
+  post_id uuid NOT NULL,
+  content jsonb NOT NULL,
+  revised_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT post_revisions_pk PRIMARY KEY (id)
+);
+
+ALTER TABLE public.post_revisions ADD CONSTRAINT post_id_fk FOREIGN KEY (post_id)
+REFERENCES public.posts (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+CREATE INDEX post_revisions_post_index ON public.post_revisions
+USING btree
+(
+  post_id,
+  revised_at
+);
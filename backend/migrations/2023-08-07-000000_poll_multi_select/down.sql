@@ -0,0 +1,13 @@
+UPDATE public.post_revisions SET content = jsonb_set(content, '{Poll,voted}', 'null'::jsonb)
+WHERE content ? 'Poll';
+UPDATE public.posts SET content = jsonb_set(content, '{Poll,voted}', 'null'::jsonb)
+WHERE content ? 'Poll';
+
+-- only one vote per user and poll can be kept
+DELETE FROM public.poll_votes a USING public.poll_votes b
+WHERE a.user_id = b.user_id AND a.post_id = b.post_id AND a.created_at > b.created_at;
+DELETE FROM public.poll_votes a USING public.poll_votes b
+WHERE a.user_id = b.user_id AND a.post_id = b.post_id AND a.choice_id > b.choice_id;
+
+ALTER TABLE public.poll_votes DROP CONSTRAINT IF EXISTS poll_votes_pk;
+ALTER TABLE public.poll_votes ADD CONSTRAINT poll_votes_pk PRIMARY KEY (user_id,post_id);
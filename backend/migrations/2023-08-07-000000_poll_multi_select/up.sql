@@ -0,0 +1,9 @@
+-- a user may vote for several choices of a poll
+ALTER TABLE public.poll_votes DROP CONSTRAINT IF EXISTS poll_votes_pk;
+ALTER TABLE public.poll_votes ADD CONSTRAINT poll_votes_pk PRIMARY KEY (user_id,post_id,choice_id);
+
+-- the choices voted for are now a set instead of a single optional choice
+UPDATE public.posts SET content = jsonb_set(content, '{Poll,voted}', '[]'::jsonb)
+WHERE content ? 'Poll';
+UPDATE public.post_revisions SET content = jsonb_set(content, '{Poll,voted}', '[]'::jsonb)
+WHERE content ? 'Poll';
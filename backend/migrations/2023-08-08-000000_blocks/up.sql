@@ -0,0 +1,23 @@
+-- users which a user has blocked
+CREATE TABLE public.blocks (
+  user_id uuid NOT NULL,
+  blocked uuid NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT blocks_pk PRIMARY KEY (user_id,blocked)
+);
+
+ALTER TABLE public.blocks ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+ALTER TABLE public.blocks ADD CONSTRAINT blocked_user_id_fk FOREIGN KEY (blocked)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+-- blocks are checked in both directions
+CREATE INDEX blocks_blocked_index ON public.blocks
+USING btree
+(
+  blocked,
+  user_id
+);
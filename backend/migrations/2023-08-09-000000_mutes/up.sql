@@ -0,0 +1,40 @@
+-- users and reply threads which a user no longer wants to see, optionally until expires_at
+CREATE TABLE public.mutes (
+  id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  muted_user uuid,
+  muted_post uuid,
+  expires_at timestamptz,
+  created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT mutes_pk PRIMARY KEY (id),
+  CONSTRAINT mutes_target_check CHECK ((muted_user IS NULL) <> (muted_post IS NULL))
+);
+
+ALTER TABLE public.mutes ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+ALTER TABLE public.mutes ADD CONSTRAINT muted_user_id_fk FOREIGN KEY (muted_user)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+ALTER TABLE public.mutes ADD CONSTRAINT muted_post_id_fk FOREIGN KEY (muted_post)
+REFERENCES public.posts (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+-- a user or thread is muted at most once per user
+CREATE UNIQUE INDEX mutes_user_index ON public.mutes
+USING btree
+(
+  user_id,
+  muted_user
+)
+WHERE muted_user IS NOT NULL;
+
+CREATE UNIQUE INDEX mutes_post_index ON public.mutes
+USING btree
+(
+  user_id,
+  muted_post
+)
+WHERE muted_post IS NOT NULL;
@@ -0,0 +1,38 @@
+-- activity other users caused which a user should be told about
+CREATE TABLE public.notifications (
+  id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  actor uuid NOT NULL,
+  kind text NOT NULL,
+  post_id uuid,
+  -- scheduled replies and messages notify once they are published
+  created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  read_at timestamptz,
+  CONSTRAINT notifications_pk PRIMARY KEY (id)
+);
+
+ALTER TABLE public.notifications ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+ALTER TABLE public.notifications ADD CONSTRAINT actor_id_fk FOREIGN KEY (actor)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+ALTER TABLE public.notifications ADD CONSTRAINT post_id_fk FOREIGN KEY (post_id)
+REFERENCES public.posts (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+CREATE INDEX notifications_user_index ON public.notifications
+USING btree
+(
+  user_id,
+  created_at
+);
+
+CREATE INDEX notifications_unread_index ON public.notifications
+USING btree
+(
+  user_id
+)
+WHERE read_at IS NULL;
@@ -0,0 +1,21 @@
+-- users mentioned with @handle in a post
+CREATE TABLE public.post_mentions (
+  post_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  CONSTRAINT post_mentions_pk PRIMARY KEY (post_id,user_id)
+);
+
+ALTER TABLE public.post_mentions ADD CONSTRAINT post_id_fk FOREIGN KEY (post_id)
+REFERENCES public.posts (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+ALTER TABLE public.post_mentions ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+-- mentions of a user are listed by looking up the user
+CREATE INDEX post_mentions_user_id_index ON public.post_mentions
+USING btree
+(
+  user_id
+);
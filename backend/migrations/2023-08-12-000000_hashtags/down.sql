@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS public.post_hashtags CASCADE;
+DROP TABLE IF EXISTS public.hashtags CASCADE;
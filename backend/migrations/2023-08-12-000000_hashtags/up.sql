@@ -0,0 +1,30 @@
+-- normalized hashtags, stored once
+CREATE TABLE public.hashtags (
+  id uuid NOT NULL,
+  tag text NOT NULL,
+  CONSTRAINT hashtags_pk PRIMARY KEY (id),
+  CONSTRAINT tag_is_unique UNIQUE (tag)
+);
+
+-- hashtags used by a post
+CREATE TABLE public.post_hashtags (
+  post_id uuid NOT NULL,
+  hashtag_id uuid NOT NULL,
+  CONSTRAINT post_hashtags_pk PRIMARY KEY (post_id,hashtag_id)
+);
+
+ALTER TABLE public.post_hashtags ADD CONSTRAINT post_id_fk FOREIGN KEY (post_id)
+REFERENCES public.posts (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+ALTER TABLE public.post_hashtags ADD CONSTRAINT hashtag_id_fk FOREIGN KEY (hashtag_id)
+REFERENCES public.hashtags (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+-- tag timelines look up posts by hashtag
+CREATE INDEX post_hashtags_hashtag_id_index ON public.post_hashtags
+USING btree
+(
+  hashtag_id,
+  post_id
+);
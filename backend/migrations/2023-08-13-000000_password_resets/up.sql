@@ -0,0 +1,15 @@
+-- single-use password reset tokens, stored hashed
+CREATE TABLE public.password_resets (
+  id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  token_hash text NOT NULL,
+  expires_at timestamptz NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  used_at timestamptz,
+  CONSTRAINT password_resets_pk PRIMARY KEY (id),
+  CONSTRAINT token_hash_is_unique UNIQUE (token_hash)
+);
+
+ALTER TABLE public.password_resets ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS public.pending_logins CASCADE;
+DROP TABLE IF EXISTS public.recovery_codes CASCADE;
+DROP TABLE IF EXISTS public.two_factor CASCADE;
@@ -0,0 +1,46 @@
+-- TOTP secrets; enrollment is finished once the first code is confirmed
+CREATE TABLE public.two_factor (
+  user_id uuid NOT NULL,
+  secret text NOT NULL,
+  confirmed_at timestamptz,
+  -- last time step a code was accepted for, so codes can't be replayed
+  last_used_step bigint,
+  -- wrong codes entered since the last successful code, locking out the second factor
+  failed_attempts integer NOT NULL DEFAULT 0,
+  last_failed_at timestamptz,
+  created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT two_factor_pk PRIMARY KEY (user_id)
+);
+
+ALTER TABLE public.two_factor ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+-- single-use codes for logging in without the authenticator app, stored hashed
+CREATE TABLE public.recovery_codes (
+  id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  code_hash text NOT NULL,
+  used_at timestamptz,
+  CONSTRAINT recovery_codes_pk PRIMARY KEY (id),
+  CONSTRAINT recovery_code_is_unique UNIQUE (user_id,code_hash)
+);
+
+ALTER TABLE public.recovery_codes ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
+
+-- logins waiting for the second factor, identified by a hashed token
+CREATE TABLE public.pending_logins (
+  id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  token_hash text NOT NULL,
+  expires_at timestamptz NOT NULL,
+  failed_attempts integer NOT NULL DEFAULT 0,
+  CONSTRAINT pending_logins_pk PRIMARY KEY (id),
+  CONSTRAINT pending_login_token_hash_is_unique UNIQUE (token_hash)
+);
+
+ALTER TABLE public.pending_logins ADD CONSTRAINT user_id_fk FOREIGN KEY (user_id)
+REFERENCES public.users (id) MATCH SIMPLE
+ON DELETE CASCADE ON UPDATE NO ACTION;
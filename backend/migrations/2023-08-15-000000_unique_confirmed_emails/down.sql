@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS public.users_confirmed_email_index CASCADE;
+ALTER TABLE public.users ADD CONSTRAINT email_is_unique UNIQUE (email);
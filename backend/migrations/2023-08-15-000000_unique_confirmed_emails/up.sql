@@ -0,0 +1,8 @@
+-- unconfirmed addresses may be shared, so nobody can hold an address hostage by entering it first
+ALTER TABLE public.users DROP CONSTRAINT IF EXISTS email_is_unique;
+
+-- addresses are stored trimmed and lowercased
+UPDATE public.users SET email = lower(trim(email)) WHERE email IS NOT NULL;
+
+CREATE UNIQUE INDEX users_confirmed_email_index ON public.users
+USING btree (lower(email)) WHERE email_confirmed IS NOT NULL;
@@ -0,0 +1,104 @@
+use chrono::{DateTime, TimeZone, Utc};
+use uchat_endpoint::Cursor;
+use uuid::Uuid;
+
+/// Keyset position for listings ordered by `(time, id)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeCursor {
+    pub time: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl TimeCursor {
+    pub fn new<I: Into<Uuid>>(time: DateTime<Utc>, id: I) -> Self {
+        Self {
+            time,
+            id: id.into(),
+        }
+    }
+
+    pub fn encode(&self) -> Cursor {
+        Cursor::new(format!(
+            "{}_{}",
+            self.time.timestamp_micros(),
+            self.id.simple()
+        ))
+    }
+
+    pub fn decode(cursor: &Cursor) -> Option<Self> {
+        let (micros, id) = cursor.as_str().split_once('_')?;
+        let micros: i64 = micros.parse().ok()?;
+        let time = Utc
+            .timestamp_opt(
+                micros.div_euclid(1_000_000),
+                (micros.rem_euclid(1_000_000) * 1_000) as u32,
+            )
+            .single()?;
+        let id = Uuid::try_parse(id).ok()?;
+
+        Some(Self { time, id })
+    }
+}
+
+/// Keyset position for listings ordered by `(score, id)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreCursor {
+    pub score: f64,
+    pub id: Uuid,
+}
+
+impl ScoreCursor {
+    pub fn new<I: Into<Uuid>>(score: f64, id: I) -> Self {
+        Self {
+            score,
+            id: id.into(),
+        }
+    }
+
+    pub fn encode(&self) -> Cursor {
+        // `Display` for floats is lossless, so the score survives the roundtrip exactly
+        Cursor::new(format!("{}_{}", self.score, self.id.simple()))
+    }
+
+    pub fn decode(cursor: &Cursor) -> Option<Self> {
+        let (score, id) = cursor.as_str().split_once('_')?;
+        let score: f64 = score.parse().ok().filter(|score: &f64| score.is_finite())?;
+        let id = Uuid::try_parse(id).ok()?;
+
+        Some(Self { score, id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_cursor_roundtrips() {
+        let cursor = TimeCursor::new(
+            Utc.timestamp_opt(1_680_000_000, 123_456_000).unwrap(),
+            Uuid::new_v4(),
+        );
+        let decoded = TimeCursor::decode(&cursor.encode()).expect("failed to decode cursor");
+        assert_eq!(cursor, decoded);
+    }
+
+    #[test]
+    fn rejects_malformed_cursor() {
+        assert!(TimeCursor::decode(&Cursor::new("not a cursor")).is_none());
+    }
+
+    #[test]
+    fn score_cursor_roundtrips() {
+        let cursor = ScoreCursor::new(-0.1 / 3.0, Uuid::new_v4());
+        let decoded = ScoreCursor::decode(&cursor.encode()).expect("failed to decode cursor");
+        assert_eq!(cursor, decoded);
+    }
+
+    #[test]
+    fn rejects_non_finite_score() {
+        let id = Uuid::new_v4().simple();
+        assert!(ScoreCursor::decode(&Cursor::new(format!("NaN_{id}"))).is_none());
+        assert!(ScoreCursor::decode(&Cursor::new(format!("inf_{id}"))).is_none());
+    }
+}
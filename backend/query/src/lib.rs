@@ -14,6 +14,13 @@ pub use error::QueryError;
 pub mod util;
 pub use util::{AsyncConnection, AsyncConnectionPool, OwnedAsyncConnection};
 
+pub mod cursor;
+pub use cursor::{ScoreCursor, TimeCursor};
+
+pub mod notification;
+pub mod password_reset;
 pub mod post;
 pub mod session;
+pub mod two_factor;
 pub mod user;
+pub mod views;
@@ -0,0 +1,343 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use uchat_domain::ids::{PostId, UserId};
+use uchat_endpoint::notification::NotificationKind;
+use uuid::Uuid;
+
+use crate::{DieselError, TimeCursor};
+
+fn kind_to_str(kind: NotificationKind) -> &'static str {
+    match kind {
+        NotificationKind::Follow => "follow",
+        NotificationKind::Like => "like",
+        NotificationKind::Boost => "boost",
+        NotificationKind::Reply => "reply",
+        NotificationKind::Vote => "vote",
+        NotificationKind::DirectMessage => "direct_message",
+    }
+}
+
+fn kind_from_str(kind: &str) -> Option<NotificationKind> {
+    match kind {
+        "follow" => Some(NotificationKind::Follow),
+        "like" => Some(NotificationKind::Like),
+        "boost" => Some(NotificationKind::Boost),
+        "reply" => Some(NotificationKind::Reply),
+        "vote" => Some(NotificationKind::Vote),
+        "direct_message" => Some(NotificationKind::DirectMessage),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NewNotification {
+    /// The user being notified.
+    pub user_id: UserId,
+    /// The user who caused the notification.
+    pub actor: UserId,
+    pub kind: NotificationKind,
+    pub post_id: Option<PostId>,
+    /// Notifications are hidden until this time, so scheduled posts notify once published.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Stores a notification. Users are never notified about their own activity.
+///
+/// Grouped kinds replace an earlier notification from the same actor about the same post, so
+/// liking a post twice only counts once.
+pub fn notify(conn: &mut PgConnection, notification: NewNotification) -> Result<(), DieselError> {
+    use crate::schema::notifications;
+    use diesel::PgExpressionMethods;
+
+    if notification.user_id == notification.actor {
+        return Ok(());
+    }
+
+    let kind = kind_to_str(notification.kind);
+    conn.transaction(|conn| {
+        if notification.kind.is_grouped() {
+            diesel::delete(notifications::table)
+                .filter(notifications::user_id.eq(notification.user_id))
+                .filter(notifications::actor.eq(notification.actor))
+                .filter(notifications::kind.eq(kind))
+                .filter(notifications::post_id.is_not_distinct_from(notification.post_id))
+                .execute(conn)?;
+        }
+
+        diesel::insert_into(notifications::table)
+            .values((
+                notifications::id.eq(Uuid::new_v4()),
+                notifications::user_id.eq(notification.user_id),
+                notifications::actor.eq(notification.actor),
+                notifications::kind.eq(kind),
+                notifications::post_id.eq(notification.post_id),
+                notifications::created_at.eq(notification.created_at),
+            ))
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+#[derive(QueryableByName)]
+struct GroupRow {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    kind: String,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Uuid>)]
+    post_id: Option<PostId>,
+    #[diesel(sql_type = diesel::sql_types::Array<diesel::sql_types::Uuid>)]
+    actors: Vec<Uuid>,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    actor_count: i64,
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    latest: DateTime<Utc>,
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    latest_id: Uuid,
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    unread: bool,
+}
+
+/// Notifications of the same kind about the same post, combined into one entry.
+#[derive(Clone, Debug)]
+pub struct NotificationGroup {
+    pub kind: NotificationKind,
+    pub post_id: Option<PostId>,
+    /// The most recent actors, newest first.
+    pub actors: Vec<UserId>,
+    pub actor_count: i64,
+    pub latest: DateTime<Utc>,
+    /// Id of the most recent notification in the group.
+    pub latest_id: Uuid,
+    pub unread: bool,
+}
+
+impl From<&NotificationGroup> for TimeCursor {
+    fn from(group: &NotificationGroup) -> Self {
+        TimeCursor::new(group.latest, group.latest_id)
+    }
+}
+
+/// Pages through the notifications of `user_id`, most recent first.
+///
+/// Grouped kinds are combined per post, listing up to `max_actors` of the most recent actors.
+/// Notifications about posts which aren't published yet are left out.
+pub fn list(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+    max_actors: i32,
+) -> Result<Vec<NotificationGroup>, DieselError> {
+    use diesel::sql_types::{BigInt, Integer, Nullable, Timestamptz, Uuid as SqlUuid};
+
+    let rows: Vec<GroupRow> = diesel::sql_query(
+        "SELECT * FROM (
+            SELECT
+                kind,
+                post_id,
+                (array_agg(actor ORDER BY created_at DESC))[1:$5] AS actors,
+                count(DISTINCT actor) AS actor_count,
+                max(created_at) AS latest,
+                (array_agg(id ORDER BY created_at DESC, id DESC))[1] AS latest_id,
+                bool_or(read_at IS NULL) AS unread
+            FROM public.notifications
+            WHERE user_id = $1 AND created_at <= $6 AND (
+                post_id IS NULL OR EXISTS (
+                    SELECT 1 FROM public.posts
+                    WHERE posts.id = notifications.post_id AND posts.time_posted <= $6
+                )
+            )
+            GROUP BY kind, post_id, CASE WHEN kind IN ('reply', 'direct_message') THEN id END
+        ) groups
+        WHERE $2::timestamptz IS NULL OR (latest, latest_id) < ($2, $3)
+        ORDER BY latest DESC, latest_id DESC
+        LIMIT $4",
+    )
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<Nullable<Timestamptz>, _>(after.map(|cursor| cursor.time))
+    .bind::<Nullable<SqlUuid>, _>(after.map(|cursor| cursor.id))
+    .bind::<BigInt, _>(limit)
+    .bind::<Integer, _>(max_actors)
+    .bind::<Timestamptz, _>(Utc::now())
+    .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(NotificationGroup {
+                kind: kind_from_str(&row.kind)?,
+                post_id: row.post_id,
+                actors: row.actors.into_iter().map(UserId::from).collect(),
+                actor_count: row.actor_count,
+                latest: row.latest,
+                latest_id: row.latest_id,
+                unread: row.unread,
+            })
+        })
+        .collect())
+}
+
+/// Marks the notifications of `user_id` received up to `until` as read.
+pub fn mark_read(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    until: DateTime<Utc>,
+) -> Result<usize, DieselError> {
+    use crate::schema::notifications;
+
+    diesel::update(notifications::table)
+        .filter(notifications::user_id.eq(user_id))
+        .filter(notifications::read_at.is_null())
+        .filter(notifications::created_at.le(until))
+        .set(notifications::read_at.eq(Utc::now()))
+        .execute(conn)
+}
+
+#[derive(QueryableByName)]
+struct CountRow {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64,
+}
+
+/// Number of published notification groups which `user_id` hasn't read, matching the entries
+/// returned by [`list`].
+pub fn unread_count(conn: &mut PgConnection, user_id: UserId) -> Result<i64, DieselError> {
+    use diesel::sql_types::{Timestamptz, Uuid as SqlUuid};
+
+    diesel::sql_query(
+        "SELECT count(*) AS count FROM (
+            SELECT 1
+            FROM public.notifications
+            WHERE user_id = $1 AND created_at <= $2 AND (
+                post_id IS NULL OR EXISTS (
+                    SELECT 1 FROM public.posts
+                    WHERE posts.id = notifications.post_id AND posts.time_posted <= $2
+                )
+            )
+            GROUP BY kind, post_id, CASE WHEN kind IN ('reply', 'direct_message') THEN id END
+            HAVING bool_or(read_at IS NULL)
+        ) groups",
+    )
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<Timestamptz, _>(Utc::now())
+    .get_result::<CountRow>(conn)
+    .map(|row| row.count)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use uchat_endpoint::notification::NotificationKind;
+    use uchat_endpoint::post::NewPostOptions;
+
+    use crate::post::tests::util as test_post;
+    use crate::post::Post;
+    use crate::test_db::{self, Result};
+    use crate::user::tests::util as test_user;
+
+    use super::NewNotification;
+
+    #[test]
+    fn groups_likes_and_counts_unread() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let fan1 = test_user::new_user(&mut conn, "fan1");
+        let fan2 = test_user::new_user(&mut conn, "fan2");
+        let post = Post::new(
+            author.id,
+            test_post::new_chat("hello"),
+            NewPostOptions::default(),
+        )?;
+        let post_id = crate::post::new(&mut conn, post)?;
+
+        let notify = |conn: &mut diesel::PgConnection, actor, kind, post_id| {
+            super::notify(
+                conn,
+                NewNotification {
+                    user_id: author.id,
+                    actor,
+                    kind,
+                    post_id,
+                    created_at: Utc::now() - Duration::seconds(1),
+                },
+            )
+        };
+        notify(&mut conn, fan1.id, NotificationKind::Like, Some(post_id))?;
+        notify(&mut conn, fan1.id, NotificationKind::Like, Some(post_id))?;
+        notify(&mut conn, fan2.id, NotificationKind::Like, Some(post_id))?;
+        notify(&mut conn, fan2.id, NotificationKind::Follow, None)?;
+        // own activity is ignored
+        notify(&mut conn, author.id, NotificationKind::Like, Some(post_id))?;
+
+        // grouping
+        let groups = super::list(&mut conn, author.id, None, 10, 3)?;
+        assert_eq!(groups.len(), 2);
+        let likes = groups
+            .iter()
+            .find(|group| group.kind == NotificationKind::Like)
+            .expect("missing likes");
+        assert_eq!(likes.actor_count, 2);
+        assert_eq!(likes.actors, vec![fan2.id, fan1.id]);
+        assert_eq!(super::unread_count(&mut conn, author.id)?, 2);
+
+        // read
+        super::mark_read(&mut conn, author.id, Utc::now())?;
+        assert_eq!(super::unread_count(&mut conn, author.id)?, 0);
+        let groups = super::list(&mut conn, author.id, None, 10, 3)?;
+        assert!(groups.iter().all(|group| !group.unread));
+        Ok(())
+    }
+
+    #[test]
+    fn scheduled_replies_notify_once_published() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let replier = test_user::new_user(&mut conn, "replier");
+        let parent = Post::new(
+            author.id,
+            test_post::new_chat("hello"),
+            NewPostOptions::default(),
+        )?;
+        let parent = crate::post::new(&mut conn, parent)?;
+
+        let time_posted = Utc::now() + Duration::hours(1);
+        let options = NewPostOptions {
+            reply_to: Some(parent),
+            time_posted,
+            ..NewPostOptions::default()
+        };
+        let reply = Post::new(replier.id, test_post::new_chat("later"), options)?;
+        let reply = crate::post::new(&mut conn, reply)?;
+        super::notify(
+            &mut conn,
+            NewNotification {
+                user_id: author.id,
+                actor: replier.id,
+                kind: NotificationKind::Reply,
+                post_id: Some(reply),
+                created_at: time_posted,
+            },
+        )?;
+        assert!(super::list(&mut conn, author.id, None, 10, 3)?.is_empty());
+        assert_eq!(super::unread_count(&mut conn, author.id)?, 0);
+
+        // rescheduling moves the notification along with the post
+        let later = time_posted + Duration::days(1);
+        assert!(crate::post::reschedule(
+            &mut conn, replier.id, reply, later
+        )?);
+        let created_at: chrono::DateTime<Utc> = {
+            use crate::schema::notifications;
+            use diesel::prelude::*;
+            notifications::table
+                .filter(notifications::post_id.eq(reply))
+                .select(notifications::created_at)
+                .get_result(&mut conn)?
+        };
+        assert_eq!(created_at.timestamp(), later.timestamp());
+        assert_eq!(super::unread_count(&mut conn, author.id)?, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Tokens which let users who forgot their password choose a new one.
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use password_hash::PasswordHashString;
+use uchat_domain::ids::UserId;
+use uuid::Uuid;
+
+use crate::DieselError;
+
+/// Stores a reset token for `user_id` by its hash, clearing out tokens of the user which can no
+/// longer be used.
+pub fn new(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<(), DieselError> {
+    use crate::schema::password_resets;
+
+    conn.transaction(|conn| {
+        diesel::delete(password_resets::table)
+            .filter(password_resets::user_id.eq(user_id))
+            .filter(
+                password_resets::used_at
+                    .is_not_null()
+                    .or(password_resets::expires_at.le(Utc::now())),
+            )
+            .execute(conn)?;
+
+        diesel::insert_into(password_resets::table)
+            .values((
+                password_resets::id.eq(Uuid::new_v4()),
+                password_resets::user_id.eq(user_id),
+                password_resets::token_hash.eq(token_hash),
+                password_resets::expires_at.eq(expires_at),
+            ))
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+/// Sets a new password using the reset token with `token_hash`, and logs the user out of every
+/// session.
+///
+/// Returns the user whose password was reset, or `None` when the token is unknown, expired or
+/// was already used.
+pub fn reset_password(
+    conn: &mut PgConnection,
+    token_hash: &str,
+    password_hash: PasswordHashString,
+) -> Result<Option<UserId>, DieselError> {
+    use crate::schema::{password_resets, users};
+
+    conn.transaction(|conn| {
+        let user_id: Option<UserId> = diesel::update(password_resets::table)
+            .filter(password_resets::token_hash.eq(token_hash))
+            .filter(password_resets::used_at.is_null())
+            .filter(password_resets::expires_at.gt(Utc::now()))
+            .set(password_resets::used_at.eq(Utc::now()))
+            .returning(password_resets::user_id)
+            .get_result(conn)
+            .optional()?;
+        let Some(user_id) = user_id else {
+            return Ok(None);
+        };
+
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .set(users::password_hash.eq(password_hash.as_str()))
+            .execute(conn)?;
+
+        // other links which were sent to the user can't be used anymore either
+        diesel::delete(password_resets::table)
+            .filter(password_resets::user_id.eq(user_id))
+            .filter(password_resets::used_at.is_null())
+            .execute(conn)?;
+
+        crate::session::delete_all(conn, user_id)?;
+
+        Ok(Some(user_id))
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::test_db::{self, Result};
+    use crate::user::tests::util as test_user;
+
+    #[test]
+    fn reset_tokens_are_single_use_and_log_out_sessions() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "forgetful");
+        let fingerprint = serde_json::json!({ "user_agent": "laptop" });
+        let session =
+            crate::session::new(&mut conn, user.id, Duration::days(1), fingerprint.into())?;
+        let new_hash = || uchat_crypto::hash_password("new password").unwrap();
+
+        super::new(&mut conn, user.id, "valid", Utc::now() + Duration::hours(1))?;
+        super::new(
+            &mut conn,
+            user.id,
+            "expired",
+            Utc::now() - Duration::seconds(1),
+        )?;
+
+        // unknown and expired tokens are rejected
+        assert_eq!(
+            super::reset_password(&mut conn, "unknown", new_hash())?,
+            None
+        );
+        assert_eq!(
+            super::reset_password(&mut conn, "expired", new_hash())?,
+            None
+        );
+
+        // reset
+        assert_eq!(
+            super::reset_password(&mut conn, "valid", new_hash())?,
+            Some(user.id)
+        );
+        let updated = crate::user::get(&mut conn, user.id)?;
+        assert_ne!(updated.password_hash, user.password_hash);
+        assert!(crate::session::get(&mut conn, session.id)?.is_none());
+
+        // tokens only work once
+        assert_eq!(super::reset_password(&mut conn, "valid", new_hash())?, None);
+        Ok(())
+    }
+}
@@ -1,16 +1,18 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uchat_domain::ids::{PollChoiceId, PostId, UserId};
-use uchat_endpoint::post::VoteCast;
+use uchat_endpoint::post::{SnippetFragment, VoteCast};
 use uuid::Uuid;
 
-use crate::{schema, DieselError};
+use crate::{schema, user::UserInfo, DieselError, ScoreCursor, TimeCursor};
 
 #[derive(Clone, Debug, DieselNewType, Serialize, Deserialize)]
 pub struct Content(pub serde_json::Value);
 
-#[derive(Debug, Queryable, Selectable, Insertable)]
+#[derive(Clone, Debug, Queryable, QueryableByName, Selectable, Insertable)]
 #[diesel(table_name = schema::posts)]
 pub struct Post {
     pub id: PostId,
@@ -20,6 +22,7 @@ pub struct Post {
     pub direct_message_to: Option<UserId>,
     pub reply_to: Option<PostId>,
     pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
 }
 
 impl Post {
@@ -36,10 +39,17 @@ impl Post {
             direct_message_to: options.direct_message_to,
             reply_to: options.reply_to,
             created_at: Utc::now(),
+            edited_at: None,
         })
     }
 }
 
+impl From<&Post> for TimeCursor {
+    fn from(post: &Post) -> Self {
+        TimeCursor::new(post.time_posted, post.id.into_inner())
+    }
+}
+
 pub fn new(conn: &mut PgConnection, post: Post) -> Result<PostId, DieselError> {
     conn.transaction::<PostId, DieselError, _>(|conn| {
         use uchat_endpoint::post::Content as EndpointContent;
@@ -48,8 +58,8 @@ pub fn new(conn: &mut PgConnection, post: Post) -> Result<PostId, DieselError> {
             .values(&post)
             .execute(conn)?;
 
-        match serde_json::from_value::<EndpointContent>(post.content.0) {
-            Ok(EndpointContent::Poll(poll)) => {
+        if let Ok(content) = serde_json::from_value::<EndpointContent>(post.content.0) {
+            if let EndpointContent::Poll(poll) = &content {
                 for choice in &poll.choices {
                     use schema::poll_choices::{self, columns as col};
 
@@ -62,25 +72,395 @@ pub fn new(conn: &mut PgConnection, post: Post) -> Result<PostId, DieselError> {
                         .execute(conn)?;
                 }
             }
-            _ => (),
+            save_mentions(conn, post.id, post.user_id, &content)?;
+            save_hashtags(conn, post.id, &content)?;
         }
         Ok(post.id)
     })
 }
 
+/// Replaces the users mentioned by a post with the `@handle`s found in `content`.
+///
+/// Handles which don't belong to anyone are ignored, as are authors mentioning themselves.
+fn save_mentions(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    author: UserId,
+    content: &uchat_endpoint::post::Content,
+) -> Result<(), DieselError> {
+    use schema::{post_mentions, users};
+    use uchat_endpoint::post::Content as EndpointContent;
+
+    diesel::delete(post_mentions::table.filter(post_mentions::post_id.eq(post_id)))
+        .execute(conn)?;
+
+    let handles = match content {
+        EndpointContent::Chat(chat) => chat.message.mentions(),
+        _ => return Ok(()),
+    };
+    if handles.is_empty() {
+        return Ok(());
+    }
+
+    let mentioned: Vec<UserId> = users::table
+        .filter(users::handle.eq_any(handles))
+        .filter(users::id.ne(author))
+        .select(users::id)
+        .load(conn)?;
+    let rows: Vec<_> = mentioned
+        .into_iter()
+        .map(|user_id| {
+            (
+                post_mentions::post_id.eq(post_id),
+                post_mentions::user_id.eq(user_id),
+            )
+        })
+        .collect();
+
+    diesel::insert_into(post_mentions::table)
+        .values(&rows)
+        .execute(conn)
+        .map(|_| ())
+}
+
+/// Replaces the hashtags indexed for a post with those used in `content`.
+fn save_hashtags(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    content: &uchat_endpoint::post::Content,
+) -> Result<(), DieselError> {
+    use schema::{hashtags, post_hashtags};
+
+    diesel::delete(post_hashtags::table.filter(post_hashtags::post_id.eq(post_id)))
+        .execute(conn)?;
+
+    let tags = content.hashtags();
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let new_tags: Vec<_> = tags
+        .iter()
+        .map(|tag| (hashtags::id.eq(Uuid::new_v4()), hashtags::tag.eq(tag)))
+        .collect();
+    diesel::insert_into(hashtags::table)
+        .values(&new_tags)
+        .on_conflict(hashtags::tag)
+        .do_nothing()
+        .execute(conn)?;
+
+    let hashtag_ids: Vec<Uuid> = hashtags::table
+        .filter(hashtags::tag.eq_any(&tags))
+        .select(hashtags::id)
+        .load(conn)?;
+    let rows: Vec<_> = hashtag_ids
+        .into_iter()
+        .map(|hashtag_id| {
+            (
+                post_hashtags::post_id.eq(post_id),
+                post_hashtags::hashtag_id.eq(hashtag_id),
+            )
+        })
+        .collect();
+
+    diesel::insert_into(post_hashtags::table)
+        .values(&rows)
+        .execute(conn)
+        .map(|_| ())
+}
+
 pub fn get(conn: &mut PgConnection, post_id: PostId) -> Result<Post, DieselError> {
     use crate::schema::posts::dsl::*;
     posts.filter(id.eq(post_id)).get_result(conn)
 }
 
-pub fn get_trending(conn: &mut PgConnection) -> Result<Vec<Post>, DieselError> {
-    use crate::schema::posts::dsl::*;
-    posts
-        .filter(time_posted.lt(Utc::now()))
-        .filter(direct_message_to.is_null())
-        .order(time_posted.desc())
-        .limit(30)
-        .get_results(conn)
+/// A previous version of an edited post.
+#[derive(Clone, Debug, Queryable, Selectable, Insertable)]
+#[diesel(table_name = schema::post_revisions)]
+pub struct PostRevision {
+    pub id: Uuid,
+    pub post_id: PostId,
+    pub content: Content,
+    pub revised_at: DateTime<Utc>,
+}
+
+/// Outcome of [`edit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditStatus {
+    Edited,
+    /// Nothing was changed, because the poll choices differ from the stored ones and votes were
+    /// already cast for them.
+    PollHasVotes,
+}
+
+/// Replaces the content of a post, keeping the previous content as a revision.
+///
+/// Mentions and hashtags are updated to match the new content. Poll choices are updated in place
+/// when they differ from the stored ones, which is only allowed while nobody has voted.
+pub fn edit(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    content: uchat_endpoint::post::Content,
+    edited_at: DateTime<Utc>,
+) -> Result<EditStatus, DieselError> {
+    conn.transaction::<_, DieselError, _>(|conn| {
+        use uchat_endpoint::post::Content as EndpointContent;
+
+        // locked so that no vote can be cast between checking for votes and changing the choices
+        let previous: Post = schema::posts::table
+            .filter(schema::posts::id.eq(post_id))
+            .for_update()
+            .get_result(conn)?;
+
+        if let EndpointContent::Poll(ref poll) = content {
+            use schema::poll_choices::{self, columns as col};
+
+            let current: Vec<(PollChoiceId, String)> = poll_choices::table
+                .filter(col::post_id.eq(post_id))
+                .select((col::id, col::choice))
+                .order(col::id)
+                .load(conn)?;
+            let mut updated: Vec<(PollChoiceId, String)> = poll
+                .choices
+                .iter()
+                .map(|choice| (choice.id, choice.description.as_ref().to_string()))
+                .collect();
+            updated.sort();
+
+            if current != updated {
+                if has_votes(conn, post_id)? {
+                    return Ok(EditStatus::PollHasVotes);
+                }
+
+                let updated_ids: Vec<_> = updated.iter().map(|(id, _)| *id).collect();
+                diesel::delete(
+                    poll_choices::table
+                        .filter(col::post_id.eq(post_id))
+                        .filter(diesel::dsl::not(col::id.eq_any(&updated_ids))),
+                )
+                .execute(conn)?;
+                for (id, choice) in updated {
+                    if current.iter().any(|(current_id, _)| *current_id == id) {
+                        diesel::update(
+                            poll_choices::table
+                                .filter(col::id.eq(id))
+                                .filter(col::post_id.eq(post_id)),
+                        )
+                        .set(col::choice.eq(choice))
+                        .execute(conn)?;
+                    } else {
+                        diesel::insert_into(poll_choices::table)
+                            .values((
+                                col::id.eq(id),
+                                col::choice.eq(choice),
+                                col::post_id.eq(post_id),
+                            ))
+                            .execute(conn)?;
+                    }
+                }
+            }
+        }
+
+        diesel::insert_into(schema::post_revisions::table)
+            .values(&PostRevision {
+                id: Uuid::new_v4(),
+                post_id,
+                content: previous.content,
+                revised_at: edited_at,
+            })
+            .execute(conn)?;
+
+        let updated_content = serde_json::to_value(&content)
+            .map_err(|e| DieselError::SerializationError(Box::new(e)))?;
+        {
+            use schema::posts::{self, columns as col};
+
+            diesel::update(posts::table.filter(col::id.eq(post_id)))
+                .set((
+                    col::content.eq(Content(updated_content)),
+                    col::edited_at.eq(Some(edited_at)),
+                ))
+                .execute(conn)?;
+        }
+
+        save_mentions(conn, post_id, previous.user_id, &content)?;
+        save_hashtags(conn, post_id, &content)?;
+
+        Ok(EditStatus::Edited)
+    })
+}
+
+/// Returns the previous versions of a post, newest first.
+pub fn get_revisions(
+    conn: &mut PgConnection,
+    post_id: PostId,
+) -> Result<Vec<PostRevision>, DieselError> {
+    let pid = post_id;
+    {
+        use crate::schema::post_revisions::dsl::*;
+        post_revisions
+            .filter(post_id.eq(pid))
+            .order((revised_at.desc(), id.desc()))
+            .select(PostRevision::as_select())
+            .load(conn)
+    }
+}
+
+#[derive(QueryableByName)]
+struct ContentRow {
+    #[diesel(sql_type = diesel::sql_types::Jsonb)]
+    content: Content,
+}
+
+/// Deletes a post along with all of its replies.
+///
+/// Returns the content of every deleted post, and of their revisions, so that any stored files can
+/// be cleaned up.
+pub fn delete(conn: &mut PgConnection, post_id: PostId) -> Result<Vec<Content>, DieselError> {
+    conn.transaction::<_, DieselError, _>(|conn| {
+        // replies and revisions are removed by foreign keys, so their content has to be collected
+        // beforehand
+        let rows: Vec<ContentRow> = diesel::sql_query(
+            "WITH RECURSIVE thread AS (
+                SELECT id, content FROM public.posts WHERE id = $1
+                UNION ALL
+                SELECT posts.id, posts.content
+                FROM public.posts
+                JOIN thread ON posts.reply_to = thread.id
+            )
+            SELECT content FROM thread
+            UNION ALL
+            SELECT post_revisions.content
+            FROM public.post_revisions
+            JOIN thread ON post_revisions.post_id = thread.id",
+        )
+        .bind::<diesel::sql_types::Uuid, _>(post_id)
+        .load(conn)?;
+
+        diesel::delete(schema::posts::table.filter(schema::posts::id.eq(post_id))).execute(conn)?;
+
+        Ok(rows.into_iter().map(|row| row.content).collect())
+    })
+}
+
+/// Filters posts to those strictly older than `$cursor` in `(time_posted, id)` order.
+macro_rules! posted_before {
+    ($cursor:expr) => {{
+        let cursor: $crate::TimeCursor = $cursor;
+        $crate::schema::posts::time_posted
+            .lt(cursor.time)
+            .or($crate::schema::posts::time_posted
+                .eq(cursor.time)
+                .and($crate::schema::posts::id.lt(cursor.id)))
+    }};
+}
+
+/// Filters out posts by users who blocked `$viewer`, or who `$viewer` blocked.
+macro_rules! not_blocked_with {
+    ($viewer:expr) => {{
+        use $crate::schema::{blocks, posts};
+        let viewer: UserId = $viewer;
+        diesel::dsl::not(
+            posts::user_id
+                .eq_any(
+                    blocks::table
+                        .filter(blocks::user_id.eq(viewer))
+                        .select(blocks::blocked),
+                )
+                .or(posts::user_id.eq_any(
+                    blocks::table
+                        .filter(blocks::blocked.eq(viewer))
+                        .select(blocks::user_id),
+                )),
+        )
+    }};
+}
+
+/// Ids of the users which `$viewer` currently mutes.
+macro_rules! muted_users_of {
+    ($viewer:expr) => {{
+        use $crate::schema::mutes;
+        let viewer: UserId = $viewer;
+        mutes::table
+            .filter(mutes::user_id.eq(viewer))
+            // thread mutes are stored in the same table without a muted user, and a NULL in the
+            // list would make every `NOT IN` check using it come out NULL
+            .filter(mutes::muted_user.is_not_null())
+            .filter(
+                mutes::expires_at
+                    .is_null()
+                    .or(mutes::expires_at.gt(Utc::now())),
+            )
+            .select(mutes::muted_user.assume_not_null())
+    }};
+}
+
+/// Filters out replies beneath posts whose thread `$viewer` currently mutes.
+macro_rules! not_in_muted_thread {
+    ($viewer:expr) => {{
+        let viewer: UserId = $viewer;
+        diesel::dsl::sql::<diesel::sql_types::Bool>(
+            "posts.id NOT IN (
+                WITH RECURSIVE muted_replies AS (
+                    SELECT posts.id
+                    FROM public.posts
+                    JOIN public.mutes ON posts.reply_to = mutes.muted_post
+                    WHERE mutes.user_id = ",
+        )
+        .bind::<diesel::sql_types::Uuid, _>(viewer)
+        .sql(
+            "
+                        AND (mutes.expires_at IS NULL OR mutes.expires_at > now())
+                    UNION ALL
+                    SELECT posts.id
+                    FROM muted_replies
+                    JOIN public.posts ON posts.reply_to = muted_replies.id
+                )
+                SELECT id FROM muted_replies
+            )",
+        )
+    }};
+}
+
+/// Returns the highest scoring public posts made after `since`, along with their scores.
+///
+/// Scores are read from the `trending_scores` materialized view, so they are only as fresh as the
+/// last [`refresh_trending`](crate::views::refresh_trending). Posts by users blocked either way
+/// with `viewer`, posts by users `viewer` mutes, and replies in threads `viewer` mutes are
+/// excluded.
+pub fn get_trending(
+    conn: &mut PgConnection,
+    viewer: UserId,
+    since: DateTime<Utc>,
+    after: Option<ScoreCursor>,
+    limit: i64,
+) -> Result<Vec<(Post, f64)>, DieselError> {
+    use crate::schema::posts;
+    use crate::views::trending_scores::dsl::*;
+
+    let mut query = trending_scores
+        .inner_join(posts::table)
+        .filter(time_posted.gt(since))
+        .filter(not_blocked_with!(viewer))
+        .filter(diesel::dsl::not(
+            posts::user_id.eq_any(muted_users_of!(viewer)),
+        ))
+        .filter(not_in_muted_thread!(viewer))
+        .select((Post::as_select(), score))
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(
+            score
+                .lt(cursor.score)
+                .or(score.eq(cursor.score).and(post_id.lt(cursor.id))),
+        );
+    }
+
+    query
+        .order((score.desc(), post_id.desc()))
+        .limit(limit)
+        .load(conn)
 }
 
 pub fn bookmark(
@@ -156,6 +536,17 @@ pub fn get_bookmark(
 #[derive(Clone, Debug, DieselNewType, Deserialize, Serialize)]
 pub struct ReactionData(serde_json::Value);
 
+impl ReactionData {
+    pub fn emoji<T: Into<String>>(emoji: T) -> Self {
+        Self(serde_json::Value::String(emoji.into()))
+    }
+
+    /// The emoji stored in this reaction, if it holds one.
+    pub fn as_emoji(&self) -> Option<&str> {
+        self.0.as_str()
+    }
+}
+
 #[derive(Clone, Debug, Queryable, Insertable, Deserialize, Serialize)]
 #[diesel(table_name = schema::reactions)]
 pub struct Reaction {
@@ -166,6 +557,10 @@ pub struct Reaction {
     pub reaction: Option<ReactionData>,
 }
 
+/// Sets the like status of a reaction, leaving any emoji reaction in place.
+///
+/// `created_at` is updated along with the like status, so liked posts are listed by when they
+/// were liked.
 pub fn react(conn: &mut PgConnection, reaction: Reaction) -> Result<(), DieselError> {
     let reaction0 = reaction;
 
@@ -178,13 +573,41 @@ pub fn react(conn: &mut PgConnection, reaction: Reaction) -> Result<(), DieselEr
             .do_update()
             .set((
                 like_status.eq(&reaction0.like_status),
-                reaction.eq(&reaction0.reaction),
+                created_at.eq(&reaction0.created_at),
             ))
             .execute(conn)
             .map(|_| ())
     }
 }
 
+/// Sets (or clears, with `None`) the emoji reaction of a user, leaving the like status in place.
+pub fn react_with_emoji(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    post_id: PostId,
+    emoji: Option<ReactionData>,
+) -> Result<(), DieselError> {
+    let new_reaction = Reaction {
+        user_id,
+        post_id,
+        created_at: Utc::now(),
+        like_status: 0,
+        reaction: emoji,
+    };
+
+    {
+        use crate::schema::reactions::dsl::*;
+
+        diesel::insert_into(reactions)
+            .values(&new_reaction)
+            .on_conflict((user_id, post_id))
+            .do_update()
+            .set(reaction.eq(&new_reaction.reaction))
+            .execute(conn)
+            .map(|_| ())
+    }
+}
+
 pub fn get_reaction(
     conn: &mut PgConnection,
     post_id: PostId,
@@ -208,6 +631,21 @@ pub struct AggregatePostInfo {
     pub likes: i64,
     pub dislikes: i64,
     pub boosts: i64,
+    /// Number of reactions per emoji, most used first.
+    pub emojis: Vec<(String, i64)>,
+}
+
+/// Orders per-emoji totals by count (highest first), then by emoji.
+fn emoji_totals<I>(counts: I) -> Vec<(String, i64)>
+where
+    I: IntoIterator<Item = (Option<ReactionData>, i64)>,
+{
+    let mut totals: Vec<(String, i64)> = counts
+        .into_iter()
+        .filter_map(|(data, n)| Some((data?.as_emoji()?.to_string(), n)))
+        .collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
 }
 
 pub fn aggregate_reactions(
@@ -234,6 +672,18 @@ pub fn aggregate_reactions(
         (likes, dislikes)
     };
 
+    let emojis = {
+        use crate::schema::reactions::dsl::*;
+
+        let counts = reactions
+            .filter(post_id.eq(pid))
+            .filter(reaction.is_not_null())
+            .group_by(reaction)
+            .select((reaction, diesel::dsl::count_star()))
+            .load::<(Option<ReactionData>, i64)>(conn)?;
+        emoji_totals(counts)
+    };
+
     let boosts = {
         use crate::schema::boosts::dsl::*;
 
@@ -245,6 +695,7 @@ pub fn aggregate_reactions(
         likes,
         dislikes,
         boosts,
+        emojis,
     })
 }
 
@@ -314,37 +765,55 @@ pub fn get_boost(
     }
 }
 
+/// Replaces the votes of `user_id` with `choices`. Voting for no choices retracts the vote.
 pub fn vote(
     conn: &mut PgConnection,
     user_id: UserId,
     post_id: PostId,
-    choice_id: PollChoiceId,
+    choices: &BTreeSet<PollChoiceId>,
 ) -> Result<VoteCast, DieselError> {
+    use uchat_endpoint::post::Content as EndpointContent;
+
     let uid = user_id;
     let pid = post_id;
-    let cid = choice_id;
-    {
+    conn.transaction::<_, DieselError, _>(|conn| {
         use crate::schema::poll_votes::dsl::*;
-        diesel::insert_into(poll_votes)
-            .values((user_id.eq(uid), post_id.eq(pid), choice_id.eq(cid)))
-            .on_conflict((user_id, post_id))
-            .do_nothing()
-            .execute(conn)
-            .map(|n| {
-                if n == 1 {
-                    VoteCast::Yes
-                } else {
-                    VoteCast::AlreadyVoted
-                }
-            })
-    }
+
+        // locked so that the poll choices can't be edited while voting
+        let post: Post = schema::posts::table
+            .filter(schema::posts::id.eq(pid))
+            .for_share()
+            .get_result(conn)?;
+        if let Ok(EndpointContent::Poll(poll)) = serde_json::from_value(post.content.0) {
+            if poll.is_closed_at(Utc::now()) {
+                return Ok(VoteCast::Closed);
+            }
+        }
+
+        diesel::delete(poll_votes.filter(user_id.eq(uid)).filter(post_id.eq(pid))).execute(conn)?;
+
+        let votes: Vec<_> = choices
+            .iter()
+            .map(|&cid| (user_id.eq(uid), post_id.eq(pid), choice_id.eq(cid)))
+            .collect();
+        if !votes.is_empty() {
+            diesel::insert_into(poll_votes)
+                .values(&votes)
+                .execute(conn)?;
+        }
+
+        Ok(match choices.is_empty() {
+            true => VoteCast::Retracted,
+            false => VoteCast::Yes,
+        })
+    })
 }
 
 pub fn did_vote(
     conn: &mut PgConnection,
     user_id: UserId,
     post_id: PostId,
-) -> Result<Option<PollChoiceId>, DieselError> {
+) -> Result<BTreeSet<PollChoiceId>, DieselError> {
     let uid = user_id;
     let pid = post_id;
     {
@@ -353,8 +822,17 @@ pub fn did_vote(
             .filter(user_id.eq(uid))
             .filter(post_id.eq(pid))
             .select(choice_id)
-            .get_result(conn)
-            .optional()
+            .load(conn)
+            .map(|ids| ids.into_iter().collect())
+    }
+}
+
+/// Whether anyone voted on the poll in `post_id`.
+pub fn has_votes(conn: &mut PgConnection, post_id: PostId) -> Result<bool, DieselError> {
+    let pid = post_id;
+    {
+        use crate::schema::poll_votes::dsl::*;
+        diesel::select(diesel::dsl::exists(poll_votes.filter(post_id.eq(pid)))).get_result(conn)
     }
 }
 
@@ -384,112 +862,1002 @@ pub fn get_poll_results(
     }
 }
 
-pub fn get_home_posts(conn: &mut PgConnection, user_id: UserId) -> Result<Vec<Post>, DieselError> {
+/// Pages through the posts made or boosted by users `user_id` follows, newest first.
+///
+/// Posts and boosts by muted users, replies in muted threads, and posts by users blocked either
+/// way are left out.
+pub fn get_home_posts(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Post>, DieselError> {
     use crate::schema::{boosts, followers, posts};
 
-    let on_schedule = posts::time_posted.lt(Utc::now());
-    let public_only = posts::direct_message_to.is_null();
-    let order = posts::time_posted.desc();
-    let limit = 30;
+    let followed = || {
+        followers::table
+            .filter(followers::user_id.eq(user_id))
+            .select(followers::follows)
+    };
+
+    let boosted_by_followed = boosts::table
+        .filter(boosts::user_id.eq_any(followed()))
+        .filter(diesel::dsl::not(
+            boosts::user_id.eq_any(muted_users_of!(user_id)),
+        ))
+        .select(boosts::post_id);
 
-    followers::table
-        .filter(followers::user_id.eq(user_id))
-        .inner_join(posts::table.on(followers::follows.eq(posts::user_id)))
-        .filter(on_schedule)
-        .filter(public_only)
+    let mut query = posts::table
+        .filter(
+            posts::user_id
+                .eq_any(followed())
+                .or(posts::id.eq_any(boosted_by_followed)),
+        )
+        .filter(posts::time_posted.lt(Utc::now()))
+        .filter(posts::direct_message_to.is_null())
+        .filter(not_blocked_with!(user_id))
+        .filter(diesel::dsl::not(
+            posts::user_id.eq_any(muted_users_of!(user_id)),
+        ))
+        .filter(not_in_muted_thread!(user_id))
         .select(Post::as_select())
-        .order(order)
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(posted_before!(cursor));
+    }
+
+    query
+        .order((posts::time_posted.desc(), posts::id.desc()))
         .limit(limit)
-        .union(
-            followers::table
-                .filter(followers::user_id.eq(user_id))
-                .inner_join(boosts::table.on(boosts::user_id.eq(followers::follows)))
-                .inner_join(posts::table.on(posts::id.eq(boosts::post_id)))
-                .filter(on_schedule)
-                .filter(public_only)
-                .select(Post::as_select())
-                .order(order)
-                .limit(limit),
-        )
         .get_results(conn)
 }
 
-pub fn get_liked_posts(conn: &mut PgConnection, user_id: UserId) -> Result<Vec<Post>, DieselError> {
+/// A liked or bookmarked post, along with when it was saved.
+#[derive(Clone, Debug)]
+pub struct SavedPost {
+    pub post: Post,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl From<&SavedPost> for TimeCursor {
+    fn from(saved: &SavedPost) -> Self {
+        TimeCursor::new(saved.saved_at, saved.post.id.into_inner())
+    }
+}
+
+/// Pages through the posts liked by `user_id`, most recently liked first.
+pub fn get_liked_posts(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<SavedPost>, DieselError> {
     use crate::schema::{posts, reactions};
 
-    reactions::table
+    let mut query = reactions::table
         .inner_join(posts::table)
         .filter(reactions::user_id.eq(user_id))
         .filter(reactions::like_status.eq(1))
         .filter(posts::direct_message_to.is_null())
-        .select(Post::as_select())
-        .limit(30)
-        .get_results(conn)
+        .select((Post::as_select(), reactions::created_at))
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(
+            reactions::created_at
+                .lt(cursor.time)
+                .or(reactions::created_at
+                    .eq(cursor.time)
+                    .and(reactions::post_id.lt(cursor.id))),
+        );
+    }
+
+    query
+        .order((reactions::created_at.desc(), reactions::post_id.desc()))
+        .limit(limit)
+        .load::<(Post, DateTime<Utc>)>(conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(post, saved_at)| SavedPost { post, saved_at })
+                .collect()
+        })
 }
 
+/// Pages through the posts bookmarked by `user_id`, most recently bookmarked first.
 pub fn get_bookmarked_posts(
     conn: &mut PgConnection,
     user_id: UserId,
-) -> Result<Vec<Post>, DieselError> {
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<SavedPost>, DieselError> {
     use crate::schema::{bookmarks, posts};
 
-    bookmarks::table
+    let mut query = bookmarks::table
         .inner_join(posts::table)
         .filter(bookmarks::user_id.eq(user_id))
         .filter(posts::direct_message_to.is_null())
+        .select((Post::as_select(), bookmarks::created_at))
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(
+            bookmarks::created_at
+                .lt(cursor.time)
+                .or(bookmarks::created_at
+                    .eq(cursor.time)
+                    .and(bookmarks::post_id.lt(cursor.id))),
+        );
+    }
+
+    query
+        .order((bookmarks::created_at.desc(), bookmarks::post_id.desc()))
+        .limit(limit)
+        .load::<(Post, DateTime<Utc>)>(conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(post, saved_at)| SavedPost { post, saved_at })
+                .collect()
+        })
+}
+
+/// Pages through the posts which mention `user_id`, most recent first.
+///
+/// Direct messages, scheduled posts, and posts by users blocked either way are left out.
+pub fn get_mentioned_posts(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Post>, DieselError> {
+    use crate::schema::{post_mentions, posts};
+
+    let mut query = post_mentions::table
+        .inner_join(posts::table)
+        .filter(post_mentions::user_id.eq(user_id))
+        .filter(posts::time_posted.lt(Utc::now()))
+        .filter(posts::direct_message_to.is_null())
+        .filter(not_blocked_with!(user_id))
         .select(Post::as_select())
-        .limit(30)
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(posted_before!(cursor));
+    }
+
+    query
+        .order((posts::time_posted.desc(), posts::id.desc()))
+        .limit(limit)
+        .get_results(conn)
+}
+
+/// Pages through the posts tagged with `tag`, most recent first.
+///
+/// `tag` is normalized before matching. Direct messages, scheduled posts, and posts by users
+/// blocked either way are left out.
+pub fn get_tag_timeline(
+    conn: &mut PgConnection,
+    viewer: UserId,
+    tag: &str,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Post>, DieselError> {
+    use crate::schema::{hashtags, post_hashtags, posts};
+
+    let mut query = post_hashtags::table
+        .inner_join(hashtags::table)
+        .inner_join(posts::table)
+        .filter(hashtags::tag.eq(uchat_domain::normalize_hashtag(tag)))
+        .filter(posts::time_posted.lt(Utc::now()))
+        .filter(posts::direct_message_to.is_null())
+        .filter(not_blocked_with!(viewer))
+        .select(Post::as_select())
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(posted_before!(cursor));
+    }
+
+    query
+        .order((posts::time_posted.desc(), posts::id.desc()))
+        .limit(limit)
         .get_results(conn)
 }
 
+/// Number of posts using a hashtag.
+#[derive(Clone, Debug, QueryableByName)]
+pub struct TagUsage {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub tag: String,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub posts: i64,
+}
+
+/// Hashtags used by the most posts published since `since`, most used first.
+///
+/// Direct messages, and posts by users blocked either way with or muted by `viewer`, don't count
+/// towards trends.
+pub fn get_trending_tags(
+    conn: &mut PgConnection,
+    viewer: UserId,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<TagUsage>, DieselError> {
+    use diesel::sql_types::{BigInt, Timestamptz, Uuid};
+
+    diesel::sql_query(
+        "SELECT hashtags.tag, count(*) AS posts
+        FROM public.post_hashtags
+        JOIN public.hashtags ON hashtags.id = post_hashtags.hashtag_id
+        JOIN public.posts ON posts.id = post_hashtags.post_id
+        WHERE posts.time_posted > $1
+            AND posts.time_posted <= $4
+            AND posts.direct_message_to IS NULL
+            AND NOT EXISTS (
+                SELECT 1 FROM public.blocks
+                WHERE (blocks.user_id = $3 AND blocks.blocked = posts.user_id)
+                    OR (blocks.user_id = posts.user_id AND blocks.blocked = $3)
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM public.mutes
+                WHERE mutes.user_id = $3
+                    AND mutes.muted_user = posts.user_id
+                    AND (mutes.expires_at IS NULL OR mutes.expires_at > $4)
+            )
+        GROUP BY hashtags.tag
+        ORDER BY count(*) DESC, hashtags.tag
+        LIMIT $2",
+    )
+    .bind::<Timestamptz, _>(since)
+    .bind::<BigInt, _>(limit)
+    .bind::<Uuid, _>(viewer)
+    .bind::<Timestamptz, _>(Utc::now())
+    .load(conn)
+}
+
+/// Pages through the public posts of a single user.
+///
+/// The `user_id` filter together with `(time_posted, id)` ordering is served by
+/// `post_pagination_index`. Nothing is returned when `user_id` and `viewer` are blocked either way.
 pub fn get_public_posts(
     conn: &mut PgConnection,
+    viewer: UserId,
     user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
 ) -> Result<Vec<Post>, DieselError> {
     let uid = user_id;
     {
         use crate::schema::posts::dsl::*;
-        posts
+
+        let mut query = posts
             .filter(user_id.eq(uid.as_uuid()))
             .filter(time_posted.lt(Utc::now()))
             .filter(direct_message_to.is_null())
-            .order(time_posted.desc())
-            .limit(30)
+            .filter(not_blocked_with!(viewer))
+            .into_boxed();
+
+        if let Some(cursor) = after {
+            query = query.filter(posted_before!(cursor));
+        }
+
+        query
+            .order((time_posted.desc(), id.desc()))
+            .limit(limit)
             .get_results(conn)
     }
 }
 
-#[cfg(test)]
-pub mod tests {
-    use crate::test_db::{self, Result};
-    use crate::user::tests::util as test_user;
-
-    use uchat_endpoint::post::NewPostOptions;
-    use util as test_post;
+/// Pages through the posts that `user_id` scheduled for later, soonest first.
+pub fn get_scheduled_posts(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Post>, DieselError> {
+    let uid = user_id;
+    {
+        use crate::schema::posts::dsl::*;
 
-    use super::Post;
-    pub mod util {
-        use uchat_domain::Message;
-        use uchat_endpoint::post::{Chat, Content};
+        let mut query = posts
+            .filter(user_id.eq(uid.as_uuid()))
+            .filter(time_posted.gt(Utc::now()))
+            .into_boxed();
 
-        pub fn new_chat(msg: &str) -> Content {
-            Content::Chat(Chat {
-                headline: None,
-                message: Message::new(msg).unwrap(),
-            })
+        if let Some(cursor) = after {
+            query = query.filter(
+                time_posted
+                    .gt(cursor.time)
+                    .or(time_posted.eq(cursor.time).and(id.gt(cursor.id))),
+            );
         }
+
+        query
+            .order((time_posted.asc(), id.asc()))
+            .limit(limit)
+            .get_results(conn)
     }
+}
 
-    #[test]
-    fn new_and_get() -> Result<()> {
-        // setup
-        let mut conn = test_db::new_connection();
-        let user1 = test_user::new_user(&mut conn, "user 1");
+/// Moves a scheduled post of `user_id` to `time_posted`, along with the notifications it sends.
+///
+/// Returns `false` when there is no such post, or when it was already published.
+pub fn reschedule(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    post_id: PostId,
+    time_posted: DateTime<Utc>,
+) -> Result<bool, DieselError> {
+    use crate::schema::notifications;
+    use crate::schema::posts::{self, columns as col};
 
-        // new post
-        let content = test_post::new_chat("test message");
-        let post = Post::new(user1.id, content, NewPostOptions::default())
-            .expect("failed to create new post structure");
+    conn.transaction(|conn| {
+        let rescheduled = diesel::update(
+            posts::table
+                .filter(col::id.eq(post_id))
+                .filter(col::user_id.eq(user_id))
+                .filter(col::time_posted.gt(Utc::now())),
+        )
+        .set(col::time_posted.eq(time_posted))
+        .execute(conn)?
+            == 1;
+
+        if rescheduled {
+            diesel::update(notifications::table)
+                .filter(notifications::post_id.eq(post_id))
+                .filter(notifications::actor.eq(user_id))
+                .set(notifications::created_at.eq(time_posted))
+                .execute(conn)?;
+        }
+        Ok(rescheduled)
+    })
+}
+
+#[derive(QueryableByName)]
+struct ConversationRow {
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    with_user: UserId,
+    #[diesel(embed)]
+    post: Post,
+}
+
+/// The latest message exchanged with another user.
+#[derive(Clone, Debug)]
+pub struct ConversationPreview {
+    pub with_user: UserId,
+    pub last_message: Post,
+}
+
+/// Lists the direct message conversations of `user_id`, most recently active first.
+pub fn list_conversations(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<ConversationPreview>, DieselError> {
+    use diesel::sql_types::{BigInt, Nullable, Timestamptz, Uuid};
+
+    let rows: Vec<ConversationRow> = diesel::sql_query(
+        "SELECT * FROM (
+            SELECT DISTINCT ON (with_user)
+                CASE WHEN posts.user_id = $1 THEN posts.direct_message_to ELSE posts.user_id END
+                    AS with_user,
+                posts.*
+            FROM public.posts
+            WHERE posts.direct_message_to IS NOT NULL
+                AND (posts.user_id = $1 OR posts.direct_message_to = $1)
+                AND posts.time_posted <= now()
+            ORDER BY with_user, posts.time_posted DESC, posts.id DESC
+        ) latest
+        WHERE $2::timestamptz IS NULL OR (time_posted, id) < ($2, $3)
+        ORDER BY time_posted DESC, id DESC
+        LIMIT $4",
+    )
+    .bind::<Uuid, _>(user_id)
+    .bind::<Nullable<Timestamptz>, _>(after.map(|cursor| cursor.time))
+    .bind::<Nullable<Uuid>, _>(after.map(|cursor| cursor.id))
+    .bind::<BigInt, _>(limit)
+    .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ConversationPreview {
+            with_user: row.with_user,
+            last_message: row.post,
+        })
+        .collect())
+}
+
+/// Pages through the direct messages exchanged between two users, newest first.
+pub fn get_conversation(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    with_user: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Post>, DieselError> {
+    let uid = user_id;
+    {
+        use crate::schema::posts::dsl::*;
+
+        let mut query = posts
+            .filter(
+                user_id
+                    .eq(uid.as_uuid())
+                    .and(direct_message_to.eq(with_user.as_uuid()))
+                    .or(user_id
+                        .eq(with_user.as_uuid())
+                        .and(direct_message_to.eq(uid.as_uuid()))),
+            )
+            .filter(time_posted.le(Utc::now()))
+            .into_boxed();
+
+        if let Some(cursor) = after {
+            query = query.filter(posted_before!(cursor));
+        }
+
+        query
+            .order((time_posted.desc(), id.desc()))
+            .limit(limit)
+            .get_results(conn)
+    }
+}
+
+/// Returns the chain of posts that `post_id` replies to, starting from the thread root.
+///
+/// The post itself is not included, nor are direct messages, posts which aren't published yet, or
+/// posts by users blocked either way with `viewer`.
+pub fn get_thread_ancestors(
+    conn: &mut PgConnection,
+    viewer: UserId,
+    post_id: PostId,
+) -> Result<Vec<Post>, DieselError> {
+    diesel::sql_query(
+        "WITH RECURSIVE ancestors AS (
+            SELECT parent.*, 1 AS depth
+            FROM public.posts child
+            JOIN public.posts parent ON parent.id = child.reply_to
+            WHERE child.id = $1
+            UNION ALL
+            SELECT parent.*, ancestors.depth + 1
+            FROM ancestors
+            JOIN public.posts parent ON parent.id = ancestors.reply_to
+        )
+        SELECT id, user_id, content, time_posted, direct_message_to, reply_to, created_at, edited_at
+        FROM ancestors posts
+        WHERE posts.direct_message_to IS NULL
+            AND posts.time_posted <= now()
+            AND NOT EXISTS (
+                SELECT 1 FROM public.blocks
+                WHERE (blocks.user_id = $2 AND blocks.blocked = posts.user_id)
+                    OR (blocks.user_id = posts.user_id AND blocks.blocked = $2)
+            )
+        ORDER BY depth DESC",
+    )
+    .bind::<diesel::sql_types::Uuid, _>(post_id)
+    .bind::<diesel::sql_types::Uuid, _>(viewer)
+    .load(conn)
+}
+
+#[derive(QueryableByName)]
+struct ReplyRow {
+    #[diesel(embed)]
+    post: Post,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    depth: i32,
+}
+
+/// A reply within a thread. Direct replies to the thread's post have a depth of 1.
+#[derive(Clone, Debug)]
+pub struct ThreadReply {
+    pub post: Post,
+    pub depth: i32,
+}
+
+/// Returns the public replies to `post_id`, oldest first, in depth-first order.
+///
+/// Only the direct replies are paginated; each of them is returned along with its descendants
+/// down to `max_depth`. Use the last reply with a depth of 1 as the cursor for the next page.
+///
+/// Replies by users blocked either way with `viewer`, and replies beneath posts whose thread
+/// `viewer` mutes, are left out along with their descendants.
+pub fn get_replies(
+    conn: &mut PgConnection,
+    viewer: UserId,
+    post_id: PostId,
+    after: Option<TimeCursor>,
+    limit: i64,
+    max_depth: i32,
+) -> Result<Vec<ThreadReply>, DieselError> {
+    use diesel::sql_types::{BigInt, Integer, Nullable, Timestamptz, Uuid};
+
+    let rows: Vec<ReplyRow> = diesel::sql_query(
+        "WITH RECURSIVE direct_replies AS (
+            SELECT posts.*
+            FROM public.posts
+            WHERE posts.reply_to = $1
+                AND posts.direct_message_to IS NULL
+                AND posts.time_posted <= now()
+                AND ($2::timestamptz IS NULL OR (posts.time_posted, posts.id) > ($2, $3))
+                AND NOT EXISTS (
+                    SELECT 1 FROM public.blocks
+                    WHERE (blocks.user_id = $6 AND blocks.blocked = posts.user_id)
+                        OR (blocks.user_id = posts.user_id AND blocks.blocked = $6)
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM public.mutes
+                    WHERE mutes.user_id = $6
+                        AND mutes.muted_post = posts.reply_to
+                        AND (mutes.expires_at IS NULL OR mutes.expires_at > now())
+                )
+            ORDER BY posts.time_posted, posts.id
+            LIMIT $4
+        ), replies AS (
+            SELECT direct_replies.*, 1 AS depth
+            FROM direct_replies
+            UNION ALL
+            SELECT posts.*, replies.depth + 1
+            FROM replies
+            JOIN public.posts ON posts.reply_to = replies.id
+            WHERE replies.depth < $5
+                AND posts.direct_message_to IS NULL
+                AND posts.time_posted <= now()
+                AND NOT EXISTS (
+                    SELECT 1 FROM public.blocks
+                    WHERE (blocks.user_id = $6 AND blocks.blocked = posts.user_id)
+                        OR (blocks.user_id = posts.user_id AND blocks.blocked = $6)
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM public.mutes
+                    WHERE mutes.user_id = $6
+                        AND mutes.muted_post = posts.reply_to
+                        AND (mutes.expires_at IS NULL OR mutes.expires_at > now())
+                )
+        )
+        SELECT id, user_id, content, time_posted, direct_message_to, reply_to, created_at,
+            edited_at, depth
+        FROM replies",
+    )
+    .bind::<Uuid, _>(post_id)
+    .bind::<Nullable<Timestamptz>, _>(after.map(|cursor| cursor.time))
+    .bind::<Nullable<Uuid>, _>(after.map(|cursor| cursor.id))
+    .bind::<BigInt, _>(limit)
+    .bind::<Integer, _>(max_depth)
+    .bind::<Uuid, _>(viewer)
+    .load(conn)?;
+
+    let mut children: HashMap<PostId, Vec<ReplyRow>> = HashMap::new();
+    for row in rows {
+        if let Some(parent) = row.post.reply_to {
+            children.entry(parent).or_default().push(row);
+        }
+    }
+    for siblings in children.values_mut() {
+        // reversed, so the oldest reply is popped first
+        siblings
+            .sort_by_key(|row| std::cmp::Reverse((row.post.time_posted, row.post.id.into_inner())));
+    }
+
+    let mut replies = vec![];
+    let mut stack = vec![post_id];
+    while let Some(parent) = stack.last().copied() {
+        match children
+            .get_mut(&parent)
+            .and_then(|siblings| siblings.pop())
+        {
+            Some(row) => {
+                stack.push(row.post.id);
+                replies.push(ThreadReply {
+                    post: row.post,
+                    depth: row.depth,
+                });
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    Ok(replies)
+}
+
+/// Marks the start of a highlighted match in search snippets.
+const HIGHLIGHT_START: char = '\u{2}';
+/// Marks the end of a highlighted match in search snippets.
+const HIGHLIGHT_STOP: char = '\u{3}';
+/// `ts_headline` options controlling the size of search snippets.
+const SNIPPET_OPTIONS: &str = "MaxFragments=2, MaxWords=20, MinWords=8";
+
+#[derive(QueryableByName)]
+struct SearchRow {
+    #[diesel(embed)]
+    post: Post,
+    #[diesel(sql_type = diesel::sql_types::Float8)]
+    rank: f64,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    snippet: String,
+}
+
+/// A post matching a search query.
+#[derive(Clone, Debug)]
+pub struct SearchMatch {
+    pub post: Post,
+    pub rank: f64,
+    pub snippet: Vec<SnippetFragment>,
+}
+
+/// Full-text search over public posts, best matches first.
+///
+/// `query` uses web search syntax (`"quoted phrases"`, `or`, `-excluded`). Matching uses the
+/// `post_search_index` expression index.
+pub fn search(
+    conn: &mut PgConnection,
+    query: &str,
+    after: Option<ScoreCursor>,
+    limit: i64,
+) -> Result<Vec<SearchMatch>, DieselError> {
+    use diesel::sql_types::{BigInt, Float8, Nullable, Text, Timestamptz};
+
+    let rows: Vec<SearchRow> = diesel::sql_query(
+        "WITH matches AS (
+            SELECT posts.*,
+                ts_rank(public.post_search_vector(posts.content), query)::float8 AS rank,
+                query
+            FROM public.posts, websearch_to_tsquery('english', $1) AS query
+            WHERE public.post_search_vector(posts.content) @@ query
+                AND posts.direct_message_to IS NULL
+                AND posts.time_posted <= $6
+        )
+        SELECT id, user_id, content, time_posted, direct_message_to, reply_to, created_at,
+            edited_at, rank,
+            ts_headline('english', public.post_search_text(content), query, $5) AS snippet
+        FROM matches
+        WHERE $2::float8 IS NULL OR (rank, id) < ($2, $3)
+        ORDER BY rank DESC, id DESC
+        LIMIT $4",
+    )
+    .bind::<Text, _>(query)
+    .bind::<Nullable<Float8>, _>(after.map(|cursor| cursor.score))
+    .bind::<Nullable<diesel::sql_types::Uuid>, _>(after.map(|cursor| cursor.id))
+    .bind::<BigInt, _>(limit)
+    .bind::<Text, _>(format!(
+        "StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_STOP}, {SNIPPET_OPTIONS}"
+    ))
+    .bind::<Timestamptz, _>(Utc::now())
+    .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SearchMatch {
+            post: row.post,
+            rank: row.rank,
+            snippet: parse_snippet(&row.snippet),
+        })
+        .collect())
+}
+
+/// Splits a `ts_headline` snippet into plain and highlighted fragments.
+fn parse_snippet(snippet: &str) -> Vec<SnippetFragment> {
+    let mut fragments = vec![];
+    let mut text = String::new();
+    let mut highlighted = false;
+
+    for ch in snippet.chars() {
+        match ch {
+            HIGHLIGHT_START | HIGHLIGHT_STOP => {
+                if !text.is_empty() {
+                    fragments.push(SnippetFragment {
+                        text: std::mem::take(&mut text),
+                        highlighted,
+                    });
+                }
+                highlighted = ch == HIGHLIGHT_START;
+            }
+            _ => text.push(ch),
+        }
+    }
+    if !text.is_empty() {
+        fragments.push(SnippetFragment { text, highlighted });
+    }
+
+    fragments
+}
+
+/// The post being replied to, along with its author.
+#[derive(Clone, Debug)]
+pub struct ReplyTo {
+    pub post_id: PostId,
+    pub user_id: UserId,
+    pub handle: String,
+}
+
+/// A user mentioned in a post.
+#[derive(Clone, Debug)]
+pub struct Mention {
+    pub user_id: UserId,
+    pub handle: String,
+}
+
+/// A post along with its author, aggregates, and the viewer's interactions with it.
+#[derive(Clone, Debug)]
+pub struct PostInfo {
+    pub post: Post,
+    pub author: UserInfo,
+    pub aggregates: AggregatePostInfo,
+    pub reply_to: Option<ReplyTo>,
+    /// Mentioned users, ordered by handle.
+    pub mentions: Vec<Mention>,
+    pub poll_results: Vec<(PollChoiceId, i64)>,
+    pub like_status: i16,
+    pub emoji: Option<String>,
+    pub bookmarked: bool,
+    pub boosted: bool,
+    pub voted: BTreeSet<PollChoiceId>,
+}
+
+/// Whether `post` is a poll whose vote counts `viewer` may not see yet.
+fn poll_results_hidden(post: &Post, viewer: Option<UserId>) -> bool {
+    use uchat_endpoint::post::Content as EndpointContent;
+
+    match serde_json::from_value(post.content.0.clone()) {
+        Ok(EndpointContent::Poll(poll)) => {
+            poll.results_hidden(viewer == Some(post.user_id), Utc::now())
+        }
+        _ => false,
+    }
+}
+
+/// Loads everything needed to display `posts` in a fixed number of queries, regardless of how
+/// many posts there are.
+///
+/// Viewer-specific fields are left at their defaults when there is no `viewer`. The returned infos
+/// are in the same order as `posts`.
+pub fn hydrate(
+    conn: &mut PgConnection,
+    posts: Vec<Post>,
+    viewer: Option<UserId>,
+) -> Result<Vec<PostInfo>, DieselError> {
+    use crate::schema::{bookmarks, boosts, poll_votes, post_mentions, reactions};
+    use diesel::dsl::count_star;
+
+    if posts.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let post_ids: Vec<PostId> = posts.iter().map(|post| post.id).collect();
+
+    // original posts of replies: post id -> author id
+    let reply_ids: Vec<PostId> = posts.iter().filter_map(|post| post.reply_to).collect();
+    let reply_authors: HashMap<PostId, UserId> = if reply_ids.is_empty() {
+        HashMap::new()
+    } else {
+        use crate::schema::posts;
+        posts::table
+            .filter(posts::id.eq_any(&reply_ids))
+            .select((posts::id, posts::user_id))
+            .load::<(PostId, UserId)>(conn)?
+            .into_iter()
+            .collect()
+    };
+
+    let mut user_ids: Vec<UserId> = posts
+        .iter()
+        .map(|post| post.user_id)
+        .chain(reply_authors.values().copied())
+        .collect();
+    user_ids.sort();
+    user_ids.dedup();
+    let users = crate::user::hydrate_ids(conn, &user_ids, viewer)?;
+
+    let reaction_counts: HashMap<(PostId, i16), i64> = reactions::table
+        .filter(reactions::post_id.eq_any(&post_ids))
+        .group_by((reactions::post_id, reactions::like_status))
+        .select((reactions::post_id, reactions::like_status, count_star()))
+        .load::<(PostId, i16, i64)>(conn)?
+        .into_iter()
+        .map(|(pid, status, n)| ((pid, status), n))
+        .collect();
+
+    let mut emoji_counts: HashMap<PostId, Vec<(Option<ReactionData>, i64)>> = HashMap::new();
+    for (pid, data, n) in reactions::table
+        .filter(reactions::post_id.eq_any(&post_ids))
+        .filter(reactions::reaction.is_not_null())
+        .group_by((reactions::post_id, reactions::reaction))
+        .select((reactions::post_id, reactions::reaction, count_star()))
+        .load::<(PostId, Option<ReactionData>, i64)>(conn)?
+    {
+        emoji_counts.entry(pid).or_default().push((data, n));
+    }
+
+    let mut mentions: HashMap<PostId, Vec<Mention>> = HashMap::new();
+    for (pid, user_id, handle) in post_mentions::table
+        .inner_join(schema::users::table)
+        .filter(post_mentions::post_id.eq_any(&post_ids))
+        .select((
+            post_mentions::post_id,
+            schema::users::id,
+            schema::users::handle,
+        ))
+        .order(schema::users::handle)
+        .load::<(PostId, UserId, String)>(conn)?
+    {
+        mentions
+            .entry(pid)
+            .or_default()
+            .push(Mention { user_id, handle });
+    }
+
+    let boost_counts: HashMap<PostId, i64> = boosts::table
+        .filter(boosts::post_id.eq_any(&post_ids))
+        .group_by(boosts::post_id)
+        .select((boosts::post_id, count_star()))
+        .load::<(PostId, i64)>(conn)?
+        .into_iter()
+        .collect();
+
+    let mut poll_results: HashMap<PostId, Vec<(PollChoiceId, i64)>> = HashMap::new();
+    for (pid, cid, votes) in poll_votes::table
+        .filter(poll_votes::post_id.eq_any(&post_ids))
+        .group_by((poll_votes::post_id, poll_votes::choice_id))
+        .select((poll_votes::post_id, poll_votes::choice_id, count_star()))
+        .load::<(PostId, PollChoiceId, i64)>(conn)?
+    {
+        poll_results.entry(pid).or_default().push((cid, votes));
+    }
+
+    let mut like_statuses = HashMap::new();
+    let mut emojis = HashMap::new();
+    let mut bookmarked = HashSet::new();
+    let mut boosted = HashSet::new();
+    let mut voted: HashMap<PostId, BTreeSet<PollChoiceId>> = HashMap::new();
+    if let Some(viewer) = viewer {
+        for (pid, status, data) in reactions::table
+            .filter(reactions::user_id.eq(viewer))
+            .filter(reactions::post_id.eq_any(&post_ids))
+            .select((
+                reactions::post_id,
+                reactions::like_status,
+                reactions::reaction,
+            ))
+            .load::<(PostId, i16, Option<ReactionData>)>(conn)?
+        {
+            like_statuses.insert(pid, status);
+            if let Some(emoji) = data.as_ref().and_then(ReactionData::as_emoji) {
+                emojis.insert(pid, emoji.to_string());
+            }
+        }
+
+        bookmarked = bookmarks::table
+            .filter(bookmarks::user_id.eq(viewer))
+            .filter(bookmarks::post_id.eq_any(&post_ids))
+            .select(bookmarks::post_id)
+            .load::<PostId>(conn)?
+            .into_iter()
+            .collect();
+
+        boosted = boosts::table
+            .filter(boosts::user_id.eq(viewer))
+            .filter(boosts::post_id.eq_any(&post_ids))
+            .select(boosts::post_id)
+            .load::<PostId>(conn)?
+            .into_iter()
+            .collect();
+
+        for (pid, cid) in poll_votes::table
+            .filter(poll_votes::user_id.eq(viewer))
+            .filter(poll_votes::post_id.eq_any(&post_ids))
+            .select((poll_votes::post_id, poll_votes::choice_id))
+            .load::<(PostId, PollChoiceId)>(conn)?
+        {
+            voted.entry(pid).or_default().insert(cid);
+        }
+    }
+
+    posts
+        .into_iter()
+        .map(|post| {
+            let author = users
+                .get(&post.user_id)
+                .cloned()
+                .ok_or(DieselError::NotFound)?;
+
+            let reply_to = post.reply_to.and_then(|original| {
+                let user_id = *reply_authors.get(&original)?;
+                let handle = users.get(&user_id)?.user.handle.clone();
+                Some(ReplyTo {
+                    post_id: original,
+                    user_id,
+                    handle,
+                })
+            });
+
+            let count_reactions = |status: i16| {
+                reaction_counts
+                    .get(&(post.id, status))
+                    .copied()
+                    .unwrap_or_default()
+            };
+
+            Ok(PostInfo {
+                aggregates: AggregatePostInfo {
+                    post_id: post.id,
+                    likes: count_reactions(1),
+                    dislikes: count_reactions(-1),
+                    boosts: boost_counts.get(&post.id).copied().unwrap_or_default(),
+                    emojis: emoji_totals(emoji_counts.remove(&post.id).unwrap_or_default()),
+                },
+                author,
+                reply_to,
+                mentions: mentions.remove(&post.id).unwrap_or_default(),
+                poll_results: match poll_results_hidden(&post, viewer) {
+                    true => vec![],
+                    false => poll_results.remove(&post.id).unwrap_or_default(),
+                },
+                like_status: like_statuses.get(&post.id).copied().unwrap_or_default(),
+                emoji: emojis.remove(&post.id),
+                bookmarked: bookmarked.contains(&post.id),
+                boosted: boosted.contains(&post.id),
+                voted: voted.remove(&post.id).unwrap_or_default(),
+                post,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::test_db::{self, Result};
+    use crate::user::tests::util as test_user;
+
+    use uchat_endpoint::post::NewPostOptions;
+    use util as test_post;
+
+    use super::Post;
+    pub mod util {
+        use chrono::{DateTime, Utc};
+        use uchat_domain::{ids::PollChoiceId, Message, PollChoiceDescription, PollHeadline};
+        use uchat_endpoint::post::{Chat, Content, Poll, PollChoice};
+
+        pub fn new_chat(msg: &str) -> Content {
+            Content::Chat(Chat {
+                headline: None,
+                message: Message::new(msg).unwrap(),
+            })
+        }
+
+        pub fn new_poll(
+            choices: &[&str],
+            closes_at: Option<DateTime<Utc>>,
+            hide_results: bool,
+        ) -> Content {
+            Content::Poll(Poll {
+                headline: PollHeadline::new("test poll").unwrap(),
+                choices: choices
+                    .iter()
+                    .map(|choice| PollChoice {
+                        id: PollChoiceId::new(),
+                        num_votes: 0,
+                        description: PollChoiceDescription::new(*choice).unwrap(),
+                    })
+                    .collect(),
+                selection: Default::default(),
+                voted: Default::default(),
+                closes_at,
+                hide_results,
+            })
+        }
+    }
+
+    #[test]
+    fn new_and_get() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user1 = test_user::new_user(&mut conn, "user 1");
+
+        // new post
+        let content = test_post::new_chat("test message");
+        let post = Post::new(user1.id, content, NewPostOptions::default())
+            .expect("failed to create new post structure");
         let post_id = super::new(&mut conn, post).expect("failed to create post");
 
         // get post
@@ -497,4 +1865,732 @@ pub mod tests {
         assert_eq!(post.id, post_id);
         Ok(())
     }
+
+    #[test]
+    fn search_finds_matching_public_posts() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "searcher");
+
+        let content = test_post::new_chat("the quick brown fox jumps over the lazy dog");
+        let post = Post::new(user.id, content, NewPostOptions::default())
+            .expect("failed to create new post structure");
+        let post_id = super::new(&mut conn, post).expect("failed to create post");
+
+        let content = test_post::new_chat("a private fox");
+        let options = NewPostOptions {
+            direct_message_to: Some(user.id),
+            ..NewPostOptions::default()
+        };
+        let post = Post::new(user.id, content, options).expect("failed to create post structure");
+        super::new(&mut conn, post).expect("failed to create post");
+
+        // search
+        let matches = super::search(&mut conn, "foxes", None, 10)?;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].post.id, post_id);
+        assert!(matches[0]
+            .snippet
+            .iter()
+            .any(|fragment| fragment.highlighted && fragment.text == "fox"));
+        Ok(())
+    }
+
+    #[test]
+    fn conversations_group_messages_by_counterpart() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user1 = test_user::new_user(&mut conn, "user 1");
+        let user2 = test_user::new_user(&mut conn, "user 2");
+        let user3 = test_user::new_user(&mut conn, "user 3");
+
+        let mut send = |from, to, msg, minutes_ago| {
+            let options = NewPostOptions {
+                direct_message_to: Some(to),
+                time_posted: chrono::Utc::now() - chrono::Duration::minutes(minutes_ago),
+                ..NewPostOptions::default()
+            };
+            let post = Post::new(from, test_post::new_chat(msg), options)
+                .expect("failed to create post structure");
+            super::new(&mut conn, post).expect("failed to create post")
+        };
+        send(user1.id, user2.id, "hi", 3);
+        let latest_with_2 = send(user2.id, user1.id, "hello", 2);
+        let latest_with_3 = send(user3.id, user1.id, "hey", 1);
+
+        // list conversations
+        let conversations = super::list_conversations(&mut conn, user1.id, None, 10)?;
+        let conversations: Vec<_> = conversations
+            .iter()
+            .map(|preview| (preview.with_user, preview.last_message.id))
+            .collect();
+        assert_eq!(
+            conversations,
+            vec![(user3.id, latest_with_3), (user2.id, latest_with_2)]
+        );
+
+        // get conversation
+        let messages = super::get_conversation(&mut conn, user2.id, user1.id, None, 10)?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].id, latest_with_2);
+        Ok(())
+    }
+
+    #[test]
+    fn thread_returns_ancestors_and_nested_replies() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "threader");
+
+        let mut reply = |reply_to, msg, minutes_ago| {
+            let options = NewPostOptions {
+                reply_to,
+                time_posted: chrono::Utc::now() - chrono::Duration::minutes(minutes_ago),
+                ..NewPostOptions::default()
+            };
+            let post = Post::new(user.id, test_post::new_chat(msg), options)
+                .expect("failed to create post structure");
+            super::new(&mut conn, post).expect("failed to create post")
+        };
+        let root = reply(None, "root", 10);
+        let focus = reply(Some(root), "focus", 9);
+        let first = reply(Some(focus), "first", 8);
+        let second = reply(Some(focus), "second", 7);
+        let nested = reply(Some(first), "nested", 6);
+        let too_deep = reply(Some(nested), "too deep", 5);
+
+        // ancestors
+        let ancestors = super::get_thread_ancestors(&mut conn, user.id, focus)?;
+        let ancestors: Vec<_> = ancestors.iter().map(|post| post.id).collect();
+        assert_eq!(ancestors, vec![root]);
+
+        // replies
+        let replies = super::get_replies(&mut conn, user.id, focus, None, 10, 2)?;
+        let replies: Vec<_> = replies
+            .iter()
+            .map(|reply| (reply.post.id, reply.depth))
+            .collect();
+        assert_eq!(replies, vec![(first, 1), (nested, 2), (second, 1)]);
+        assert!(!replies.iter().any(|(id, _)| *id == too_deep));
+
+        // next page of direct replies
+        let cursor = super::get(&mut conn, first)?;
+        let replies = super::get_replies(&mut conn, user.id, focus, Some((&cursor).into()), 10, 2)?;
+        let replies: Vec<_> = replies.iter().map(|reply| reply.post.id).collect();
+        assert_eq!(replies, vec![second]);
+        Ok(())
+    }
+
+    #[test]
+    fn thread_ancestors_leave_out_messages_and_scheduled_posts() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "sender");
+        let recipient = test_user::new_user(&mut conn, "recipient");
+
+        let mut post = |options| {
+            let post = Post::new(user.id, test_post::new_chat("text"), options)
+                .expect("failed to create post structure");
+            super::new(&mut conn, post).expect("failed to create post")
+        };
+        let message = post(NewPostOptions {
+            direct_message_to: Some(recipient.id),
+            ..NewPostOptions::default()
+        });
+        let scheduled = post(NewPostOptions {
+            time_posted: chrono::Utc::now() + chrono::Duration::hours(1),
+            ..NewPostOptions::default()
+        });
+        let reply_to_message = post(NewPostOptions {
+            reply_to: Some(message),
+            ..NewPostOptions::default()
+        });
+        let reply_to_scheduled = post(NewPostOptions {
+            reply_to: Some(scheduled),
+            ..NewPostOptions::default()
+        });
+
+        let ancestors = super::get_thread_ancestors(&mut conn, recipient.id, reply_to_message)?;
+        assert!(ancestors.is_empty());
+        let ancestors = super::get_thread_ancestors(&mut conn, recipient.id, reply_to_scheduled)?;
+        assert!(ancestors.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn blocked_users_are_hidden_from_feeds_and_threads() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let alice = test_user::new_user(&mut conn, "alice");
+        let bob = test_user::new_user(&mut conn, "bob");
+        let carol = test_user::new_user(&mut conn, "carol");
+
+        let mut post = |user_id, reply_to, msg| {
+            let options = NewPostOptions {
+                reply_to,
+                time_posted: chrono::Utc::now() - chrono::Duration::minutes(1),
+                ..NewPostOptions::default()
+            };
+            let post = Post::new(user_id, test_post::new_chat(msg), options)
+                .expect("failed to create post structure");
+            super::new(&mut conn, post).expect("failed to create post")
+        };
+        let root = post(carol.id, None, "root");
+        let from_bob = post(bob.id, Some(root), "from bob");
+        let from_alice = post(alice.id, Some(root), "from alice");
+
+        crate::user::follow(&mut conn, alice.id, carol.id)?;
+        crate::user::follow(&mut conn, bob.id, carol.id)?;
+        crate::user::block(&mut conn, alice.id, bob.id)?;
+
+        // home and profile
+        let home = super::get_home_posts(&mut conn, bob.id, None, 10)?;
+        assert_eq!(home.len(), 1);
+        assert!(super::get_public_posts(&mut conn, alice.id, bob.id, None, 10)?.is_empty());
+        assert!(super::get_public_posts(&mut conn, bob.id, alice.id, None, 10)?.is_empty());
+        assert_eq!(
+            super::get_public_posts(&mut conn, carol.id, bob.id, None, 10)?.len(),
+            1
+        );
+
+        // threads, in both directions
+        let replies = super::get_replies(&mut conn, alice.id, root, None, 10, 2)?;
+        let replies: Vec<_> = replies.iter().map(|reply| reply.post.id).collect();
+        assert_eq!(replies, vec![from_alice]);
+        let replies = super::get_replies(&mut conn, bob.id, root, None, 10, 2)?;
+        let replies: Vec<_> = replies.iter().map(|reply| reply.post.id).collect();
+        assert_eq!(replies, vec![from_bob]);
+        assert!(super::get_thread_ancestors(&mut conn, carol.id, from_bob)?.len() == 1);
+        Ok(())
+    }
+
+    #[test]
+    fn muted_users_and_threads_are_hidden_from_home() -> Result<()> {
+        use crate::user::MuteTarget;
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let me = test_user::new_user(&mut conn, "me");
+        let friend = test_user::new_user(&mut conn, "friend");
+        let loud = test_user::new_user(&mut conn, "loud");
+
+        let mut post = |user_id, reply_to, msg| {
+            let options = NewPostOptions {
+                reply_to,
+                time_posted: chrono::Utc::now() - chrono::Duration::minutes(1),
+                ..NewPostOptions::default()
+            };
+            let post = Post::new(user_id, test_post::new_chat(msg), options)
+                .expect("failed to create post structure");
+            super::new(&mut conn, post).expect("failed to create post")
+        };
+        let root = post(friend.id, None, "root");
+        let reply = post(friend.id, Some(root), "reply");
+        post(friend.id, Some(reply), "nested reply");
+        let from_loud = post(loud.id, None, "loud");
+        let mine = post(me.id, None, "mine");
+
+        crate::user::follow(&mut conn, me.id, friend.id)?;
+        crate::user::follow(&mut conn, me.id, loud.id)?;
+        super::boost(&mut conn, loud.id, root, chrono::Utc::now())?;
+        assert_eq!(super::get_home_posts(&mut conn, me.id, None, 10)?.len(), 4);
+
+        // muting a user hides their posts but keeps posts they boosted by others
+        crate::user::mute(&mut conn, me.id, MuteTarget::User(loud.id), None)?;
+        let home = super::get_home_posts(&mut conn, me.id, None, 10)?;
+        assert_eq!(home.len(), 3);
+        assert!(!home.iter().any(|post| post.id == from_loud));
+
+        // muting a thread hides all replies beneath it
+        crate::user::mute(&mut conn, me.id, MuteTarget::Thread(root), None)?;
+        let home: Vec<_> = super::get_home_posts(&mut conn, me.id, None, 10)?
+            .iter()
+            .map(|post| post.id)
+            .collect();
+        assert_eq!(home, vec![root]);
+
+        // a thread mute on its own hides nothing else
+        let quiet = test_user::new_user(&mut conn, "quiet");
+        crate::user::follow(&mut conn, quiet.id, friend.id)?;
+        crate::user::follow(&mut conn, quiet.id, loud.id)?;
+        crate::user::mute(&mut conn, quiet.id, MuteTarget::Thread(reply), None)?;
+        let home: Vec<_> = super::get_home_posts(&mut conn, quiet.id, None, 10)?
+            .iter()
+            .map(|post| post.id)
+            .collect();
+        assert!(home.contains(&root));
+        assert!(home.contains(&reply));
+        assert!(home.contains(&from_loud));
+        assert_eq!(home.len(), 3);
+
+        // muted threads are hidden from thread views too
+        let replies = super::get_replies(&mut conn, quiet.id, root, None, 10, 5)?;
+        let replies: Vec<_> = replies.iter().map(|reply| reply.post.id).collect();
+        assert_eq!(replies, vec![reply]);
+        assert!(super::get_replies(&mut conn, me.id, root, None, 10, 5)?.is_empty());
+
+        // the muted user still sees my posts
+        crate::user::follow(&mut conn, loud.id, me.id)?;
+        let home = super::get_home_posts(&mut conn, loud.id, None, 10)?;
+        assert!(home.iter().any(|post| post.id == mine));
+        Ok(())
+    }
+
+    #[test]
+    fn delete_removes_replies() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "deleter");
+
+        let post = Post::new(
+            user.id,
+            test_post::new_chat("parent"),
+            NewPostOptions::default(),
+        )
+        .expect("failed to create post structure");
+        let parent = super::new(&mut conn, post).expect("failed to create post");
+
+        let options = NewPostOptions {
+            reply_to: Some(parent),
+            ..NewPostOptions::default()
+        };
+        let post = Post::new(user.id, test_post::new_chat("reply"), options)
+            .expect("failed to create post structure");
+        let reply = super::new(&mut conn, post).expect("failed to create post");
+
+        // delete
+        let deleted = super::delete(&mut conn, parent)?;
+        assert_eq!(deleted.len(), 2);
+        assert!(super::get(&mut conn, parent).is_err());
+        assert!(super::get(&mut conn, reply).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn edit_keeps_previous_content() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "editor");
+
+        let post = Post::new(
+            user.id,
+            test_post::new_chat("typo"),
+            NewPostOptions::default(),
+        )
+        .expect("failed to create post structure");
+        let post_id = super::new(&mut conn, post).expect("failed to create post");
+
+        // edit
+        let status = super::edit(
+            &mut conn,
+            post_id,
+            test_post::new_chat("fixed"),
+            chrono::Utc::now(),
+        )?;
+        assert_eq!(status, super::EditStatus::Edited);
+
+        let post = super::get(&mut conn, post_id)?;
+        assert!(post.edited_at.is_some());
+        assert_eq!(post.content.0["Chat"]["message"], "fixed");
+
+        let revisions = super::get_revisions(&mut conn, post_id)?;
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].content.0["Chat"]["message"], "typo");
+        Ok(())
+    }
+
+    #[test]
+    fn poll_choices_are_edited_in_place_until_voted() -> Result<()> {
+        use uchat_domain::PollChoiceDescription;
+        use uchat_endpoint::post::{Content, VoteCast};
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let voter = test_user::new_user(&mut conn, "voter");
+
+        let content = test_post::new_poll(&["a", "b"], None, false);
+        let post = Post::new(author.id, content.clone(), NewPostOptions::default())?;
+        let post_id = super::new(&mut conn, post)?;
+
+        // renamed choices keep their ids
+        let mut renamed = content;
+        let Content::Poll(ref mut poll) = renamed else {
+            unreachable!()
+        };
+        poll.choices[0].description = PollChoiceDescription::new("renamed")?;
+        let first_choice = poll.choices[0].id;
+        let status = super::edit(&mut conn, post_id, renamed.clone(), chrono::Utc::now())?;
+        assert_eq!(status, super::EditStatus::Edited);
+        let cast = super::vote(&mut conn, voter.id, post_id, &[first_choice].into())?;
+        assert_eq!(cast, VoteCast::Yes);
+
+        // once voted, the choices are locked and the votes kept
+        let mut removed = renamed;
+        let Content::Poll(ref mut poll) = removed else {
+            unreachable!()
+        };
+        poll.choices.pop();
+        let status = super::edit(&mut conn, post_id, removed, chrono::Utc::now())?;
+        assert_eq!(status, super::EditStatus::PollHasVotes);
+        assert_eq!(super::get_revisions(&mut conn, post_id)?.len(), 1);
+        let voted = super::did_vote(&mut conn, voter.id, post_id)?;
+        assert_eq!(voted, [first_choice].into());
+        Ok(())
+    }
+
+    #[test]
+    fn hashtags_are_indexed_and_trend() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "tagger");
+
+        let mut post = |msg| {
+            let options = NewPostOptions {
+                time_posted: chrono::Utc::now() - chrono::Duration::minutes(1),
+                ..NewPostOptions::default()
+            };
+            let post = Post::new(user.id, test_post::new_chat(msg), options)
+                .expect("failed to create post structure");
+            super::new(&mut conn, post).expect("failed to create post")
+        };
+        let first = post("#Rust is #fun");
+        post("more #rust");
+
+        // timelines match regardless of case
+        let timeline = super::get_tag_timeline(&mut conn, user.id, "RUST", None, 10)?;
+        assert_eq!(timeline.len(), 2);
+
+        // trends
+        let since = chrono::Utc::now() - chrono::Duration::hours(1);
+        let trending: Vec<_> = super::get_trending_tags(&mut conn, user.id, since, 10)?
+            .into_iter()
+            .map(|usage| (usage.tag, usage.posts))
+            .collect();
+        assert_eq!(
+            trending,
+            vec![("rust".to_string(), 2), ("fun".to_string(), 1)]
+        );
+
+        // tags of blocked users don't trend
+        let blocker = test_user::new_user(&mut conn, "blocker");
+        crate::user::block(&mut conn, blocker.id, user.id)?;
+        assert!(super::get_trending_tags(&mut conn, blocker.id, since, 10)?.is_empty());
+
+        // edits replace hashtags
+        super::edit(
+            &mut conn,
+            first,
+            test_post::new_chat("no tags"),
+            chrono::Utc::now(),
+        )?;
+        assert!(super::get_tag_timeline(&mut conn, user.id, "fun", None, 10)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn mentions_are_resolved_listed_and_edited() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let alice = test_user::new_user(&mut conn, "alice");
+        let bob = test_user::new_user(&mut conn, "bob");
+
+        let options = NewPostOptions {
+            time_posted: chrono::Utc::now() - chrono::Duration::minutes(1),
+            ..NewPostOptions::default()
+        };
+        let post = Post::new(
+            alice.id,
+            test_post::new_chat("hi @bob, @nobody and @alice"),
+            options,
+        )
+        .expect("failed to create post structure");
+        let post_id = super::new(&mut conn, post).expect("failed to create post");
+
+        // unknown handles and the author are ignored
+        let mentioned = super::get_mentioned_posts(&mut conn, bob.id, None, 10)?;
+        assert_eq!(mentioned.len(), 1);
+        assert!(super::get_mentioned_posts(&mut conn, alice.id, None, 10)?.is_empty());
+
+        let info = super::hydrate(&mut conn, mentioned, Some(bob.id))?;
+        let mentions: Vec<_> = info[0]
+            .mentions
+            .iter()
+            .map(|mention| (mention.user_id, mention.handle.as_str()))
+            .collect();
+        assert_eq!(mentions, vec![(bob.id, "bob")]);
+
+        // edits replace mentions
+        super::edit(
+            &mut conn,
+            post_id,
+            test_post::new_chat("hi everyone"),
+            chrono::Utc::now(),
+        )?;
+        assert!(super::get_mentioned_posts(&mut conn, bob.id, None, 10)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn scheduled_posts_are_listed_and_rescheduled() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "scheduler");
+
+        let published = Post::new(
+            user.id,
+            test_post::new_chat("now"),
+            NewPostOptions::default(),
+        )
+        .expect("failed to create post structure");
+        let published = super::new(&mut conn, published).expect("failed to create post");
+
+        let options = NewPostOptions {
+            time_posted: chrono::Utc::now() + chrono::Duration::hours(1),
+            ..NewPostOptions::default()
+        };
+        let post = Post::new(user.id, test_post::new_chat("later"), options)
+            .expect("failed to create post structure");
+        let scheduled = super::new(&mut conn, post).expect("failed to create post");
+
+        // list
+        let posts = super::get_scheduled_posts(&mut conn, user.id, None, 10)?;
+        let posts: Vec<_> = posts.iter().map(|post| post.id).collect();
+        assert_eq!(posts, vec![scheduled]);
+
+        // reschedule
+        let later = chrono::Utc::now() + chrono::Duration::days(1);
+        assert!(super::reschedule(&mut conn, user.id, scheduled, later)?);
+        assert!(!super::reschedule(&mut conn, user.id, published, later)?);
+        Ok(())
+    }
+
+    #[test]
+    fn parses_snippet_highlights() {
+        let fragments = super::parse_snippet("the \u{2}quick\u{3} brown \u{2}fox\u{3}");
+        let fragments: Vec<_> = fragments
+            .iter()
+            .map(|fragment| (fragment.text.as_str(), fragment.highlighted))
+            .collect();
+        assert_eq!(
+            fragments,
+            vec![
+                ("the ", false),
+                ("quick", true),
+                (" brown ", false),
+                ("fox", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn hydrate_loads_aggregates_and_viewer_flags() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let viewer = test_user::new_user(&mut conn, "viewer");
+
+        let content = test_post::new_chat("test message");
+        let post = Post::new(author.id, content, NewPostOptions::default())
+            .expect("failed to create new post structure");
+        let post_id = super::new(&mut conn, post).expect("failed to create post");
+
+        super::bookmark(&mut conn, viewer.id, post_id)?;
+        super::react(
+            &mut conn,
+            super::Reaction {
+                user_id: viewer.id,
+                post_id,
+                created_at: chrono::Utc::now(),
+                like_status: 1,
+                reaction: None,
+            },
+        )?;
+
+        // hydrate
+        let post = super::get(&mut conn, post_id)?;
+        let info = super::hydrate(&mut conn, vec![post], Some(viewer.id))?;
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].author.user.id, author.id);
+        assert_eq!(info[0].aggregates.likes, 1);
+        assert_eq!(info[0].aggregates.dislikes, 0);
+        assert_eq!(info[0].like_status, 1);
+        assert!(info[0].bookmarked);
+        assert!(!info[0].boosted);
+        Ok(())
+    }
+
+    #[test]
+    fn liked_posts_are_paged_by_when_they_were_liked() -> Result<()> {
+        use chrono::{Duration, Utc};
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let fan = test_user::new_user(&mut conn, "fan");
+
+        let mut post = |msg| {
+            let post = Post::new(
+                author.id,
+                test_post::new_chat(msg),
+                NewPostOptions::default(),
+            )
+            .expect("failed to create post structure");
+            super::new(&mut conn, post).expect("failed to create post")
+        };
+        let older = post("older");
+        let newer = post("newer");
+
+        // the older post is liked last
+        for (post_id, minutes_ago) in [(newer, 2), (older, 1)] {
+            super::react(
+                &mut conn,
+                super::Reaction {
+                    user_id: fan.id,
+                    post_id,
+                    created_at: Utc::now() - Duration::minutes(minutes_ago),
+                    like_status: 1,
+                    reaction: None,
+                },
+            )?;
+        }
+
+        let page = super::get_liked_posts(&mut conn, fan.id, None, 1)?;
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].post.id, older);
+
+        let after = Some(crate::TimeCursor::from(&page[0]));
+        let page = super::get_liked_posts(&mut conn, fan.id, after, 1)?;
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].post.id, newer);
+        Ok(())
+    }
+
+    #[test]
+    fn closed_polls_reject_votes_and_hidden_results_are_withheld() -> Result<()> {
+        use chrono::{Duration, Utc};
+        use uchat_endpoint::post::{Content, VoteCast};
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let voter = test_user::new_user(&mut conn, "voter");
+
+        let first_choice = |content: &Content| match content {
+            Content::Poll(poll) => poll.choices[0].id,
+            _ => unreachable!(),
+        };
+
+        let content =
+            test_post::new_poll(&["a", "b"], Some(Utc::now() - Duration::hours(1)), false);
+        let closed_choice = first_choice(&content);
+        let post = Post::new(author.id, content, NewPostOptions::default())?;
+        let closed_id = super::new(&mut conn, post)?;
+
+        let content = test_post::new_poll(&["a", "b"], Some(Utc::now() + Duration::hours(1)), true);
+        let open_choice = first_choice(&content);
+        let post = Post::new(author.id, content, NewPostOptions::default())?;
+        let open_id = super::new(&mut conn, post)?;
+
+        // votes
+        let cast = super::vote(&mut conn, voter.id, closed_id, &[closed_choice].into())?;
+        assert_eq!(cast, VoteCast::Closed);
+        let cast = super::vote(&mut conn, voter.id, open_id, &[open_choice].into())?;
+        assert_eq!(cast, VoteCast::Yes);
+
+        // only the author sees the results before the poll closes
+        let post = super::get(&mut conn, open_id)?;
+        let info = super::hydrate(&mut conn, vec![post.clone()], Some(voter.id))?;
+        assert!(info[0].poll_results.is_empty());
+        assert_eq!(info[0].voted, [open_choice].into());
+        let info = super::hydrate(&mut conn, vec![post], Some(author.id))?;
+        assert_eq!(info[0].poll_results, vec![(open_choice, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn votes_can_be_changed_and_retracted() -> Result<()> {
+        use std::collections::BTreeSet;
+        use uchat_endpoint::post::{Content, VoteCast};
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let voter = test_user::new_user(&mut conn, "voter");
+
+        let content = test_post::new_poll(&["a", "b", "c"], None, false);
+        let choices: Vec<_> = match &content {
+            Content::Poll(poll) => poll.choices.iter().map(|choice| choice.id).collect(),
+            _ => unreachable!(),
+        };
+        let post = Post::new(author.id, content, NewPostOptions::default())?;
+        let post_id = super::new(&mut conn, post)?;
+
+        // several choices at once
+        let cast = super::vote(
+            &mut conn,
+            voter.id,
+            post_id,
+            &[choices[0], choices[1]].into(),
+        )?;
+        assert_eq!(cast, VoteCast::Yes);
+        let voted = super::did_vote(&mut conn, voter.id, post_id)?;
+        assert_eq!(voted, [choices[0], choices[1]].into());
+
+        // changing replaces the previous choices
+        super::vote(&mut conn, voter.id, post_id, &[choices[2]].into())?;
+        let voted = super::did_vote(&mut conn, voter.id, post_id)?;
+        assert_eq!(voted, [choices[2]].into());
+
+        // retracting removes them all
+        let cast = super::vote(&mut conn, voter.id, post_id, &BTreeSet::new())?;
+        assert_eq!(cast, VoteCast::Retracted);
+        assert!(super::did_vote(&mut conn, voter.id, post_id)?.is_empty());
+        assert!(!super::has_votes(&mut conn, post_id)?);
+        Ok(())
+    }
+
+    #[test]
+    fn emoji_reactions_are_counted_separately_from_likes() -> Result<()> {
+        use super::ReactionData;
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let author = test_user::new_user(&mut conn, "author");
+        let first = test_user::new_user(&mut conn, "first");
+        let second = test_user::new_user(&mut conn, "second");
+
+        let content = test_post::new_chat("test message");
+        let post = Post::new(author.id, content, NewPostOptions::default())
+            .expect("failed to create new post structure");
+        let post_id = super::new(&mut conn, post).expect("failed to create post");
+
+        super::react(
+            &mut conn,
+            super::Reaction {
+                user_id: first.id,
+                post_id,
+                created_at: chrono::Utc::now(),
+                like_status: 1,
+                reaction: None,
+            },
+        )?;
+        for (user_id, emoji) in [(first.id, "🎉"), (second.id, "🎉"), (author.id, "👍")] {
+            let emoji = Some(ReactionData::emoji(emoji));
+            super::react_with_emoji(&mut conn, user_id, post_id, emoji)?;
+        }
+        super::react_with_emoji(&mut conn, author.id, post_id, None)?;
+
+        // emoji reactions keep the like status, and cleared emoji are not counted
+        let aggregates = super::aggregate_reactions(&mut conn, post_id)?;
+        assert_eq!(aggregates.likes, 1);
+        assert_eq!(aggregates.emojis, vec![("🎉".to_string(), 2)]);
+
+        let post = super::get(&mut conn, post_id)?;
+        let info = super::hydrate(&mut conn, vec![post], Some(first.id))?;
+        assert_eq!(info[0].like_status, 1);
+        assert_eq!(info[0].emoji.as_deref(), Some("🎉"));
+        assert_eq!(info[0].aggregates.emojis, aggregates.emojis);
+        Ok(())
+    }
 }
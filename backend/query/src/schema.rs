@@ -1,5 +1,13 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    blocks (user_id, blocked) {
+        user_id -> Uuid,
+        blocked -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     bookmarks (user_id, post_id) {
         user_id -> Uuid,
@@ -24,6 +32,57 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    hashtags (id) {
+        id -> Uuid,
+        tag -> Text,
+    }
+}
+
+diesel::table! {
+    mutes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        muted_user -> Nullable<Uuid>,
+        muted_post -> Nullable<Uuid>,
+        expires_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    notifications (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        actor -> Uuid,
+        kind -> Text,
+        post_id -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        read_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    password_resets (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Text,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    pending_logins (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Text,
+        expires_at -> Timestamptz,
+        failed_attempts -> Int4,
+    }
+}
+
 diesel::table! {
     poll_choices (id) {
         id -> Uuid,
@@ -33,7 +92,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    poll_votes (user_id, post_id) {
+    poll_votes (user_id, post_id, choice_id) {
         user_id -> Uuid,
         post_id -> Uuid,
         choice_id -> Uuid,
@@ -41,6 +100,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_hashtags (post_id, hashtag_id) {
+        post_id -> Uuid,
+        hashtag_id -> Uuid,
+    }
+}
+
+diesel::table! {
+    post_mentions (post_id, user_id) {
+        post_id -> Uuid,
+        user_id -> Uuid,
+    }
+}
+
+diesel::table! {
+    post_revisions (id) {
+        id -> Uuid,
+        post_id -> Uuid,
+        content -> Jsonb,
+        revised_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     posts (id) {
         id -> Uuid,
@@ -50,6 +132,7 @@ diesel::table! {
         direct_message_to -> Nullable<Uuid>,
         reply_to -> Nullable<Uuid>,
         created_at -> Timestamptz,
+        edited_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -63,6 +146,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        code_hash -> Text,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    two_factor (user_id) {
+        user_id -> Uuid,
+        secret -> Text,
+        confirmed_at -> Nullable<Timestamptz>,
+        last_used_step -> Nullable<Int8>,
+        failed_attempts -> Int4,
+        last_failed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Uuid,
@@ -87,19 +191,39 @@ diesel::table! {
 }
 
 diesel::joinable!(followers -> users (follows));
+diesel::joinable!(password_resets -> users (user_id));
+diesel::joinable!(pending_logins -> users (user_id));
 diesel::joinable!(poll_votes -> poll_choices (choice_id));
 diesel::joinable!(posts -> users (direct_message_to));
 diesel::joinable!(bookmarks -> posts (post_id));
+diesel::joinable!(post_hashtags -> hashtags (hashtag_id));
+diesel::joinable!(post_hashtags -> posts (post_id));
+diesel::joinable!(post_mentions -> posts (post_id));
+diesel::joinable!(post_mentions -> users (user_id));
+diesel::joinable!(post_revisions -> posts (post_id));
 diesel::joinable!(reactions -> posts (post_id));
+diesel::joinable!(recovery_codes -> users (user_id));
+diesel::joinable!(two_factor -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    blocks,
     bookmarks,
     boosts,
     followers,
+    hashtags,
+    mutes,
+    notifications,
+    password_resets,
+    pending_logins,
     poll_choices,
     poll_votes,
+    post_hashtags,
+    post_mentions,
+    post_revisions,
     posts,
     reactions,
+    recovery_codes,
+    two_factor,
     users,
     web,
 );
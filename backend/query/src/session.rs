@@ -4,11 +4,18 @@ use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uchat_domain::ids::{SessionId, UserId};
 
-use crate::schema;
+use crate::{post::DeleteStatus, schema};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, DieselNewType)]
 pub struct FingerPrint(serde_json::Value);
 
+impl FingerPrint {
+    /// A recorded detail of the device, such as its `user_agent`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key)?.as_str()
+    }
+}
+
 impl From<serde_json::Value> for FingerPrint {
     fn from(value: serde_json::Value) -> Self {
         Self(value)
@@ -25,6 +32,13 @@ pub struct Session {
     pub fingerprint: FingerPrint,
 }
 
+impl Session {
+    /// User agent of the device which logged in, when it was recorded.
+    pub fn user_agent(&self) -> Option<&str> {
+        self.fingerprint.get("user_agent")
+    }
+}
+
 pub fn new(
     conn: &mut PgConnection,
     user_id: UserId,
@@ -52,14 +66,29 @@ pub fn new(
     }
 }
 
+/// Gets a session which hasn't expired yet.
 pub fn get(conn: &mut PgConnection, session_id: SessionId) -> Result<Option<Session>, DieselError> {
     use crate::schema::web;
     web::table
         .filter(web::id.eq(session_id))
+        .filter(web::expires_at.gt(Utc::now()))
         .get_result(conn)
         .optional()
 }
 
+/// Extends a session so that it expires at `expires_at`.
+pub fn renew(
+    conn: &mut PgConnection,
+    session_id: SessionId,
+    expires_at: DateTime<Utc>,
+) -> Result<Session, DieselError> {
+    use crate::schema::web;
+    diesel::update(web::table)
+        .filter(web::id.eq(session_id))
+        .set(web::expires_at.eq(expires_at))
+        .get_result(conn)
+}
+
 pub fn find(
     conn: &mut PgConnection,
     user_id: UserId,
@@ -71,3 +100,149 @@ pub fn find(
         .filter(web::fingerprint.eq(fingerprint))
         .get_result(conn)
 }
+
+/// Unexpired sessions of `user_id`, most recently created first.
+pub fn list(conn: &mut PgConnection, user_id: UserId) -> Result<Vec<Session>, DieselError> {
+    use crate::schema::web;
+    web::table
+        .filter(web::user_id.eq(user_id))
+        .filter(web::expires_at.gt(Utc::now()))
+        .order((web::created_at.desc(), web::id.desc()))
+        .load(conn)
+}
+
+/// Deletes a session of `user_id`, logging out the device which uses it.
+///
+/// Sessions belonging to other users are left alone.
+pub fn delete(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    session_id: SessionId,
+) -> Result<DeleteStatus, DieselError> {
+    use crate::schema::web;
+    diesel::delete(web::table)
+        .filter(web::id.eq(session_id))
+        .filter(web::user_id.eq(user_id))
+        .execute(conn)
+        .map(|row_count| {
+            if row_count > 0 {
+                DeleteStatus::Deleted
+            } else {
+                DeleteStatus::NotFound
+            }
+        })
+}
+
+/// Deletes every session of `user_id` except `keep`, returning how many were deleted.
+pub fn delete_all_except(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    keep: SessionId,
+) -> Result<usize, DieselError> {
+    use crate::schema::web;
+    diesel::delete(web::table)
+        .filter(web::user_id.eq(user_id))
+        .filter(web::id.ne(keep))
+        .execute(conn)
+}
+
+/// Deletes every session of `user_id`, logging the user out everywhere.
+pub fn delete_all(conn: &mut PgConnection, user_id: UserId) -> Result<usize, DieselError> {
+    use crate::schema::web;
+    diesel::delete(web::table)
+        .filter(web::user_id.eq(user_id))
+        .execute(conn)
+}
+
+/// Deletes every expired session, returning how many were deleted.
+pub fn delete_expired(conn: &mut PgConnection) -> Result<usize, DieselError> {
+    use crate::schema::web;
+    diesel::delete(web::table)
+        .filter(web::expires_at.le(Utc::now()))
+        .execute(conn)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::post::DeleteStatus;
+    use crate::test_db::{self, Result};
+    use crate::user::tests::util as test_user;
+
+    #[test]
+    fn sessions_are_listed_and_revoked() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "traveler");
+        let other = test_user::new_user(&mut conn, "other");
+
+        let mut new_session = |user_id, device: &str| {
+            let fingerprint = serde_json::json!({ "user_agent": device });
+            super::new(&mut conn, user_id, Duration::days(1), fingerprint.into())
+                .expect("failed to create session")
+        };
+        let laptop = new_session(user.id, "laptop");
+        let phone = new_session(user.id, "phone");
+        let tablet = new_session(user.id, "tablet");
+        let elsewhere = new_session(other.id, "laptop");
+
+        let sessions = super::list(&mut conn, user.id)?;
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(tablet.user_agent(), Some("tablet"));
+
+        // only the owner can revoke a session
+        assert_eq!(
+            super::delete(&mut conn, user.id, elsewhere.id)?,
+            DeleteStatus::NotFound
+        );
+        assert_eq!(
+            super::delete(&mut conn, user.id, phone.id)?,
+            DeleteStatus::Deleted
+        );
+
+        assert_eq!(super::delete_all_except(&mut conn, user.id, laptop.id)?, 1);
+        let remaining: Vec<_> = super::list(&mut conn, user.id)?
+            .into_iter()
+            .map(|session| session.id)
+            .collect();
+        assert_eq!(remaining, vec![laptop.id]);
+        assert!(super::get(&mut conn, elsewhere.id)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn expired_sessions_are_ignored_renewed_and_swept() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "sleeper");
+
+        let mut new_session = |device: &str| {
+            let fingerprint = serde_json::json!({ "user_agent": device });
+            super::new(&mut conn, user.id, Duration::days(1), fingerprint.into())
+                .expect("failed to create session")
+        };
+        let active = new_session("laptop");
+        let stale = new_session("phone");
+        let revived = new_session("tablet");
+        super::renew(&mut conn, stale.id, Utc::now() - Duration::seconds(1))?;
+        super::renew(&mut conn, revived.id, Utc::now() - Duration::seconds(1))?;
+
+        // expired sessions can't be used
+        assert!(super::get(&mut conn, active.id)?.is_some());
+        assert!(super::get(&mut conn, stale.id)?.is_none());
+        assert_eq!(super::list(&mut conn, user.id)?.len(), 1);
+
+        // renewing extends the expiry
+        let expires_at = Utc::now() + Duration::days(7);
+        let renewed = super::renew(&mut conn, active.id, expires_at)?;
+        assert!(renewed.expires_at > active.expires_at);
+
+        // only expired sessions are swept
+        super::renew(&mut conn, revived.id, expires_at)?;
+        assert_eq!(super::delete_expired(&mut conn)?, 1);
+        assert!(super::get(&mut conn, active.id)?.is_some());
+        assert!(super::get(&mut conn, revived.id)?.is_some());
+        Ok(())
+    }
+}
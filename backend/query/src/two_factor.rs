@@ -0,0 +1,341 @@
+//! Second login factor using codes from an authenticator app, with recovery codes as a fallback.
+
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use uchat_domain::ids::UserId;
+use uuid::Uuid;
+
+use crate::DieselError;
+
+#[derive(Clone, Debug, Queryable)]
+#[diesel(table_name = crate::schema::two_factor)]
+pub struct TwoFactor {
+    pub user_id: UserId,
+    /// Base32 encoded secret shared with the authenticator app.
+    pub secret: String,
+    /// Unset until the user confirms the app is set up by entering a code.
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    /// Wrong codes entered since a code was last accepted.
+    pub failed_attempts: i32,
+    pub last_failed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TwoFactor {
+    pub fn is_enabled(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+
+    /// Whether `max_failed_attempts` wrong codes were entered, the last one within `lockout`.
+    pub fn is_locked_out(&self, max_failed_attempts: i32, lockout: Duration) -> bool {
+        self.failed_attempts >= max_failed_attempts
+            && self
+                .last_failed_at
+                .map_or(false, |failed_at| failed_at + lockout > Utc::now())
+    }
+}
+
+pub fn get(conn: &mut PgConnection, user_id: UserId) -> Result<Option<TwoFactor>, DieselError> {
+    use crate::schema::two_factor;
+    two_factor::table
+        .filter(two_factor::user_id.eq(user_id))
+        .get_result(conn)
+        .optional()
+}
+
+/// Whether logging in as `user_id` requires a second factor.
+pub fn is_enabled(conn: &mut PgConnection, user_id: UserId) -> Result<bool, DieselError> {
+    get(conn, user_id).map(|two_factor| two_factor.map_or(false, |t| t.is_enabled()))
+}
+
+/// Starts setting up an authenticator app with `secret`, replacing any earlier unfinished setup.
+///
+/// Users who already finished setting up an app are left alone; returns whether the setup was
+/// started.
+pub fn begin_enrollment(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    secret: &str,
+) -> Result<bool, DieselError> {
+    use crate::schema::two_factor;
+
+    conn.transaction(|conn| {
+        if is_enabled(conn, user_id)? {
+            return Ok(false);
+        }
+
+        diesel::delete(two_factor::table)
+            .filter(two_factor::user_id.eq(user_id))
+            .execute(conn)?;
+        diesel::insert_into(two_factor::table)
+            .values((
+                two_factor::user_id.eq(user_id),
+                two_factor::secret.eq(secret),
+            ))
+            .execute(conn)?;
+        Ok(true)
+    })
+}
+
+/// Finishes setting up the authenticator app once a code from time step `step` was entered,
+/// replacing the recovery codes of the user with `recovery_code_hashes`.
+pub fn confirm_enrollment(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    step: i64,
+    recovery_code_hashes: Vec<String>,
+) -> Result<(), DieselError> {
+    use crate::schema::{recovery_codes, two_factor};
+
+    conn.transaction(|conn| {
+        diesel::update(two_factor::table)
+            .filter(two_factor::user_id.eq(user_id))
+            .set((
+                two_factor::confirmed_at.eq(Utc::now()),
+                two_factor::last_used_step.eq(step),
+            ))
+            .execute(conn)?;
+
+        diesel::delete(recovery_codes::table)
+            .filter(recovery_codes::user_id.eq(user_id))
+            .execute(conn)?;
+        let rows: Vec<_> = recovery_code_hashes
+            .into_iter()
+            .map(|code_hash| {
+                (
+                    recovery_codes::id.eq(Uuid::new_v4()),
+                    recovery_codes::user_id.eq(user_id),
+                    recovery_codes::code_hash.eq(code_hash),
+                )
+            })
+            .collect();
+        diesel::insert_into(recovery_codes::table)
+            .values(rows)
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+/// Records that a code from time step `step` was used.
+///
+/// Returns `false` when a code from this or a later step was already used, so each code only
+/// works once.
+pub fn record_step(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    step: i64,
+) -> Result<bool, DieselError> {
+    use crate::schema::two_factor;
+
+    diesel::update(two_factor::table)
+        .filter(two_factor::user_id.eq(user_id))
+        .filter(
+            two_factor::last_used_step
+                .is_null()
+                .or(two_factor::last_used_step.lt(step)),
+        )
+        .set(two_factor::last_used_step.eq(step))
+        .execute(conn)
+        .map(|row_count| row_count > 0)
+}
+
+/// Counts a wrong code entered for `user_id`, whether logging in or turning two-factor login off.
+pub fn fail_attempt(conn: &mut PgConnection, user_id: UserId) -> Result<(), DieselError> {
+    use crate::schema::two_factor;
+
+    diesel::update(two_factor::table)
+        .filter(two_factor::user_id.eq(user_id))
+        .set((
+            two_factor::failed_attempts.eq(two_factor::failed_attempts + 1),
+            two_factor::last_failed_at.eq(Utc::now()),
+        ))
+        .execute(conn)
+        .map(|_| ())
+}
+
+/// Forgets wrong codes once the user proved they have their second factor by logging in.
+pub fn reset_failed_attempts(conn: &mut PgConnection, user_id: UserId) -> Result<(), DieselError> {
+    use crate::schema::two_factor;
+
+    diesel::update(two_factor::table)
+        .filter(two_factor::user_id.eq(user_id))
+        .set((
+            two_factor::failed_attempts.eq(0),
+            two_factor::last_failed_at.eq(None::<DateTime<Utc>>),
+        ))
+        .execute(conn)
+        .map(|_| ())
+}
+
+/// Uses up the recovery code with `code_hash`, returning whether it was valid.
+pub fn use_recovery_code(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    code_hash: &str,
+) -> Result<bool, DieselError> {
+    use crate::schema::recovery_codes;
+
+    diesel::update(recovery_codes::table)
+        .filter(recovery_codes::user_id.eq(user_id))
+        .filter(recovery_codes::code_hash.eq(code_hash))
+        .filter(recovery_codes::used_at.is_null())
+        .set(recovery_codes::used_at.eq(Utc::now()))
+        .execute(conn)
+        .map(|row_count| row_count > 0)
+}
+
+/// Turns off the second factor of `user_id`, removing the app secret and recovery codes.
+pub fn disable(conn: &mut PgConnection, user_id: UserId) -> Result<(), DieselError> {
+    use crate::schema::{recovery_codes, two_factor};
+
+    conn.transaction(|conn| {
+        diesel::delete(recovery_codes::table)
+            .filter(recovery_codes::user_id.eq(user_id))
+            .execute(conn)?;
+        diesel::delete(two_factor::table)
+            .filter(two_factor::user_id.eq(user_id))
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+/// Stores a login which is waiting for the second factor, by the hash of its token.
+pub fn new_pending_login(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<(), DieselError> {
+    use crate::schema::pending_logins;
+
+    conn.transaction(|conn| {
+        diesel::delete(pending_logins::table)
+            .filter(pending_logins::expires_at.le(Utc::now()))
+            .execute(conn)?;
+
+        diesel::insert_into(pending_logins::table)
+            .values((
+                pending_logins::id.eq(Uuid::new_v4()),
+                pending_logins::user_id.eq(user_id),
+                pending_logins::token_hash.eq(token_hash),
+                pending_logins::expires_at.eq(expires_at),
+            ))
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+/// The user of an unexpired pending login with fewer than `max_failed_attempts` wrong codes.
+pub fn get_pending_login(
+    conn: &mut PgConnection,
+    token_hash: &str,
+    max_failed_attempts: i32,
+) -> Result<Option<UserId>, DieselError> {
+    use crate::schema::pending_logins;
+
+    pending_logins::table
+        .filter(pending_logins::token_hash.eq(token_hash))
+        .filter(pending_logins::expires_at.gt(Utc::now()))
+        .filter(pending_logins::failed_attempts.lt(max_failed_attempts))
+        .select(pending_logins::user_id)
+        .get_result(conn)
+        .optional()
+}
+
+/// Counts a wrong code entered for a pending login.
+pub fn fail_pending_login(conn: &mut PgConnection, token_hash: &str) -> Result<(), DieselError> {
+    use crate::schema::pending_logins;
+
+    diesel::update(pending_logins::table)
+        .filter(pending_logins::token_hash.eq(token_hash))
+        .set(pending_logins::failed_attempts.eq(pending_logins::failed_attempts + 1))
+        .execute(conn)
+        .map(|_| ())
+}
+
+/// Removes a pending login once it was completed, so its token can't be used again.
+pub fn finish_pending_login(conn: &mut PgConnection, token_hash: &str) -> Result<(), DieselError> {
+    use crate::schema::pending_logins;
+
+    diesel::delete(pending_logins::table)
+        .filter(pending_logins::token_hash.eq(token_hash))
+        .execute(conn)
+        .map(|_| ())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::test_db::{self, Result};
+    use crate::user::tests::util as test_user;
+
+    #[test]
+    fn enrollment_codes_and_recovery() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "cautious");
+
+        // enrollment
+        assert!(super::begin_enrollment(&mut conn, user.id, "FIRST")?);
+        assert!(super::begin_enrollment(&mut conn, user.id, "SECOND")?);
+        assert!(!super::is_enabled(&mut conn, user.id)?);
+        let codes = vec!["one".to_string(), "two".to_string()];
+        super::confirm_enrollment(&mut conn, user.id, 100, codes)?;
+        assert!(super::is_enabled(&mut conn, user.id)?);
+        assert!(!super::begin_enrollment(&mut conn, user.id, "THIRD")?);
+        let two_factor = super::get(&mut conn, user.id)?.expect("missing two factor");
+        assert_eq!(two_factor.secret, "SECOND");
+
+        // codes can't be replayed
+        assert!(!super::record_step(&mut conn, user.id, 100)?);
+        assert!(super::record_step(&mut conn, user.id, 101)?);
+
+        // wrong codes are counted until the next login
+        super::fail_attempt(&mut conn, user.id)?;
+        super::fail_attempt(&mut conn, user.id)?;
+        let two_factor = super::get(&mut conn, user.id)?.expect("missing two factor");
+        assert_eq!(two_factor.failed_attempts, 2);
+        assert!(two_factor.is_locked_out(2, Duration::minutes(15)));
+        assert!(!two_factor.is_locked_out(3, Duration::minutes(15)));
+        super::reset_failed_attempts(&mut conn, user.id)?;
+        let two_factor = super::get(&mut conn, user.id)?.expect("missing two factor");
+        assert_eq!(two_factor.failed_attempts, 0);
+        assert!(!two_factor.is_locked_out(2, Duration::minutes(15)));
+
+        // recovery codes only work once
+        assert!(super::use_recovery_code(&mut conn, user.id, "one")?);
+        assert!(!super::use_recovery_code(&mut conn, user.id, "one")?);
+        assert!(!super::use_recovery_code(&mut conn, user.id, "three")?);
+
+        super::disable(&mut conn, user.id)?;
+        assert!(!super::is_enabled(&mut conn, user.id)?);
+        assert!(!super::use_recovery_code(&mut conn, user.id, "two")?);
+        Ok(())
+    }
+
+    #[test]
+    fn pending_logins_lock_after_failed_attempts() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "pending");
+        let expires_at = Utc::now() + Duration::minutes(5);
+        super::new_pending_login(&mut conn, user.id, "token", expires_at)?;
+
+        assert_eq!(
+            super::get_pending_login(&mut conn, "token", 2)?,
+            Some(user.id)
+        );
+        super::fail_pending_login(&mut conn, "token")?;
+        super::fail_pending_login(&mut conn, "token")?;
+        assert_eq!(super::get_pending_login(&mut conn, "token", 2)?, None);
+
+        // finished logins are gone
+        super::new_pending_login(&mut conn, user.id, "other", expires_at)?;
+        super::finish_pending_login(&mut conn, "other")?;
+        assert_eq!(super::get_pending_login(&mut conn, "other", 2)?, None);
+        Ok(())
+    }
+}
@@ -1,15 +1,18 @@
+use std::collections::{HashMap, HashSet};
+
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::{PgConnection, RunQueryDsl};
 use password_hash::PasswordHashString;
-use uchat_domain::ids::UserId;
+use uchat_domain::ids::{PostId, UserId};
 use uchat_domain::Username;
 use uchat_endpoint::Update;
 
 use crate::post::DeleteStatus;
-use crate::{DieselError, QueryError};
+use crate::{DieselError, QueryError, TimeCursor};
 
-#[derive(Debug, Queryable)]
+#[derive(Clone, Debug, Queryable, QueryableByName)]
+#[diesel(table_name = crate::schema::users)]
 pub struct User {
     pub id: UserId,
     pub email: Option<String>,
@@ -77,19 +80,30 @@ pub struct UpdateProfileParams {
 struct UpdateProfileParamsInternal {
     pub display_name: Option<Option<String>>,
     pub email: Option<Option<String>>,
+    pub email_confirmed: Option<Option<DateTime<Utc>>>,
     pub password_hash: Option<String>,
     pub profile_image: Option<Option<String>>,
 }
 
+/// Email addresses are stored and looked up trimmed and lowercased.
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Updates a profile. Changing the email address clears its confirmation.
 pub fn update_profile(
     conn: &mut PgConnection,
     query_params: UpdateProfileParams,
 ) -> Result<(), DieselError> {
     use crate::schema::users;
 
-    let update = UpdateProfileParamsInternal {
+    let mut update = UpdateProfileParamsInternal {
         display_name: query_params.display_name.into_nullable(),
-        email: query_params.email.into_nullable(),
+        email: query_params
+            .email
+            .into_nullable()
+            .map(|email| email.map(|email| normalize_email(&email))),
+        email_confirmed: None,
         password_hash: query_params
             .password_hash
             .into_option()
@@ -97,11 +111,56 @@ pub fn update_profile(
         profile_image: query_params.profile_image.into_nullable(),
     };
 
+    conn.transaction(|conn| {
+        let previous_email: Option<String> = users::table
+            .filter(users::id.eq(&query_params.id))
+            .select(users::email)
+            .get_result(conn)?;
+
+        // cleared in the same statement, so a confirmed address is never moved to another one
+        if matches!(&update.email, Some(email) if *email != previous_email) {
+            update.email_confirmed = Some(None);
+        }
+
+        diesel::update(users::table)
+            .filter(users::id.eq(&query_params.id))
+            .set(&update)
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+/// Confirms the email address of `user_id`, provided it is still `email`.
+///
+/// Returns whether the address was confirmed. Fails with a unique violation when another user
+/// already confirmed the address.
+pub fn confirm_email(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    email: &str,
+) -> Result<bool, DieselError> {
+    use crate::schema::users;
+
     diesel::update(users::table)
-        .filter(users::id.eq(&query_params.id))
-        .set(&update)
+        .filter(users::id.eq(user_id))
+        .filter(users::email.eq(normalize_email(email)))
+        .set(users::email_confirmed.eq(Utc::now()))
         .execute(conn)
-        .map(|_| ())
+        .map(|row_count| row_count > 0)
+}
+
+/// Finds the user who confirmed `email`. Unconfirmed addresses never match.
+pub fn find_by_confirmed_email(
+    conn: &mut PgConnection,
+    email: &str,
+) -> Result<Option<User>, DieselError> {
+    use crate::schema::users;
+
+    users::table
+        .filter(users::email.eq(normalize_email(email)))
+        .filter(users::email_confirmed.is_not_null())
+        .get_result(conn)
+        .optional()
 }
 
 pub fn follow(conn: &mut PgConnection, user_id: UserId, follow: UserId) -> Result<(), DieselError> {
@@ -164,8 +223,475 @@ pub fn is_following(
     }
 }
 
+/// Blocks `blocked` on behalf of `user_id`, removing any follows between the two users.
+pub fn block(conn: &mut PgConnection, user_id: UserId, blocked: UserId) -> Result<(), DieselError> {
+    use crate::schema::{blocks, followers};
+
+    conn.transaction(|conn| {
+        diesel::insert_into(blocks::table)
+            .values((blocks::user_id.eq(user_id), blocks::blocked.eq(blocked)))
+            .on_conflict((blocks::user_id, blocks::blocked))
+            .do_nothing()
+            .execute(conn)?;
+
+        diesel::delete(followers::table)
+            .filter(
+                followers::user_id
+                    .eq(user_id)
+                    .and(followers::follows.eq(blocked))
+                    .or(followers::user_id
+                        .eq(blocked)
+                        .and(followers::follows.eq(user_id))),
+            )
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+pub fn unblock(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    blocked: UserId,
+) -> Result<DeleteStatus, DieselError> {
+    let uid = user_id;
+    let bid = blocked;
+    {
+        use crate::schema::blocks::dsl::*;
+        diesel::delete(blocks)
+            .filter(user_id.eq(uid))
+            .filter(blocked.eq(bid))
+            .execute(conn)
+            .map(|row_count| {
+                if row_count > 0 {
+                    DeleteStatus::Deleted
+                } else {
+                    DeleteStatus::NotFound
+                }
+            })
+    }
+}
+
+/// Returns `true` when either user has blocked the other.
+pub fn is_blocked_between(
+    conn: &mut PgConnection,
+    user_a: UserId,
+    user_b: UserId,
+) -> Result<bool, DieselError> {
+    use crate::schema::blocks;
+    use diesel::dsl::{exists, select};
+
+    select(exists(
+        blocks::table.filter(
+            blocks::user_id
+                .eq(user_a)
+                .and(blocks::blocked.eq(user_b))
+                .or(blocks::user_id.eq(user_b).and(blocks::blocked.eq(user_a))),
+        ),
+    ))
+    .get_result(conn)
+}
+
+/// What a mute applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MuteTarget {
+    User(UserId),
+    /// All replies beneath a post.
+    Thread(PostId),
+}
+
+/// Mutes `target` for `user_id` until `expires_at`, or indefinitely. Muting a target again
+/// replaces the previous expiry.
+pub fn mute(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    target: MuteTarget,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<(), DieselError> {
+    use crate::schema::mutes;
+
+    let (muted_user, muted_post) = match target {
+        MuteTarget::User(id) => (Some(id), None),
+        MuteTarget::Thread(id) => (None, Some(id)),
+    };
+
+    conn.transaction(|conn| {
+        unmute(conn, user_id, target)?;
+        diesel::insert_into(mutes::table)
+            .values((
+                mutes::id.eq(uuid::Uuid::new_v4()),
+                mutes::user_id.eq(user_id),
+                mutes::muted_user.eq(muted_user),
+                mutes::muted_post.eq(muted_post),
+                mutes::expires_at.eq(expires_at),
+            ))
+            .execute(conn)
+            .map(|_| ())
+    })
+}
+
+pub fn unmute(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    target: MuteTarget,
+) -> Result<DeleteStatus, DieselError> {
+    use crate::schema::mutes;
+
+    let query = diesel::delete(mutes::table).filter(mutes::user_id.eq(user_id));
+    let row_count = match target {
+        MuteTarget::User(id) => query.filter(mutes::muted_user.eq(id)).execute(conn)?,
+        MuteTarget::Thread(id) => query.filter(mutes::muted_post.eq(id)).execute(conn)?,
+    };
+    Ok(match row_count {
+        0 => DeleteStatus::NotFound,
+        _ => DeleteStatus::Deleted,
+    })
+}
+
+/// Returns when the mute of `target` by `user_id` ends. The outer `Option` is `None` when
+/// `target` isn't muted, and the inner one is `None` when it is muted indefinitely.
+pub fn get_mute(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    target: MuteTarget,
+) -> Result<Option<Option<DateTime<Utc>>>, DieselError> {
+    use crate::schema::mutes;
+
+    let query = mutes::table
+        .filter(mutes::user_id.eq(user_id))
+        .filter(
+            mutes::expires_at
+                .is_null()
+                .or(mutes::expires_at.gt(Utc::now())),
+        )
+        .select(mutes::expires_at)
+        .into_boxed();
+    let query = match target {
+        MuteTarget::User(id) => query.filter(mutes::muted_user.eq(id)),
+        MuteTarget::Thread(id) => query.filter(mutes::muted_post.eq(id)),
+    };
+    query.get_result(conn).optional()
+}
+
+/// Escapes `LIKE` wildcards so `text` only matches literally.
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Finds users by handle or display name.
+///
+/// Prefix matches rank first, followed by fuzzy (trigram) matches ordered by similarity.
+pub fn search(conn: &mut PgConnection, query: &str, limit: i64) -> Result<Vec<User>, DieselError> {
+    use diesel::sql_types::{BigInt, Text};
+
+    diesel::sql_query(
+        "SELECT users.*
+        FROM public.users
+        WHERE handle ILIKE $2 OR display_name ILIKE $2 OR handle % $1 OR display_name % $1
+        ORDER BY
+            (handle ILIKE $2 OR coalesce(display_name, '') ILIKE $2) DESC,
+            greatest(similarity(handle, $1), similarity(coalesce(display_name, ''), $1)) DESC,
+            handle
+        LIMIT $3",
+    )
+    .bind::<Text, _>(query)
+    .bind::<Text, _>(format!("{}%", escape_like(query)))
+    .bind::<BigInt, _>(limit)
+    .load(conn)
+}
+
+/// Returns the users among `user_ids` which `user_id` follows.
+pub fn following_among(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    user_ids: &[UserId],
+) -> Result<HashSet<UserId>, DieselError> {
+    let uid = user_id;
+    {
+        use crate::schema::followers::dsl::*;
+        followers
+            .filter(user_id.eq(uid))
+            .filter(follows.eq_any(user_ids))
+            .select(follows)
+            .load::<UserId>(conn)
+            .map(|ids| ids.into_iter().collect())
+    }
+}
+
+pub fn get_many(conn: &mut PgConnection, user_ids: &[UserId]) -> Result<Vec<User>, DieselError> {
+    use crate::schema::users::dsl::*;
+    users.filter(id.eq_any(user_ids)).load(conn)
+}
+
+/// A user in a follower or following list, along with when the follow started.
+#[derive(Clone, Debug)]
+pub struct Follow {
+    pub user: User,
+    pub since: DateTime<Utc>,
+}
+
+impl From<&Follow> for TimeCursor {
+    fn from(follow: &Follow) -> Self {
+        TimeCursor::new(follow.since, follow.user.id.into_inner())
+    }
+}
+
+/// Pages through the users following `user_id`, most recent follows first.
+pub fn get_followers(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Follow>, DieselError> {
+    use crate::schema::{followers, users};
+
+    let mut query = followers::table
+        .inner_join(users::table.on(users::id.eq(followers::user_id)))
+        .filter(followers::follows.eq(user_id))
+        .select((users::all_columns, followers::created_at))
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(
+            followers::created_at
+                .lt(cursor.time)
+                .or(followers::created_at
+                    .eq(cursor.time)
+                    .and(followers::user_id.lt(cursor.id))),
+        );
+    }
+
+    query
+        .order((followers::created_at.desc(), followers::user_id.desc()))
+        .limit(limit)
+        .load::<(User, DateTime<Utc>)>(conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(user, since)| Follow { user, since })
+                .collect()
+        })
+}
+
+/// Pages through the users that `user_id` follows, most recent follows first.
+pub fn get_following(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Follow>, DieselError> {
+    use crate::schema::{followers, users};
+
+    let mut query = followers::table
+        .inner_join(users::table.on(users::id.eq(followers::follows)))
+        .filter(followers::user_id.eq(user_id))
+        .select((users::all_columns, followers::created_at))
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(
+            followers::created_at
+                .lt(cursor.time)
+                .or(followers::created_at
+                    .eq(cursor.time)
+                    .and(followers::follows.lt(cursor.id))),
+        );
+    }
+
+    query
+        .order((followers::created_at.desc(), followers::follows.desc()))
+        .limit(limit)
+        .load::<(User, DateTime<Utc>)>(conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(user, since)| Follow { user, since })
+                .collect()
+        })
+}
+
+/// A user blocked by another, along with when the block started.
+#[derive(Clone, Debug)]
+pub struct Blocked {
+    pub user: User,
+    pub since: DateTime<Utc>,
+}
+
+impl From<&Blocked> for TimeCursor {
+    fn from(blocked: &Blocked) -> Self {
+        TimeCursor::new(blocked.since, blocked.user.id.into_inner())
+    }
+}
+
+/// Pages through the users that `user_id` has blocked, most recent blocks first.
+pub fn get_blocked(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    after: Option<TimeCursor>,
+    limit: i64,
+) -> Result<Vec<Blocked>, DieselError> {
+    use crate::schema::{blocks, users};
+
+    let mut query = blocks::table
+        .inner_join(users::table.on(users::id.eq(blocks::blocked)))
+        .filter(blocks::user_id.eq(user_id))
+        .select((users::all_columns, blocks::created_at))
+        .into_boxed();
+
+    if let Some(cursor) = after {
+        query = query.filter(
+            blocks::created_at.lt(cursor.time).or(blocks::created_at
+                .eq(cursor.time)
+                .and(blocks::blocked.lt(cursor.id))),
+        );
+    }
+
+    query
+        .order((blocks::created_at.desc(), blocks::blocked.desc()))
+        .limit(limit)
+        .load::<(User, DateTime<Utc>)>(conn)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(user, since)| Blocked { user, since })
+                .collect()
+        })
+}
+
+/// A user along with the relationship to the user viewing them.
+#[derive(Clone, Debug)]
+pub struct UserInfo {
+    pub user: User,
+    pub am_following: bool,
+    pub follows_me: bool,
+    pub am_blocking: bool,
+    pub am_muting: bool,
+    pub follower_count: i64,
+    pub following_count: i64,
+    /// Published public posts; scheduled posts and direct messages are not counted.
+    pub post_count: i64,
+}
+
+/// Loads counts and viewer-specific information for `users` in a fixed number of queries.
+///
+/// The returned infos are in the same order as `users`.
+pub fn hydrate(
+    conn: &mut PgConnection,
+    users: Vec<User>,
+    viewer: Option<UserId>,
+) -> Result<Vec<UserInfo>, DieselError> {
+    use crate::schema::{blocks, followers, mutes, posts};
+    use diesel::dsl::count_star;
+
+    let user_ids: Vec<UserId> = users.iter().map(|user| user.id).collect();
+
+    let (following, followers_of_viewer) = match viewer {
+        Some(viewer) => (
+            following_among(conn, viewer, &user_ids)?,
+            followers::table
+                .filter(followers::follows.eq(viewer))
+                .filter(followers::user_id.eq_any(&user_ids))
+                .select(followers::user_id)
+                .load::<UserId>(conn)?
+                .into_iter()
+                .collect(),
+        ),
+        None => (HashSet::new(), HashSet::new()),
+    };
+
+    let blocking: HashSet<UserId> = match viewer {
+        Some(viewer) => blocks::table
+            .filter(blocks::user_id.eq(viewer))
+            .filter(blocks::blocked.eq_any(&user_ids))
+            .select(blocks::blocked)
+            .load::<UserId>(conn)?
+            .into_iter()
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    let muting: HashSet<UserId> = match viewer {
+        Some(viewer) => mutes::table
+            .filter(mutes::user_id.eq(viewer))
+            .filter(mutes::muted_user.eq_any(&user_ids))
+            .filter(
+                mutes::expires_at
+                    .is_null()
+                    .or(mutes::expires_at.gt(Utc::now())),
+            )
+            .select(mutes::muted_user.assume_not_null())
+            .load::<UserId>(conn)?
+            .into_iter()
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    let follower_counts: HashMap<UserId, i64> = followers::table
+        .filter(followers::follows.eq_any(&user_ids))
+        .group_by(followers::follows)
+        .select((followers::follows, count_star()))
+        .load::<(UserId, i64)>(conn)?
+        .into_iter()
+        .collect();
+
+    let following_counts: HashMap<UserId, i64> = followers::table
+        .filter(followers::user_id.eq_any(&user_ids))
+        .group_by(followers::user_id)
+        .select((followers::user_id, count_star()))
+        .load::<(UserId, i64)>(conn)?
+        .into_iter()
+        .collect();
+
+    let post_counts: HashMap<UserId, i64> = posts::table
+        .filter(posts::user_id.eq_any(&user_ids))
+        .filter(posts::time_posted.lt(Utc::now()))
+        .filter(posts::direct_message_to.is_null())
+        .group_by(posts::user_id)
+        .select((posts::user_id, count_star()))
+        .load::<(UserId, i64)>(conn)?
+        .into_iter()
+        .collect();
+
+    let count = |counts: &HashMap<UserId, i64>, id: &UserId| counts.get(id).copied().unwrap_or(0);
+
+    Ok(users
+        .into_iter()
+        .map(|user| UserInfo {
+            am_following: following.contains(&user.id),
+            follows_me: followers_of_viewer.contains(&user.id),
+            am_blocking: blocking.contains(&user.id),
+            am_muting: muting.contains(&user.id),
+            follower_count: count(&follower_counts, &user.id),
+            following_count: count(&following_counts, &user.id),
+            post_count: count(&post_counts, &user.id),
+            user,
+        })
+        .collect())
+}
+
+/// Like [`hydrate`], for users which are only known by id.
+///
+/// Ids which do not belong to any user are skipped.
+pub fn hydrate_ids(
+    conn: &mut PgConnection,
+    user_ids: &[UserId],
+    viewer: Option<UserId>,
+) -> Result<HashMap<UserId, UserInfo>, DieselError> {
+    let users = get_many(conn, user_ids)?;
+    Ok(hydrate(conn, users, viewer)?
+        .into_iter()
+        .map(|info| (info.user.id, info))
+        .collect())
+}
+
 #[cfg(test)]
 pub mod tests {
+    use crate::test_db::{self, Result};
+    use util as test_user;
+
     pub mod util {
         use diesel::PgConnection;
 
@@ -179,4 +705,218 @@ pub mod tests {
             user_query::get(conn, id).unwrap()
         }
     }
+
+    #[test]
+    fn search_matches_prefix_and_similar_handles() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let prefixed = test_user::new_user(&mut conn, "zebrafish_fan");
+        let similar = test_user::new_user(&mut conn, "zebrafisch");
+        test_user::new_user(&mut conn, "unrelated");
+
+        // search
+        let found = super::search(&mut conn, "zebrafish", 10)?;
+        let ids: Vec<_> = found.iter().map(|user| user.id).collect();
+        assert_eq!(ids.first(), Some(&prefixed.id));
+        assert!(ids.contains(&similar.id));
+        assert_eq!(ids.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn lists_follows_and_counts_them() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let star = test_user::new_user(&mut conn, "star");
+        let fan1 = test_user::new_user(&mut conn, "fan1");
+        let fan2 = test_user::new_user(&mut conn, "fan2");
+
+        super::follow(&mut conn, fan1.id, star.id)?;
+        super::follow(&mut conn, fan2.id, star.id)?;
+        super::follow(&mut conn, star.id, fan1.id)?;
+
+        // lists, paginated
+        let first = super::get_followers(&mut conn, star.id, None, 1)?;
+        assert_eq!(first.len(), 1);
+        let cursor = first.last().map(crate::TimeCursor::from);
+        let second = super::get_followers(&mut conn, star.id, cursor, 1)?;
+        assert_eq!(second.len(), 1);
+        let mut ids = vec![first[0].user.id, second[0].user.id];
+        ids.sort();
+        let mut expected = vec![fan1.id, fan2.id];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        let following = super::get_following(&mut conn, star.id, None, 10)?;
+        assert_eq!(following.len(), 1);
+        assert_eq!(following[0].user.id, fan1.id);
+
+        // counts and relationship to the viewer
+        let info = super::hydrate(&mut conn, vec![star], Some(fan1.id))?.remove(0);
+        assert_eq!(info.follower_count, 2);
+        assert_eq!(info.following_count, 1);
+        assert_eq!(info.post_count, 0);
+        assert!(info.am_following);
+        assert!(info.follows_me);
+        Ok(())
+    }
+
+    #[test]
+    fn blocking_removes_follows_in_both_directions() -> Result<()> {
+        // setup
+        let mut conn = test_db::new_connection();
+        let alice = test_user::new_user(&mut conn, "alice");
+        let troll = test_user::new_user(&mut conn, "troll");
+
+        super::follow(&mut conn, alice.id, troll.id)?;
+        super::follow(&mut conn, troll.id, alice.id)?;
+
+        // block
+        super::block(&mut conn, alice.id, troll.id)?;
+        assert!(!super::is_following(&mut conn, alice.id, troll.id)?);
+        assert!(!super::is_following(&mut conn, troll.id, alice.id)?);
+        assert!(super::is_blocked_between(&mut conn, alice.id, troll.id)?);
+        assert!(super::is_blocked_between(&mut conn, troll.id, alice.id)?);
+
+        let blocked = super::get_blocked(&mut conn, alice.id, None, 10)?;
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].user.id, troll.id);
+        assert!(super::get_blocked(&mut conn, troll.id, None, 10)?.is_empty());
+
+        let info = super::hydrate(&mut conn, vec![troll.clone()], Some(alice.id))?.remove(0);
+        assert!(info.am_blocking);
+
+        // unblock
+        super::unblock(&mut conn, alice.id, troll.id)?;
+        assert!(!super::is_blocked_between(&mut conn, alice.id, troll.id)?);
+        Ok(())
+    }
+
+    #[test]
+    fn mutes_expire_and_can_be_replaced() -> Result<()> {
+        use super::MuteTarget;
+        use chrono::{Duration, Utc};
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let me = test_user::new_user(&mut conn, "me");
+        let loud = test_user::new_user(&mut conn, "loud");
+        let target = MuteTarget::User(loud.id);
+
+        // expired mutes are ignored
+        super::mute(
+            &mut conn,
+            me.id,
+            target,
+            Some(Utc::now() - Duration::hours(1)),
+        )?;
+        assert_eq!(super::get_mute(&mut conn, me.id, target)?, None);
+
+        // muting again replaces the expiry
+        super::mute(&mut conn, me.id, target, None)?;
+        assert_eq!(super::get_mute(&mut conn, me.id, target)?, Some(None));
+        let info = super::hydrate(&mut conn, vec![loud.clone()], Some(me.id))?.remove(0);
+        assert!(info.am_muting);
+
+        // unmute
+        super::unmute(&mut conn, me.id, target)?;
+        assert_eq!(super::get_mute(&mut conn, me.id, target)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_like_wildcards() {
+        assert_eq!(super::escape_like("100%_a\\b"), "100\\%\\_a\\\\b");
+    }
+
+    #[test]
+    fn emails_are_confirmed_until_changed() -> Result<()> {
+        use uchat_endpoint::Update;
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let user = test_user::new_user(&mut conn, "correspondent");
+        let set_email = |conn: &mut diesel::PgConnection, email: &str| {
+            super::update_profile(
+                conn,
+                super::UpdateProfileParams {
+                    id: user.id,
+                    display_name: Update::NoChange,
+                    email: Update::Change(email.to_string()),
+                    password_hash: Update::NoChange,
+                    profile_image: Update::NoChange,
+                },
+            )
+        };
+        set_email(&mut conn, "first@example.com")?;
+
+        // unconfirmed addresses can't be used
+        assert!(super::find_by_confirmed_email(&mut conn, "first@example.com")?.is_none());
+        let stale = super::confirm_email(&mut conn, user.id, "other@example.com")?;
+        assert!(!stale);
+        let confirmed = super::confirm_email(&mut conn, user.id, "first@example.com")?;
+        assert!(confirmed);
+        let found = super::find_by_confirmed_email(&mut conn, "first@example.com")?;
+        assert_eq!(found.map(|found| found.id), Some(user.id));
+
+        // saving the same address keeps it confirmed
+        set_email(&mut conn, "first@example.com")?;
+        assert!(super::get(&mut conn, user.id)?.email_confirmed.is_some());
+
+        // a new address needs confirming again
+        set_email(&mut conn, "second@example.com")?;
+        assert!(super::get(&mut conn, user.id)?.email_confirmed.is_none());
+        assert!(super::find_by_confirmed_email(&mut conn, "second@example.com")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn confirmed_emails_are_unique_ignoring_case() -> Result<()> {
+        use diesel::result::DatabaseErrorKind;
+        use diesel::Connection;
+        use uchat_endpoint::Update;
+
+        // setup
+        let mut conn = test_db::new_connection();
+        let first = test_user::new_user(&mut conn, "first");
+        let second = test_user::new_user(&mut conn, "second");
+        for (user, email) in [
+            (&first, " Shared@Example.com"),
+            (&second, "shared@example.COM"),
+        ] {
+            super::update_profile(
+                &mut conn,
+                super::UpdateProfileParams {
+                    id: user.id,
+                    display_name: Update::NoChange,
+                    email: Update::Change(email.to_string()),
+                    password_hash: Update::NoChange,
+                    profile_image: Update::NoChange,
+                },
+            )?;
+        }
+        assert_eq!(
+            super::get(&mut conn, first.id)?.email.as_deref(),
+            Some("shared@example.com")
+        );
+
+        // unconfirmed addresses can be shared, but only one user can confirm them
+        assert!(super::confirm_email(
+            &mut conn,
+            first.id,
+            "SHARED@example.com"
+        )?);
+        let found = super::find_by_confirmed_email(&mut conn, " shared@EXAMPLE.com ")?;
+        assert_eq!(found.map(|found| found.id), Some(first.id));
+        let taken =
+            conn.transaction(|conn| super::confirm_email(conn, second.id, "shared@example.com"));
+        assert!(matches!(
+            taken,
+            Err(super::DieselError::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                _
+            ))
+        ));
+        Ok(())
+    }
 }
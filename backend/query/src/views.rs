@@ -0,0 +1,28 @@
+//! Materialized views. These are not emitted by `diesel print-schema`, so they are declared here
+//! instead of in `schema.rs`.
+
+use diesel::prelude::*;
+
+use crate::schema::{blocks, mutes, posts};
+use crate::DieselError;
+
+diesel::table! {
+    trending_scores (post_id) {
+        post_id -> Uuid,
+        time_posted -> Timestamptz,
+        score -> Float8,
+    }
+}
+
+diesel::joinable!(trending_scores -> posts (post_id));
+diesel::allow_tables_to_appear_in_same_query!(trending_scores, posts);
+// trending posts leave out authors blocked by, blocking or muted by the viewer
+diesel::allow_tables_to_appear_in_same_query!(trending_scores, blocks);
+diesel::allow_tables_to_appear_in_same_query!(trending_scores, mutes);
+
+/// Recomputes trending scores without blocking readers of the view.
+pub fn refresh_trending(conn: &mut PgConnection) -> Result<(), DieselError> {
+    diesel::sql_query("REFRESH MATERIALIZED VIEW CONCURRENTLY trending_scores")
+        .execute(conn)
+        .map(|_| ())
+}
@@ -0,0 +1,62 @@
+//! Maintenance jobs which run periodically alongside the API server.
+
+use std::time::Duration;
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use uchat_query::{AsyncConnection, AsyncConnectionPool, DieselError};
+
+/// Runs `job` every `period` for as long as the server is running.
+///
+/// Failures are logged and the job is attempted again on the next tick.
+pub fn spawn_periodic<F>(
+    name: &'static str,
+    db_pool: AsyncConnectionPool,
+    period: Duration,
+    job: F,
+) -> JoinHandle<()>
+where
+    F: Fn(&mut AsyncConnection<'_>) -> Result<(), DieselError> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let mut conn = match db_pool.get().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::error!(target: "uchat_server", job = name, err = %e, "failed to acquire database connection");
+                    continue;
+                }
+            };
+
+            // jobs run synchronous queries which may take a while
+            match tokio::task::block_in_place(|| job(&mut conn)) {
+                Ok(()) => {
+                    tracing::debug!(target: "uchat_server", job = name, "background job complete")
+                }
+                Err(e) => {
+                    tracing::error!(target: "uchat_server", job = name, err = %e, "background job failed")
+                }
+            }
+        }
+    })
+}
+
+/// Periodically recomputes trending post scores.
+pub fn spawn_trending_refresh(db_pool: AsyncConnectionPool, period: Duration) -> JoinHandle<()> {
+    spawn_periodic("refresh trending", db_pool, period, |conn| {
+        uchat_query::views::refresh_trending(conn)
+    })
+}
+
+/// Periodically deletes sessions which have expired.
+pub fn spawn_session_sweep(db_pool: AsyncConnectionPool, period: Duration) -> JoinHandle<()> {
+    spawn_periodic("sweep sessions", db_pool, period, |conn| {
+        let deleted = uchat_query::session::delete_expired(conn)?;
+        tracing::debug!(target: "uchat_server", deleted, "expired sessions swept");
+        Ok(())
+    })
+}
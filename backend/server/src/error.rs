@@ -11,6 +11,15 @@ pub struct ApiError {
     pub err: color_eyre::Report,
 }
 
+impl ApiError {
+    pub fn new<T: Into<String>>(code: StatusCode, msg: T) -> Self {
+        Self {
+            code: Some(code),
+            err: color_eyre::Report::new(uchat_endpoint::RequestFailed { msg: msg.into() }),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ServerError {
     #[error("Login failed")]
@@ -1,12 +1,21 @@
-use std::str::FromStr;
-
-use axum::{async_trait, extract::FromRequestParts, Extension, Json, RequestPartsExt};
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    Extension, Json, RequestPartsExt,
+};
+use chrono::{DateTime, Duration, Utc};
 use hyper::{header, http::request::Parts, StatusCode};
-use uchat_domain::ids::{SessionId, UserId};
+use uchat_domain::ids::{DeviceId, SessionId, UserId};
 use uchat_endpoint::RequestFailed;
-use uchat_query::OwnedAsyncConnection;
+use uchat_query::{session::FingerPrint, OwnedAsyncConnection};
 
-use crate::AppState;
+use crate::{AppState, SessionBinding};
 
 pub struct DbConnection(pub OwnedAsyncConnection);
 
@@ -30,10 +39,117 @@ where
     }
 }
 
+/// Details of the device which sent a request.
+#[derive(Clone, Debug, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    /// The network the request came from, coarse enough to survive address changes within it.
+    pub network: Option<String>,
+    pub device_id: Option<DeviceId>,
+}
+
+impl ClientInfo {
+    fn from_parts(parts: &Parts) -> Self {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|header| header.to_str().ok())
+            .map(str::to_owned);
+
+        let network = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| coarse_network(addr.ip()));
+
+        let device_id = parts
+            .headers
+            .get(header::COOKIE)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|cookies| uchat_cookie::get_from_str(cookies, uchat_cookie::DEVICE_ID))
+            .and_then(|id| DeviceId::from_str(id).ok());
+
+        Self {
+            user_agent,
+            network,
+            device_id,
+        }
+    }
+
+    /// Fingerprint stored with sessions created by this device.
+    pub fn fingerprint(&self) -> FingerPrint {
+        serde_json::json!({
+            "user_agent": self.user_agent,
+            "device_id": self.device_id,
+            "network": self.network,
+        })
+        .into()
+    }
+
+    /// Whether `fingerprint` could belong to this device.
+    ///
+    /// Details which weren't recorded in the fingerprint are not compared, so sessions created
+    /// before fingerprints were collected keep working.
+    pub fn matches(&self, fingerprint: &FingerPrint) -> bool {
+        let device_id = self.device_id.map(|id| id.to_string());
+        [
+            ("user_agent", self.user_agent.as_deref()),
+            ("device_id", device_id.as_deref()),
+            ("network", self.network.as_deref()),
+        ]
+        .iter()
+        .all(|&(key, current)| match fingerprint.get(key) {
+            Some(recorded) => current == Some(recorded),
+            None => true,
+        })
+    }
+}
+
+/// The /24 (IPv4) or /48 (IPv6) network containing `ip`.
+fn coarse_network(ip: IpAddr) -> String {
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        v4 => v4,
+    };
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            format!("{a}.{b}.{c}.0/24")
+        }
+        IpAddr::V6(v6) => {
+            let [a, b, c, ..] = v6.segments();
+            format!("{a:x}:{b:x}:{c:x}::/48")
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(parts))
+    }
+}
+
+/// How long a session lasts once created or renewed.
+pub fn session_duration() -> Duration {
+    Duration::weeks(3)
+}
+
+/// Sessions used when they have less than this long left are renewed.
+fn renewal_window() -> Duration {
+    Duration::weeks(1)
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct UserSession {
     pub user_id: UserId,
     pub session_id: SessionId,
+    /// New expiry of the session, when it was renewed by this request.
+    pub renewed_until: Option<DateTime<Utc>>,
 }
 
 #[async_trait]
@@ -77,11 +193,35 @@ where
             .verify(session_id.as_uuid().as_bytes(), session_signature)
             .map_err(|_| unauthorized())?;
 
-        let session = uchat_query::session::get(&mut conn, session_id)
+        let mut session = uchat_query::session::get(&mut conn, session_id)
             .ok()
             .flatten()
             .ok_or_else(unauthorized)?;
 
+        if state.session_binding != SessionBinding::Off
+            && !ClientInfo::from_parts(parts).matches(&session.fingerprint)
+        {
+            tracing::warn!(
+                user_id = session.user_id.into_inner().to_string(),
+                session_id = session.id.to_string(),
+                "session used from a different device"
+            );
+            if state.session_binding == SessionBinding::Reject {
+                return Err(unauthorized());
+            }
+        }
+
+        let now = Utc::now();
+        let renewed_until = match session.expires_at - now < renewal_window() {
+            true => {
+                let expires_at = now + session_duration();
+                session = uchat_query::session::renew(&mut conn, session.id, expires_at)
+                    .map_err(|_| unauthorized())?;
+                Some(session.expires_at)
+            }
+            false => None,
+        };
+
         tracing::info!(
             user_id = session.user_id.into_inner().to_string(),
             "user logged in"
@@ -90,6 +230,7 @@ where
         Ok(Self {
             user_id: session.user_id,
             session_id: session.id,
+            renewed_until,
         })
     }
 }
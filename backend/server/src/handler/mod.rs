@@ -9,15 +9,17 @@ use axum::{
 };
 use hyper::{body::Bytes, header, StatusCode};
 use serde::Deserialize;
-use uchat_domain::ids::ImageId;
+use uchat_domain::ids::{ImageId, UserId};
+use uchat_endpoint::{Cursor, Page};
 use uuid::Uuid;
 
 use crate::{
-    error::ApiResult,
-    extractor::{DbConnection, UserSession},
+    error::{ApiError, ApiResult},
+    extractor::{ClientInfo, DbConnection, UserSession},
     AppState,
 };
 
+pub mod notification;
 pub mod post;
 pub mod user;
 
@@ -30,19 +32,21 @@ pub trait PublicApiRequest {
     async fn process_request(
         self,
         conn: DbConnection,
+        client: ClientInfo,
         state: AppState,
     ) -> ApiResult<Self::Response>;
 }
 
 pub async fn with_public_handler<'a, Req>(
     conn: DbConnection,
+    client: ClientInfo,
     State(state): State<AppState>,
     Json(payload): Json<Req>,
 ) -> ApiResult<Req::Response>
 where
     Req: PublicApiRequest + Deserialize<'a>,
 {
-    payload.process_request(conn, state).await
+    payload.process_request(conn, client, state).await
 }
 
 #[async_trait]
@@ -57,16 +61,62 @@ pub trait AuthorizedApiRequest {
     ) -> ApiResult<Self::Response>;
 }
 
+/// Runs an authorized request. Sessions renewed while authorizing the request report their new
+/// expiry in the [`uchat_cookie::SESSION_EXPIRES_HEADER`] header, whether or not the request
+/// succeeds.
 pub async fn with_handler<'a, Req>(
     conn: DbConnection,
     session: UserSession,
     State(state): State<AppState>,
     Json(payload): Json<Req>,
-) -> ApiResult<Req::Response>
+) -> Response
 where
     Req: AuthorizedApiRequest + Deserialize<'a>,
 {
-    payload.process_request(conn, session, state).await
+    let renewed_until = session.renewed_until;
+    let response = match payload.process_request(conn, session, state).await {
+        Ok(response) => response.into_response(),
+        Err(e) => e.into_response(),
+    };
+    match renewed_until {
+        Some(expires_at) => (
+            [(
+                uchat_cookie::SESSION_EXPIRES_HEADER,
+                expires_at.to_rfc3339(),
+            )],
+            response,
+        )
+            .into_response(),
+        None => response,
+    }
+}
+
+/// Decodes the cursor of a requested page, rejecting cursors which were not issued by the server.
+pub fn decode_cursor<C, F>(page: &Page, decode: F) -> ApiResult<Option<C>>
+where
+    F: FnOnce(&Cursor) -> Option<C>,
+{
+    match page.cursor {
+        Some(ref cursor) => decode(cursor)
+            .map(Some)
+            .ok_or_else(|| ApiError::new(StatusCode::BAD_REQUEST, "invalid cursor")),
+        None => Ok(None),
+    }
+}
+
+/// Rejects interactions between two users when either of them has blocked the other.
+pub fn ensure_not_blocked(
+    conn: &mut uchat_query::AsyncConnection,
+    user_id: UserId,
+    other: UserId,
+) -> ApiResult<()> {
+    if uchat_query::user::is_blocked_between(conn, user_id, other)? {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "cannot interact with this user",
+        ));
+    }
+    Ok(())
 }
 
 pub async fn save_image<T: AsRef<[u8]>>(id: ImageId, data: T) -> ApiResult<()> {
@@ -80,6 +130,19 @@ pub async fn save_image<T: AsRef<[u8]>>(id: ImageId, data: T) -> ApiResult<()> {
     Ok(())
 }
 
+/// Removes a stored image. Images which were already removed are ignored.
+pub async fn delete_image(id: ImageId) -> ApiResult<()> {
+    use tokio::fs;
+
+    let mut path = PathBuf::from(USER_CONTENT_DIR);
+    path.push(id.to_string());
+    match fs::remove_file(&path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 pub async fn load_image(Path(img_id): Path<Uuid>) -> ApiResult<Response<Full<Bytes>>> {
     use tokio::fs;
 
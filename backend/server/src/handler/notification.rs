@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use axum::{async_trait, Json};
+use hyper::StatusCode;
+use uchat_endpoint::notification::{
+    ListNotifications, ListNotificationsOk, MarkNotificationsRead, MarkNotificationsReadOk,
+    Notification, UnreadCount, UnreadCountOk,
+};
+use uchat_query::{notification::NewNotification, TimeCursor};
+
+use crate::{
+    error::ApiResult,
+    extractor::{DbConnection, UserSession},
+    AppState,
+};
+
+use super::{decode_cursor, AuthorizedApiRequest};
+
+/// Most actors listed with a single grouped notification.
+const MAX_NOTIFICATION_ACTORS: i32 = 3;
+
+/// Stores a notification. Failures are only logged, since the action which caused the
+/// notification already succeeded.
+pub fn notify(conn: &mut uchat_query::AsyncConnection, notification: NewNotification) {
+    let kind = notification.kind;
+    if let Err(e) = uchat_query::notification::notify(conn, notification) {
+        tracing::error!(err = %e, ?kind, "failed to store notification");
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ListNotifications {
+    type Response = (StatusCode, Json<ListNotificationsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let groups = uchat_query::notification::list(
+            &mut conn,
+            session.user_id,
+            after,
+            self.page.size(),
+            MAX_NOTIFICATION_ACTORS,
+        )?;
+        let next_cursor = match groups.len() as i64 == self.page.size() {
+            true => groups.last().map(|group| TimeCursor::from(group).encode()),
+            false => None,
+        };
+
+        let mut actor_ids: Vec<_> = groups
+            .iter()
+            .flat_map(|group| group.actors.iter().copied())
+            .collect();
+        actor_ids.sort();
+        actor_ids.dedup();
+        let actors: HashMap<_, _> =
+            uchat_query::user::hydrate_ids(&mut conn, &actor_ids, Some(session.user_id))?
+                .into_iter()
+                .map(|(id, info)| (id, super::user::to_public(info)))
+                .collect();
+
+        let notifications = groups
+            .into_iter()
+            .map(|group| Notification {
+                kind: group.kind,
+                post_id: group.post_id,
+                actors: group
+                    .actors
+                    .iter()
+                    .filter_map(|id| actors.get(id).cloned())
+                    .collect(),
+                actor_count: group.actor_count,
+                latest: group.latest,
+                unread: group.unread,
+            })
+            .collect();
+
+        Ok((
+            StatusCode::OK,
+            Json(ListNotificationsOk {
+                notifications,
+                next_cursor,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for MarkNotificationsRead {
+    type Response = (StatusCode, Json<MarkNotificationsReadOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        uchat_query::notification::mark_read(&mut conn, session.user_id, self.until)?;
+        let unread = uchat_query::notification::unread_count(&mut conn, session.user_id)?;
+
+        Ok((StatusCode::OK, Json(MarkNotificationsReadOk { unread })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for UnreadCount {
+    type Response = (StatusCode, Json<UnreadCountOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let unread = uchat_query::notification::unread_count(&mut conn, session.user_id)?;
+
+        Ok((StatusCode::OK, Json(UnreadCountOk { unread })))
+    }
+}
@@ -1,31 +1,67 @@
+use std::collections::HashMap;
+
 use axum::{async_trait, Json};
 use chrono::Utc;
 use hyper::StatusCode;
-use uchat_domain::{ids::ImageId, Username};
+use uchat_domain::{
+    ids::{ImageId, PollChoiceId, UserId},
+    Username,
+};
 use uchat_endpoint::{
     app_url::{self, user_content},
+    notification::NotificationKind,
     post::{
         Bookmark, BookmarkAction, BookmarkOk, BookmarkedPosts, BookmarkedPostsOk, Boost,
-        BoostAction, BoostOk, Content, HomePosts, HomePostsOk, ImageKind, LikeStatus, LikedPosts,
-        LikedPostsOk, NewPost, NewPostOk, PublicPost, React, ReactOk, TrendingPosts,
-        TrendingPostsOk, Vote, VoteOk,
+        BoostAction, BoostOk, CancelScheduledPost, CancelScheduledPostOk, Content,
+        ConversationPreview, DeletePost, DeletePostOk, EditPost, EditPostOk, EmojiCount,
+        EmojiReact, EmojiReactOk, GetConversation, GetConversationOk, GetPostRevisions,
+        GetPostRevisionsOk, GetReactionEmojis, GetReactionEmojisOk, GetThread, GetThreadOk,
+        HomePosts, HomePostsOk, ImageKind, LikeStatus, LikedPosts, LikedPostsOk, ListConversations,
+        ListConversationsOk, ListScheduledPosts, ListScheduledPostsOk, MentionedPosts,
+        MentionedPostsOk, NewPost, NewPostOk, PostRevision, PostSearchResult, PublicPost, React,
+        ReactOk, ReschedulePost, ReschedulePostOk, SearchPosts, SearchPostsOk, TagCount,
+        TagTimeline, TagTimelineOk, ThreadReply, TrendingPosts, TrendingPostsOk, TrendingTags,
+        TrendingTagsOk, Vote, VoteCast, VoteOk,
     },
-    RequestFailed,
+    Cursor, Page, RequestFailed,
 };
 use uchat_query::{
-    post::{AggregatePostInfo, Post},
-    AsyncConnection,
+    notification::NewNotification,
+    post::{AggregatePostInfo, Post, PostInfo, SavedPost},
+    AsyncConnection, ScoreCursor, TimeCursor,
 };
+use url::Url;
 
 use crate::{
     error::{ApiError, ApiResult},
     extractor::{DbConnection, UserSession},
-    handler::save_image,
+    handler::{decode_cursor, delete_image, ensure_not_blocked, notification::notify, save_image},
     AppState,
 };
 
 use super::AuthorizedApiRequest;
 
+fn image_id_to_url(id: ImageId) -> Url {
+    app_url::domain_and(user_content::ROOT)
+        .join(user_content::IMAGES)
+        .unwrap()
+        .join(&id.to_string())
+        .unwrap()
+}
+
+/// Whether `user_id` may see `post`.
+///
+/// Scheduled posts are only visible to their author, and direct messages only to the two users
+/// exchanging them.
+pub fn is_visible_to(post: &Post, user_id: UserId) -> bool {
+    let is_author = post.user_id == user_id;
+    let is_published = post.time_posted <= Utc::now();
+    match post.direct_message_to {
+        Some(recipient) => is_author || (recipient == user_id && is_published),
+        None => is_author || is_published,
+    }
+}
+
 #[async_trait]
 impl AuthorizedApiRequest for NewPost {
     type Response = (StatusCode, Json<NewPostOk>);
@@ -39,6 +75,42 @@ impl AuthorizedApiRequest for NewPost {
         use uchat_endpoint::post::Content;
 
         let mut content = self.content;
+        if let Content::Poll(ref poll) = content {
+            if let Some(closes_at) = poll.closes_at {
+                if closes_at <= self.options.time_posted.max(Utc::now()) {
+                    return Err(ApiError::new(
+                        StatusCode::BAD_REQUEST,
+                        "a poll must close after it is posted",
+                    ));
+                }
+            } else if poll.hide_results {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "results can only be hidden for polls which close",
+                ));
+            }
+            if !(1..=poll.choices.len()).contains(&poll.max_choices()) {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "the number of selectable choices must not exceed the number of choices",
+                ));
+            }
+        }
+        let mut notified = None;
+        if let Some(recipient) = self.options.direct_message_to {
+            ensure_not_blocked(&mut conn, session.user_id, recipient)?;
+            notified = Some((recipient, NotificationKind::DirectMessage));
+        }
+        if let Some(reply_to) = self.options.reply_to {
+            let not_found = || ApiError::new(StatusCode::NOT_FOUND, "post not found");
+            let parent = uchat_query::post::get(&mut conn, reply_to).map_err(|_| not_found())?;
+            if !is_visible_to(&parent, session.user_id) {
+                return Err(not_found());
+            }
+            ensure_not_blocked(&mut conn, session.user_id, parent.user_id)?;
+            notified = notified.or(Some((parent.user_id, NotificationKind::Reply)));
+        }
+
         if let Content::Image(ref mut img) = content {
             if let ImageKind::DataUrl(ref data) = img.kind {
                 let id = ImageId::new();
@@ -48,13 +120,211 @@ impl AuthorizedApiRequest for NewPost {
         }
 
         let post = Post::new(session.user_id, content, self.options)?;
+        let time_posted = post.time_posted;
 
         let post_id = uchat_query::post::new(&mut conn, post)?;
 
+        if let Some((user_id, kind)) = notified {
+            notify(
+                &mut conn,
+                NewNotification {
+                    user_id,
+                    actor: session.user_id,
+                    kind,
+                    post_id: Some(post_id),
+                    created_at: time_posted,
+                },
+            );
+        }
+
         Ok((StatusCode::OK, Json(NewPostOk { post_id })))
     }
 }
 
+/// Removes the stored images of deleted posts and their revisions.
+async fn delete_post_images(deleted: Vec<uchat_query::post::Content>) {
+    // revisions of an edit which kept the image refer to the same file
+    let mut image_ids = vec![];
+    for content in deleted {
+        if let Ok(Content::Image(image)) = serde_json::from_value(content.0) {
+            if let ImageKind::Id(id) = image.kind {
+                if !image_ids.contains(&id) {
+                    image_ids.push(id);
+                }
+            }
+        }
+    }
+
+    for id in image_ids {
+        // the post is already gone, so a leftover file is only logged
+        if let Err(e) = delete_image(id).await {
+            tracing::error!(err = %e.err, image_id = ?id, "failed to delete image");
+        }
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for DeletePost {
+    type Response = (StatusCode, Json<DeletePostOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let post = uchat_query::post::get(&mut conn, self.post_id)
+            .map_err(|_| ApiError::new(StatusCode::NOT_FOUND, "post not found"))?;
+        if post.user_id != session.user_id {
+            return Err(ApiError::new(
+                StatusCode::FORBIDDEN,
+                "only the author can delete a post",
+            ));
+        }
+
+        let deleted = uchat_query::post::delete(&mut conn, self.post_id)?;
+        delete_post_images(deleted).await;
+
+        Ok((
+            StatusCode::OK,
+            Json(DeletePostOk {
+                post_id: self.post_id,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for EditPost {
+    type Response = (StatusCode, Json<EditPostOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let post = query_post::get(&mut conn, self.post_id)
+            .map_err(|_| ApiError::new(StatusCode::NOT_FOUND, "post not found"))?;
+        if post.user_id != session.user_id {
+            return Err(ApiError::new(
+                StatusCode::FORBIDDEN,
+                "only the author can edit a post",
+            ));
+        }
+        let previous: Content = serde_json::from_value(post.content.0)?;
+
+        let mut content = self.content;
+        match (&previous, &mut content) {
+            (Content::Chat(_), Content::Chat(_)) => (),
+            (Content::Image(previous), Content::Image(image)) => match image.kind {
+                ImageKind::DataUrl(ref data) => {
+                    let id = ImageId::new();
+                    save_image(id, data).await?;
+                    image.kind = ImageKind::Id(id);
+                }
+                // the image was sent back unchanged
+                ImageKind::Url(_) => image.kind = previous.kind.clone(),
+                ImageKind::Id(_) => {
+                    return Err(ApiError::new(StatusCode::BAD_REQUEST, "invalid image"));
+                }
+            },
+            (Content::Poll(previous), Content::Poll(poll)) => {
+                // vote counts are always read from the votes themselves
+                for choice in &mut poll.choices {
+                    choice.num_votes = 0;
+                }
+                poll.voted.clear();
+                // voting rules are fixed once voters have seen the poll
+                poll.selection = previous.selection;
+                poll.closes_at = previous.closes_at;
+                poll.hide_results = previous.hide_results;
+                if !(1..=poll.choices.len()).contains(&poll.max_choices()) {
+                    return Err(ApiError::new(
+                        StatusCode::BAD_REQUEST,
+                        "the number of selectable choices must not exceed the number of choices",
+                    ));
+                }
+            }
+            _ => {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "the type of a post cannot be changed",
+                ))
+            }
+        }
+
+        if query_post::edit(&mut conn, self.post_id, content, Utc::now())?
+            == query_post::EditStatus::PollHasVotes
+        {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "poll choices cannot be changed after voting has started",
+            ));
+        }
+
+        let post = query_post::get(&mut conn, self.post_id)?;
+        let post = query_post::hydrate(&mut conn, vec![post], Some(session.user_id))?.remove(0);
+        let post = to_public(post)?;
+
+        Ok((StatusCode::OK, Json(EditPostOk { post })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for GetPostRevisions {
+    type Response = (StatusCode, Json<GetPostRevisionsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let not_found = || ApiError::new(StatusCode::NOT_FOUND, "post not found");
+
+        let post = query_post::get(&mut conn, self.post_id).map_err(|_| not_found())?;
+        if !is_visible_to(&post, session.user_id) {
+            return Err(not_found());
+        }
+        ensure_not_blocked(&mut conn, session.user_id, post.user_id)?;
+
+        let revisions = query_post::get_revisions(&mut conn, self.post_id)?
+            .into_iter()
+            .filter_map(
+                |revision| match serde_json::from_value::<Content>(revision.content.0) {
+                    Ok(mut content) => {
+                        if let Content::Image(ref mut image) = content {
+                            if let ImageKind::Id(id) = image.kind {
+                                image.kind = ImageKind::Url(image_id_to_url(id));
+                            }
+                        }
+                        Some(PostRevision {
+                            content,
+                            revised_at: revision.revised_at,
+                        })
+                    }
+                    Err(e) => {
+                        let revision_id = revision.id;
+                        tracing::error!(err = %e, ?revision_id, "revision contains invalid data");
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        let post = hydrate_posts(&mut conn, vec![post], Some(&session))?
+            .pop()
+            .ok_or_else(not_found)?;
+
+        Ok((StatusCode::OK, Json(GetPostRevisionsOk { post, revisions })))
+    }
+}
+
 #[async_trait]
 impl AuthorizedApiRequest for Bookmark {
     type Response = (StatusCode, Json<BookmarkOk>);
@@ -95,7 +365,26 @@ impl AuthorizedApiRequest for Boost {
     ) -> ApiResult<Self::Response> {
         match self.action {
             BoostAction::Add => {
+                let post = uchat_query::post::get(&mut conn, self.post_id)
+                    .ok()
+                    .filter(|post| is_visible_to(post, session.user_id))
+                    .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "post not found"))?;
+                ensure_not_blocked(&mut conn, session.user_id, post.user_id)?;
+                let boosted =
+                    uchat_query::post::get_boost(&mut conn, session.user_id, self.post_id)?;
                 uchat_query::post::boost(&mut conn, session.user_id, self.post_id, Utc::now())?;
+                if !boosted {
+                    notify(
+                        &mut conn,
+                        NewNotification {
+                            user_id: post.user_id,
+                            actor: session.user_id,
+                            kind: NotificationKind::Boost,
+                            post_id: Some(post.id),
+                            created_at: Utc::now(),
+                        },
+                    );
+                }
             }
             BoostAction::Remove => {
                 uchat_query::post::delete_boost(&mut conn, session.user_id, self.post_id)?;
@@ -121,10 +410,46 @@ impl AuthorizedApiRequest for Vote {
         session: UserSession,
         _state: AppState,
     ) -> ApiResult<Self::Response> {
+        let post = uchat_query::post::get(&mut conn, self.post_id)
+            .ok()
+            .filter(|post| is_visible_to(post, session.user_id))
+            .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "post not found"))?;
+        let poll = match serde_json::from_value(post.content.0)? {
+            Content::Poll(poll) => poll,
+            _ => return Err(ApiError::new(StatusCode::BAD_REQUEST, "post is not a poll")),
+        };
+
+        let is_choice = |id: &PollChoiceId| poll.choices.iter().any(|choice| choice.id == *id);
+        if !self.choices.iter().all(is_choice) {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "invalid poll choice",
+            ));
+        }
+        if self.choices.len() > poll.max_choices() {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                format!("at most {} choices can be selected", poll.max_choices()),
+            ));
+        }
+
         let cast =
-            uchat_query::post::vote(&mut conn, session.user_id, self.post_id, self.choice_id)?;
+            uchat_query::post::vote(&mut conn, session.user_id, self.post_id, &self.choices)?;
+        if cast == VoteCast::Yes {
+            notify(
+                &mut conn,
+                NewNotification {
+                    user_id: post.user_id,
+                    actor: session.user_id,
+                    kind: NotificationKind::Vote,
+                    post_id: Some(post.id),
+                    created_at: Utc::now(),
+                },
+            );
+        }
+        let voted = uchat_query::post::did_vote(&mut conn, session.user_id, self.post_id)?;
 
-        Ok((StatusCode::OK, Json(VoteOk { cast })))
+        Ok((StatusCode::OK, Json(VoteOk { cast, voted })))
     }
 }
 
@@ -140,6 +465,15 @@ impl AuthorizedApiRequest for React {
     ) -> ApiResult<Self::Response> {
         use uchat_endpoint::post::LikeStatus;
 
+        let post = uchat_query::post::get(&mut conn, self.post_id)
+            .ok()
+            .filter(|post| is_visible_to(post, session.user_id))
+            .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "post not found"))?;
+        ensure_not_blocked(&mut conn, session.user_id, post.user_id)?;
+
+        let liked = uchat_query::post::get_reaction(&mut conn, self.post_id, session.user_id)?
+            .map_or(false, |reaction| reaction.like_status == 1);
+
         let reaction = uchat_query::post::Reaction {
             post_id: self.post_id,
             user_id: session.user_id,
@@ -153,6 +487,18 @@ impl AuthorizedApiRequest for React {
         };
 
         uchat_query::post::react(&mut conn, reaction)?;
+        if self.like_status == LikeStatus::Like && !liked {
+            notify(
+                &mut conn,
+                NewNotification {
+                    user_id: post.user_id,
+                    actor: session.user_id,
+                    kind: NotificationKind::Like,
+                    post_id: Some(post.id),
+                    created_at: Utc::now(),
+                },
+            );
+        }
 
         let AggregatePostInfo {
             likes, dislikes, ..
@@ -169,37 +515,104 @@ impl AuthorizedApiRequest for React {
     }
 }
 
-pub fn to_public(
-    conn: &mut AsyncConnection,
-    post: Post,
-    session: Option<&UserSession>,
-) -> ApiResult<PublicPost> {
-    use uchat_query::post as query_post;
-    use uchat_query::user as query_user;
+fn to_emoji_counts(emojis: Vec<(String, i64)>) -> Vec<EmojiCount> {
+    emojis
+        .into_iter()
+        .map(|(emoji, count)| EmojiCount { emoji, count })
+        .collect()
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for EmojiReact {
+    type Response = (StatusCode, Json<EmojiReactOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post::ReactionData;
 
-    let AggregatePostInfo {
-        likes,
-        dislikes,
-        boosts,
-        ..
-    } = query_post::aggregate_reactions(conn, post.id)?;
+        if let Some(emoji) = &self.emoji {
+            if !state.reaction_emojis.contains(emoji) {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "emoji is not available for reactions",
+                ));
+            }
+        }
+
+        let post = uchat_query::post::get(&mut conn, self.post_id)
+            .ok()
+            .filter(|post| is_visible_to(post, session.user_id))
+            .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "post not found"))?;
+        ensure_not_blocked(&mut conn, session.user_id, post.user_id)?;
+
+        uchat_query::post::react_with_emoji(
+            &mut conn,
+            session.user_id,
+            post.id,
+            self.emoji.clone().map(ReactionData::emoji),
+        )?;
+
+        let AggregatePostInfo { emojis, .. } =
+            uchat_query::post::aggregate_reactions(&mut conn, post.id)?;
+
+        Ok((
+            StatusCode::OK,
+            Json(EmojiReactOk {
+                my_emoji: self.emoji,
+                emoji_reactions: to_emoji_counts(emojis),
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for GetReactionEmojis {
+    type Response = (StatusCode, Json<GetReactionEmojisOk>);
+
+    async fn process_request(
+        self,
+        _conn: DbConnection,
+        _session: UserSession,
+        state: AppState,
+    ) -> ApiResult<Self::Response> {
+        Ok((
+            StatusCode::OK,
+            Json(GetReactionEmojisOk {
+                emojis: state.reaction_emojis.to_vec(),
+            }),
+        ))
+    }
+}
+
+pub fn to_public(info: PostInfo) -> ApiResult<PublicPost> {
+    let PostInfo {
+        post,
+        author,
+        aggregates,
+        reply_to,
+        mentions,
+        poll_results,
+        like_status,
+        emoji,
+        bookmarked,
+        boosted,
+        voted,
+    } = info;
 
     match serde_json::from_value(post.content.0) {
         Ok(mut content) => {
             match content {
                 Content::Image(ref mut image) => {
                     if let ImageKind::Id(id) = image.kind {
-                        let url = app_url::domain_and(user_content::ROOT)
-                            .join(user_content::IMAGES)
-                            .unwrap()
-                            .join(&id.to_string())
-                            .unwrap();
-
-                        image.kind = ImageKind::Url(url);
+                        image.kind = ImageKind::Url(image_id_to_url(id));
                     }
                 }
                 Content::Poll(ref mut poll) => {
-                    for (id, result) in query_post::get_poll_results(conn, post.id)?.results {
+                    for (id, result) in poll_results {
                         for choice in &mut poll.choices {
                             if choice.id == id {
                                 choice.num_votes = result;
@@ -207,61 +620,39 @@ pub fn to_public(
                             }
                         }
                     }
-                    if let Some(session) = session {
-                        poll.voted = query_post::did_vote(conn, session.user_id, post.id)?;
-                    }
+                    poll.voted = voted;
                 }
                 _ => (),
             }
             Ok(PublicPost {
                 id: post.id,
-                by_user: {
-                    let profile = query_user::get(conn, post.user_id)?;
-                    super::user::to_public(conn, session, profile)?
-                },
+                by_user: super::user::to_public(author),
                 content,
                 time_posted: post.time_posted,
-                reply_to: {
-                    match post.reply_to {
-                        Some(other_post_id) => {
-                            let orignal_post = query_post::get(conn, other_post_id)?;
-                            let original_user = query_user::get(conn, orignal_post.user_id)?;
-                            Some((
-                                Username::new(original_user.handle).unwrap(),
-                                original_user.id,
-                                other_post_id,
-                            ))
-                        }
-                        None => None,
-                    }
+                edited_at: post.edited_at,
+                reply_to: reply_to.map(|reply_to| {
+                    (
+                        Username::new(reply_to.handle).unwrap(),
+                        reply_to.user_id,
+                        reply_to.post_id,
+                    )
+                }),
+                mentions: mentions
+                    .into_iter()
+                    .map(|mention| (Username::new(mention.handle).unwrap(), mention.user_id))
+                    .collect(),
+                like_status: match like_status {
+                    -1 => LikeStatus::Dislike,
+                    1 => LikeStatus::Like,
+                    _ => LikeStatus::NoReaction,
                 },
-                like_status: {
-                    match session {
-                        Some(session) => {
-                            match query_post::get_reaction(conn, post.id, session.user_id)? {
-                                Some(reaction) if reaction.like_status == -1 => LikeStatus::Dislike,
-                                Some(reaction) if reaction.like_status == 1 => LikeStatus::Like,
-                                _ => LikeStatus::NoReaction,
-                            }
-                        }
-                        None => LikeStatus::NoReaction,
-                    }
-                },
-                bookmarked: {
-                    match session {
-                        Some(session) => query_post::get_bookmark(conn, session.user_id, post.id)?,
-                        None => false,
-                    }
-                },
-                boosted: {
-                    match session {
-                        Some(session) => query_post::get_boost(conn, session.user_id, post.id)?,
-                        None => false,
-                    }
-                },
-                likes,
-                dislikes,
-                boosts,
+                bookmarked,
+                boosted,
+                likes: aggregates.likes,
+                dislikes: aggregates.dislikes,
+                boosts: aggregates.boosts,
+                emoji_reactions: to_emoji_counts(aggregates.emojis),
+                my_emoji: emoji,
             })
         }
         Err(_) => Err(ApiError {
@@ -273,6 +664,46 @@ pub fn to_public(
     }
 }
 
+/// Converts posts into their public form, skipping any which contain invalid data.
+pub fn hydrate_posts(
+    conn: &mut AsyncConnection,
+    posts: Vec<Post>,
+    session: Option<&UserSession>,
+) -> ApiResult<Vec<PublicPost>> {
+    let infos = uchat_query::post::hydrate(conn, posts, session.map(|session| session.user_id))?;
+
+    Ok(infos
+        .into_iter()
+        .filter_map(|info| {
+            let post_id = info.post.id;
+            match to_public(info) {
+                Ok(post) => Some(post),
+                Err(e) => {
+                    tracing::error!(err = %e.err, post_id = ?post_id, "post contains invalid data");
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+/// Cursor for the page following `posts`, if the page was filled.
+pub fn next_cursor(page: &Page, posts: &[Post]) -> Option<Cursor> {
+    if posts.len() as i64 == page.size() {
+        posts.last().map(|post| TimeCursor::from(post).encode())
+    } else {
+        None
+    }
+}
+
+fn next_saved_cursor(page: &Page, saved: &[SavedPost]) -> Option<Cursor> {
+    if saved.len() as i64 == page.size() {
+        saved.last().map(|saved| TimeCursor::from(saved).encode())
+    } else {
+        None
+    }
+}
+
 #[async_trait]
 impl AuthorizedApiRequest for TrendingPosts {
     type Response = (StatusCode, Json<TrendingPostsOk>);
@@ -285,19 +716,21 @@ impl AuthorizedApiRequest for TrendingPosts {
     ) -> ApiResult<Self::Response> {
         use uchat_query::post as query_post;
 
-        let mut posts = vec![];
+        let since = Utc::now() - self.window.duration();
+        let after = decode_cursor(&self.page, ScoreCursor::decode)?;
+        let page =
+            query_post::get_trending(&mut conn, session.user_id, since, after, self.page.size())?;
+        let next_cursor = match page.len() as i64 == self.page.size() {
+            true => page
+                .last()
+                .map(|(post, score)| ScoreCursor::new(*score, post.id.into_inner()).encode()),
+            false => None,
+        };
 
-        for post in query_post::get_trending(&mut conn)? {
-            let post_id = post.id;
-            match to_public(&mut conn, post, Some(&session)) {
-                Ok(post) => posts.push(post),
-                Err(e) => {
-                    tracing::error!(err = %e.err, post_id = ?post_id, "post contains invalid data");
-                }
-            }
-        }
+        let page = page.into_iter().map(|(post, _)| post).collect();
+        let posts = hydrate_posts(&mut conn, page, Some(&session))?;
 
-        Ok((StatusCode::OK, Json(TrendingPostsOk { posts })))
+        Ok((StatusCode::OK, Json(TrendingPostsOk { posts, next_cursor })))
     }
 }
 
@@ -313,19 +746,13 @@ impl AuthorizedApiRequest for HomePosts {
     ) -> ApiResult<Self::Response> {
         use uchat_query::post as query_post;
 
-        let mut posts = vec![];
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let page = query_post::get_home_posts(&mut conn, session.user_id, after, self.page.size())?;
+        let next_cursor = next_cursor(&self.page, &page);
 
-        for post in query_post::get_home_posts(&mut conn, session.user_id)? {
-            let post_id = post.id;
-            match to_public(&mut conn, post, Some(&session)) {
-                Ok(post) => posts.push(post),
-                Err(e) => {
-                    tracing::error!(err = %e.err, post_id = ?post_id, "post contains invalid data");
-                }
-            }
-        }
+        let posts = hydrate_posts(&mut conn, page, Some(&session))?;
 
-        Ok((StatusCode::OK, Json(HomePostsOk { posts })))
+        Ok((StatusCode::OK, Json(HomePostsOk { posts, next_cursor })))
     }
 }
 
@@ -341,19 +768,15 @@ impl AuthorizedApiRequest for LikedPosts {
     ) -> ApiResult<Self::Response> {
         use uchat_query::post as query_post;
 
-        let mut posts = vec![];
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let saved =
+            query_post::get_liked_posts(&mut conn, session.user_id, after, self.page.size())?;
+        let next_cursor = next_saved_cursor(&self.page, &saved);
 
-        for post in query_post::get_liked_posts(&mut conn, session.user_id)? {
-            let post_id = post.id;
-            match to_public(&mut conn, post, Some(&session)) {
-                Ok(post) => posts.push(post),
-                Err(e) => {
-                    tracing::error!(err = %e.err, post_id = ?post_id, "post contains invalid data");
-                }
-            }
-        }
+        let page = saved.into_iter().map(|saved| saved.post).collect();
+        let posts = hydrate_posts(&mut conn, page, Some(&session))?;
 
-        Ok((StatusCode::OK, Json(LikedPostsOk { posts })))
+        Ok((StatusCode::OK, Json(LikedPostsOk { posts, next_cursor })))
     }
 }
 
@@ -369,18 +792,444 @@ impl AuthorizedApiRequest for BookmarkedPosts {
     ) -> ApiResult<Self::Response> {
         use uchat_query::post as query_post;
 
-        let mut posts = vec![];
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let saved =
+            query_post::get_bookmarked_posts(&mut conn, session.user_id, after, self.page.size())?;
+        let next_cursor = next_saved_cursor(&self.page, &saved);
 
-        for post in query_post::get_bookmarked_posts(&mut conn, session.user_id)? {
-            let post_id = post.id;
-            match to_public(&mut conn, post, Some(&session)) {
-                Ok(post) => posts.push(post),
-                Err(e) => {
-                    tracing::error!(err = %e.err, post_id = ?post_id, "post contains invalid data");
-                }
-            }
+        let page = saved.into_iter().map(|saved| saved.post).collect();
+        let posts = hydrate_posts(&mut conn, page, Some(&session))?;
+
+        Ok((
+            StatusCode::OK,
+            Json(BookmarkedPostsOk { posts, next_cursor }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for MentionedPosts {
+    type Response = (StatusCode, Json<MentionedPostsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let page =
+            query_post::get_mentioned_posts(&mut conn, session.user_id, after, self.page.size())?;
+        let next_cursor = next_cursor(&self.page, &page);
+
+        let posts = hydrate_posts(&mut conn, page, Some(&session))?;
+
+        Ok((
+            StatusCode::OK,
+            Json(MentionedPostsOk { posts, next_cursor }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for TagTimeline {
+    type Response = (StatusCode, Json<TagTimelineOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let page = query_post::get_tag_timeline(
+            &mut conn,
+            session.user_id,
+            &self.tag,
+            after,
+            self.page.size(),
+        )?;
+        let next_cursor = next_cursor(&self.page, &page);
+
+        let posts = hydrate_posts(&mut conn, page, Some(&session))?;
+
+        Ok((StatusCode::OK, Json(TagTimelineOk { posts, next_cursor })))
+    }
+}
+
+/// Number of hashtags returned by `TrendingTags`.
+const TRENDING_TAGS_LIMIT: i64 = 10;
+
+#[async_trait]
+impl AuthorizedApiRequest for TrendingTags {
+    type Response = (StatusCode, Json<TrendingTagsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let since = Utc::now() - self.window.duration();
+        let tags =
+            query_post::get_trending_tags(&mut conn, session.user_id, since, TRENDING_TAGS_LIMIT)?
+                .into_iter()
+                .map(|usage| TagCount {
+                    tag: usage.tag,
+                    posts: usage.posts,
+                })
+                .collect();
+
+        Ok((StatusCode::OK, Json(TrendingTagsOk { tags })))
+    }
+}
+
+/// Longest accepted search query, in characters.
+const MAX_SEARCH_QUERY_LEN: usize = 200;
+
+#[async_trait]
+impl AuthorizedApiRequest for SearchPosts {
+    type Response = (StatusCode, Json<SearchPostsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let query = self.query.trim();
+        if query.is_empty() {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "search query is empty",
+            ));
+        }
+        if query.chars().count() > MAX_SEARCH_QUERY_LEN {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "search query is too long",
+            ));
+        }
+
+        let after = decode_cursor(&self.page, ScoreCursor::decode)?;
+        let matches = query_post::search(&mut conn, query, after, self.page.size())?;
+        let next_cursor = match matches.len() as i64 == self.page.size() {
+            true => matches
+                .last()
+                .map(|found| ScoreCursor::new(found.rank, found.post.id.into_inner()).encode()),
+            false => None,
+        };
+
+        let mut snippets = HashMap::new();
+        let mut page = vec![];
+        for found in matches {
+            snippets.insert(found.post.id, found.snippet);
+            page.push(found.post);
+        }
+
+        let results = hydrate_posts(&mut conn, page, Some(&session))?
+            .into_iter()
+            .map(|post| PostSearchResult {
+                snippet: snippets.remove(&post.id).unwrap_or_default(),
+                post,
+            })
+            .collect();
+
+        Ok((
+            StatusCode::OK,
+            Json(SearchPostsOk {
+                results,
+                next_cursor,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ListConversations {
+    type Response = (StatusCode, Json<ListConversationsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let previews =
+            query_post::list_conversations(&mut conn, session.user_id, after, self.page.size())?;
+        let next_cursor = match previews.len() as i64 == self.page.size() {
+            true => previews
+                .last()
+                .map(|preview| TimeCursor::from(&preview.last_message).encode()),
+            false => None,
+        };
+
+        let user_ids: Vec<_> = previews.iter().map(|preview| preview.with_user).collect();
+        let users = uchat_query::user::hydrate_ids(&mut conn, &user_ids, Some(session.user_id))?;
+
+        let mut with_users = HashMap::new();
+        let mut last_messages = vec![];
+        for preview in previews {
+            with_users.insert(preview.last_message.id, preview.with_user);
+            last_messages.push(preview.last_message);
         }
 
-        Ok((StatusCode::OK, Json(BookmarkedPostsOk { posts })))
+        let conversations = hydrate_posts(&mut conn, last_messages, Some(&session))?
+            .into_iter()
+            .filter_map(|last_message| {
+                let with_user = users.get(with_users.get(&last_message.id)?)?.clone();
+                Some(ConversationPreview {
+                    with_user: super::user::to_public(with_user),
+                    last_message,
+                })
+            })
+            .collect();
+
+        Ok((
+            StatusCode::OK,
+            Json(ListConversationsOk {
+                conversations,
+                next_cursor,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for GetConversation {
+    type Response = (StatusCode, Json<GetConversationOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let with_user = uchat_query::user::get(&mut conn, self.with_user)
+            .map_err(|_| ApiError::new(StatusCode::NOT_FOUND, "user not found"))?;
+        let with_user =
+            uchat_query::user::hydrate(&mut conn, vec![with_user], Some(session.user_id))?
+                .remove(0);
+
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let page = query_post::get_conversation(
+            &mut conn,
+            session.user_id,
+            self.with_user,
+            after,
+            self.page.size(),
+        )?;
+        let next_cursor = next_cursor(&self.page, &page);
+
+        let messages = hydrate_posts(&mut conn, page, Some(&session))?;
+
+        Ok((
+            StatusCode::OK,
+            Json(GetConversationOk {
+                with_user: super::user::to_public(with_user),
+                messages,
+                next_cursor,
+            }),
+        ))
+    }
+}
+
+/// Levels of replies returned when a thread request doesn't ask for a depth.
+const DEFAULT_THREAD_DEPTH: u32 = 3;
+/// Deepest reply tree returned by a single thread request.
+const MAX_THREAD_DEPTH: u32 = 8;
+
+#[async_trait]
+impl AuthorizedApiRequest for GetThread {
+    type Response = (StatusCode, Json<GetThreadOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let not_found = || ApiError::new(StatusCode::NOT_FOUND, "post not found");
+
+        let post = query_post::get(&mut conn, self.post_id).map_err(|_| not_found())?;
+        if post.direct_message_to.is_some()
+            || !is_visible_to(&post, session.user_id)
+            || uchat_query::user::is_blocked_between(&mut conn, session.user_id, post.user_id)?
+        {
+            return Err(not_found());
+        }
+
+        let ancestors = query_post::get_thread_ancestors(&mut conn, session.user_id, post.id)?;
+        let ancestors = hydrate_posts(&mut conn, ancestors, Some(&session))?;
+
+        let post = hydrate_posts(&mut conn, vec![post], Some(&session))?
+            .pop()
+            .ok_or_else(not_found)?;
+
+        let max_depth = self
+            .max_depth
+            .unwrap_or(DEFAULT_THREAD_DEPTH)
+            .clamp(1, MAX_THREAD_DEPTH);
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let replies = query_post::get_replies(
+            &mut conn,
+            session.user_id,
+            self.post_id,
+            after,
+            self.page.size(),
+            max_depth as i32,
+        )?;
+
+        let direct_replies: Vec<_> = replies
+            .iter()
+            .filter(|reply| reply.depth == 1)
+            .map(|reply| reply.post.clone())
+            .collect();
+        let next_cursor = next_cursor(&self.page, &direct_replies);
+
+        let mut depths = HashMap::new();
+        let mut page = vec![];
+        for reply in replies {
+            depths.insert(reply.post.id, reply.depth as u32);
+            page.push(reply.post);
+        }
+
+        let replies = hydrate_posts(&mut conn, page, Some(&session))?
+            .into_iter()
+            .map(|post| ThreadReply {
+                depth: depths.get(&post.id).copied().unwrap_or(1),
+                post,
+            })
+            .collect();
+
+        let muted = uchat_query::user::get_mute(
+            &mut conn,
+            session.user_id,
+            uchat_query::user::MuteTarget::Thread(self.post_id),
+        )?
+        .is_some();
+
+        Ok((
+            StatusCode::OK,
+            Json(GetThreadOk {
+                ancestors,
+                post,
+                replies,
+                next_cursor,
+                muted,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ListScheduledPosts {
+    type Response = (StatusCode, Json<ListScheduledPostsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let page =
+            query_post::get_scheduled_posts(&mut conn, session.user_id, after, self.page.size())?;
+        let next_cursor = next_cursor(&self.page, &page);
+
+        let posts = hydrate_posts(&mut conn, page, Some(&session))?;
+
+        Ok((
+            StatusCode::OK,
+            Json(ListScheduledPostsOk { posts, next_cursor }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ReschedulePost {
+    type Response = (StatusCode, Json<ReschedulePostOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        if self.time_posted <= Utc::now() {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "posts can only be rescheduled to a future time",
+            ));
+        }
+
+        let rescheduled = uchat_query::post::reschedule(
+            &mut conn,
+            session.user_id,
+            self.post_id,
+            self.time_posted,
+        )?;
+        if !rescheduled {
+            return Err(ApiError::new(
+                StatusCode::NOT_FOUND,
+                "no such scheduled post",
+            ));
+        }
+
+        Ok((
+            StatusCode::OK,
+            Json(ReschedulePostOk {
+                post_id: self.post_id,
+                time_posted: self.time_posted,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for CancelScheduledPost {
+    type Response = (StatusCode, Json<CancelScheduledPostOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post as query_post;
+
+        let not_found = || ApiError::new(StatusCode::NOT_FOUND, "no such scheduled post");
+
+        let post = query_post::get(&mut conn, self.post_id).map_err(|_| not_found())?;
+        if post.user_id != session.user_id || post.time_posted <= Utc::now() {
+            return Err(not_found());
+        }
+
+        let deleted = query_post::delete(&mut conn, self.post_id)?;
+        delete_post_images(deleted).await;
+
+        Ok((
+            StatusCode::OK,
+            Json(CancelScheduledPostOk {
+                post_id: self.post_id,
+            }),
+        ))
     }
 }
@@ -1,29 +1,46 @@
+use std::str::FromStr;
+
 use axum::{async_trait, Json};
 use chrono::{Duration, Utc};
 use hyper::StatusCode;
 use uchat_domain::{ids::*, DisplayName};
 use uchat_endpoint::{
+    notification::NotificationKind,
     user::{
-        CreateUser, CreateUserOk, FollowAction, FollowUser, FollowUserOk, GetMyProfile,
-        GetMyProfileOk, Login, LoginOk, PublicUserProfile, UpdateProfile, UpdateProfileOk,
-        ViewProfile, ViewProfileOk,
+        BlockUser, BlockUserOk, CompleteLogin, CompleteLoginOk, ConfirmEmail, ConfirmEmailOk,
+        ConfirmTwoFactor, ConfirmTwoFactorOk, CreateUser, CreateUserOk, DisableTwoFactor,
+        DisableTwoFactorOk, EnableTwoFactor, EnableTwoFactorOk, FollowAction, FollowUser,
+        FollowUserOk, GetMyProfile, GetMyProfileOk, ListBlocked, ListBlockedOk, ListFollowers,
+        ListFollowersOk, ListFollowing, ListFollowingOk, ListSessions, ListSessionsOk, LoggedIn,
+        Login, LoginOk, Logout, LogoutOk, MuteThread, MuteThreadOk, MuteUser, MuteUserOk,
+        PublicUserProfile, RequestPasswordReset, RequestPasswordResetOk, ResendEmailConfirmation,
+        ResendEmailConfirmationOk, ResetPassword, ResetPasswordOk, RevokeAllOtherSessions,
+        RevokeAllOtherSessionsOk, RevokeSession, RevokeSessionOk, SearchUsers, SearchUsersOk,
+        SessionInfo, UnblockUser, UnblockUserOk, UnmuteThread, UnmuteThreadOk, UnmuteUser,
+        UnmuteUserOk, UpdateProfile, UpdateProfileOk, ViewProfile, ViewProfileOk,
     },
-    RequestFailed, Update,
+    Cursor, Page, RequestFailed, Update,
 };
 use uchat_query::{
+    notification::NewNotification,
     session::Session,
-    user::{UpdateProfileParams, User},
-    AsyncConnection,
+    two_factor::TwoFactor,
+    user::{Blocked, Follow, MuteTarget, UpdateProfileParams, User, UserInfo},
+    QueryError, TimeCursor,
 };
 use url::Url;
 
 use crate::{
     error::{ApiError, ApiResult, ServerError},
-    extractor::{DbConnection, UserSession},
+    extractor::{ClientInfo, DbConnection, UserSession},
+    mailer::Email,
     AppState,
 };
 
-use super::{save_image, AuthorizedApiRequest, PublicApiRequest};
+use super::{
+    decode_cursor, ensure_not_blocked, notification::notify, save_image, AuthorizedApiRequest,
+    PublicApiRequest,
+};
 
 fn profile_id_to_url(id: &str) -> Url {
     use uchat_endpoint::app_url::{self, user_content};
@@ -37,12 +54,18 @@ fn profile_id_to_url(id: &str) -> Url {
 #[derive(Clone)]
 pub struct SessionSignature(String);
 
-pub fn to_public(
-    conn: &mut AsyncConnection,
-    session: Option<&UserSession>,
-    user: User,
-) -> ApiResult<PublicUserProfile> {
-    Ok(PublicUserProfile {
+pub fn to_public(info: UserInfo) -> PublicUserProfile {
+    let UserInfo {
+        user,
+        am_following,
+        follows_me,
+        am_blocking,
+        am_muting,
+        follower_count,
+        following_count,
+        post_count,
+    } = info;
+    PublicUserProfile {
         id: user.id,
         display_name: user
             .display_name
@@ -50,23 +73,24 @@ pub fn to_public(
         handle: user.handle,
         profile_image: user.profile_image.as_ref().map(|id| profile_id_to_url(id)),
         created_at: user.created_at,
-        am_following: {
-            match session {
-                Some(session) => uchat_query::user::is_following(conn, session.user_id, user.id)?,
-                None => false,
-            }
-        },
-    })
+        am_following,
+        follows_me,
+        am_blocking,
+        am_muting,
+        follower_count,
+        following_count,
+        post_count,
+    }
 }
 
 fn new_session(
     state: &AppState,
     conn: &mut uchat_query::AsyncConnection,
     user_id: UserId,
+    client: &ClientInfo,
 ) -> ApiResult<(Session, SessionSignature, Duration)> {
-    let fingerprint = serde_json::json!({});
-    let session_duration = Duration::weeks(3);
-    let session = uchat_query::session::new(conn, user_id, session_duration, fingerprint.into())?;
+    let session_duration = crate::extractor::session_duration();
+    let session = uchat_query::session::new(conn, user_id, session_duration, client.fingerprint())?;
 
     let mut rng = state.rng.clone();
 
@@ -81,6 +105,322 @@ fn new_session(
     ))
 }
 
+/// Starts a session for `user`, who has passed every login step.
+fn logged_in(
+    state: &AppState,
+    conn: &mut uchat_query::AsyncConnection,
+    user: User,
+    client: &ClientInfo,
+) -> ApiResult<LoggedIn> {
+    let (session, signature, duration) = new_session(state, conn, user.id, client)?;
+
+    Ok(LoggedIn {
+        session_signature: signature.0,
+        session_id: session.id,
+        session_expires: Utc::now() + duration,
+        display_name: user.display_name,
+        email: user.email,
+        profile_image: user.profile_image.as_ref().map(|id| profile_id_to_url(id)),
+        user_id: user.id,
+    })
+}
+
+/// How long a login waits for the second factor once the password was entered.
+fn pending_login_duration() -> Duration {
+    Duration::minutes(5)
+}
+
+/// Wrong codes accepted for a pending login before the password has to be entered again. The
+/// same number of wrong codes across all logins of a user locks out the second factor.
+const MAX_SECOND_FACTOR_ATTEMPTS: i32 = 5;
+
+/// How long the second factor stays locked after too many wrong codes.
+fn second_factor_lockout() -> Duration {
+    Duration::minutes(15)
+}
+
+fn ensure_not_locked_out(two_factor: &TwoFactor) -> ApiResult<()> {
+    if two_factor.is_locked_out(MAX_SECOND_FACTOR_ATTEMPTS, second_factor_lockout()) {
+        return Err(ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "too many wrong codes, try again later",
+        ));
+    }
+    Ok(())
+}
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Checks a code from the authenticator app, or one of the recovery codes. Either only works once.
+fn verify_second_factor(
+    conn: &mut uchat_query::AsyncConnection,
+    two_factor: &TwoFactor,
+    code: &str,
+) -> ApiResult<bool> {
+    let code = code.trim();
+    let is_app_code = code.len() == uchat_crypto::totp::DIGITS as usize
+        && code.chars().all(|c| c.is_ascii_digit());
+
+    if is_app_code {
+        let now = Utc::now().timestamp() as u64;
+        match uchat_crypto::totp::verify(&two_factor.secret, code, now)? {
+            Some(step) => Ok(uchat_query::two_factor::record_step(
+                conn,
+                two_factor.user_id,
+                step as i64,
+            )?),
+            None => Ok(false),
+        }
+    } else {
+        let code_hash =
+            uchat_crypto::token::hash_token(uchat_crypto::totp::normalize_recovery_code(code));
+        Ok(uchat_query::two_factor::use_recovery_code(
+            conn,
+            two_factor.user_id,
+            &code_hash,
+        )?)
+    }
+}
+
+/// How long email confirmation links stay valid.
+fn email_confirmation_duration() -> Duration {
+    Duration::days(1)
+}
+
+/// Prefixed to the signed payload of confirmation links, so signatures made by the same keys for
+/// other purposes can't pass as one.
+const EMAIL_CONFIRMATION_DOMAIN: &[u8] = b"email-confirm:";
+
+fn email_confirmation_message(payload: &str) -> Vec<u8> {
+    [EMAIL_CONFIRMATION_DOMAIN, payload.as_bytes()].concat()
+}
+
+/// Signed token proving that a confirmation link for `email` was sent to `user_id`.
+fn email_confirmation_token(state: &AppState, user_id: UserId, email: &str) -> String {
+    let expires_at = Utc::now() + email_confirmation_duration();
+    let payload = format!("{}:{}:{email}", user_id.to_string(), expires_at.timestamp());
+
+    let mut rng = state.rng.clone();
+    let signature = state
+        .signing_keys
+        .sign(&mut rng, &email_confirmation_message(&payload));
+
+    format!(
+        "{}.{}",
+        uchat_crypto::encode_base64_url(payload),
+        uchat_crypto::encode_base64_url(signature)
+    )
+}
+
+/// Checks an email confirmation token, returning the user and email address it confirms.
+fn verify_email_confirmation_token(state: &AppState, token: &str) -> ApiResult<(UserId, String)> {
+    let invalid = || ApiError::new(StatusCode::BAD_REQUEST, "invalid confirmation link");
+
+    let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+    let payload = uchat_crypto::decode_base64_url(payload)
+        .ok()
+        .and_then(|payload| String::from_utf8(payload).ok())
+        .ok_or_else(invalid)?;
+    let signature = uchat_crypto::decode_base64_url(signature)
+        .ok()
+        .and_then(|sig| uchat_crypto::sign::signature_from_bytes(sig).ok())
+        .ok_or_else(invalid)?;
+    state
+        .signing_keys
+        .verify(&email_confirmation_message(&payload), signature)
+        .map_err(|_| invalid())?;
+
+    let mut fields = payload.splitn(3, ':');
+    let (Some(user_id), Some(expires_at), Some(email)) =
+        (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(invalid());
+    };
+    let user_id = UserId::from_str(user_id).map_err(|_| invalid())?;
+    let expires_at: i64 = expires_at.parse().map_err(|_| invalid())?;
+    if expires_at <= Utc::now().timestamp() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "confirmation link has expired",
+        ));
+    }
+
+    Ok((user_id, email.to_string()))
+}
+
+/// Emails a confirmation link for `email` to its owner.
+async fn send_email_confirmation(state: &AppState, user_id: UserId, email: &str) -> ApiResult<()> {
+    let token = email_confirmation_token(state, user_id, email);
+    // matches the confirmation page route of the frontend
+    let link = state
+        .frontend_url
+        .join(&format!("account/confirm_email/{token}"))?;
+
+    state
+        .mailer
+        .send(Email {
+            to: email.to_string(),
+            subject: "Confirm your email address".to_string(),
+            body: format!(
+                "Open this link within {} hours to confirm your email address on uchat:\n\n{link}",
+                email_confirmation_duration().num_hours()
+            ),
+        })
+        .await?;
+    Ok(())
+}
+
+#[async_trait]
+impl PublicApiRequest for ConfirmEmail {
+    type Response = (StatusCode, Json<ConfirmEmailOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        _client: ClientInfo,
+        state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let (user_id, email) = verify_email_confirmation_token(&state, &self.token)?;
+        let in_use = || ApiError::new(StatusCode::CONFLICT, "email address is already in use");
+
+        if let Some(owner) = uchat_query::user::find_by_confirmed_email(&mut conn, &email)? {
+            if owner.id != user_id {
+                return Err(in_use());
+            }
+        }
+
+        // another user may confirm the same address between the check and the update
+        let confirmed = uchat_query::user::confirm_email(&mut conn, user_id, &email)
+            .map_err(QueryError::from)
+            .map_err(|e| match e {
+                QueryError::UniqueViolation => in_use(),
+                e => e.into(),
+            })?;
+        if !confirmed {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "email address has changed since the link was sent",
+            ));
+        }
+
+        Ok((StatusCode::OK, Json(ConfirmEmailOk { email })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ResendEmailConfirmation {
+    type Response = (StatusCode, Json<ResendEmailConfirmationOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let user = uchat_query::user::get(&mut conn, session.user_id)?;
+
+        match (user.email, user.email_confirmed) {
+            (Some(email), None) => send_email_confirmation(&state, user.id, &email).await?,
+            (Some(_), Some(_)) => {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "email address is already confirmed",
+                ))
+            }
+            (None, _) => {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "no email address to confirm",
+                ))
+            }
+        }
+
+        Ok((StatusCode::OK, Json(ResendEmailConfirmationOk)))
+    }
+}
+
+/// How long password reset links stay valid.
+fn password_reset_duration() -> Duration {
+    Duration::hours(1)
+}
+
+#[async_trait]
+impl PublicApiRequest for RequestPasswordReset {
+    type Response = (StatusCode, Json<RequestPasswordResetOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        _client: ClientInfo,
+        state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let email = self.email.trim();
+
+        if let Some(user) = uchat_query::user::find_by_confirmed_email(&mut conn, email)? {
+            // the state's rng is cloned for each request, so it would hand out the same token
+            let token = uchat_crypto::token::new_token(&mut rand::thread_rng());
+            let expires_at = Utc::now() + password_reset_duration();
+            uchat_query::password_reset::new(
+                &mut conn,
+                user.id,
+                &uchat_crypto::token::hash_token(&token),
+                expires_at,
+            )?;
+
+            // matches the password reset page route of the frontend
+            let link = state
+                .frontend_url
+                .join(&format!("account/reset_password/{token}"))?;
+            let email = Email {
+                to: email.to_string(),
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Open this link within {} minutes to choose a new password for @{} on uchat:\n\n{link}\n\nIf you didn't ask to reset your password, you can ignore this email.",
+                    password_reset_duration().num_minutes(),
+                    user.handle
+                ),
+            };
+
+            // sent in the background, so response times don't reveal which addresses have accounts
+            let mailer = state.mailer.clone();
+            tokio::spawn(async move {
+                if let Err(e) = mailer.send(email).await {
+                    tracing::error!(err = %e, "failed to send password reset email");
+                }
+            });
+
+            tracing::info!(user_id = user.id.to_string(), "password reset requested");
+        }
+
+        Ok((StatusCode::OK, Json(RequestPasswordResetOk)))
+    }
+}
+
+#[async_trait]
+impl PublicApiRequest for ResetPassword {
+    type Response = (StatusCode, Json<ResetPasswordOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        _client: ClientInfo,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let password_hash = uchat_crypto::hash_password(self.password)?;
+        let token_hash = uchat_crypto::token::hash_token(&self.token);
+
+        let user_id =
+            uchat_query::password_reset::reset_password(&mut conn, &token_hash, password_hash)?
+                .ok_or_else(|| {
+                    ApiError::new(StatusCode::BAD_REQUEST, "invalid or expired reset link")
+                })?;
+
+        tracing::info!(user_id = user_id.to_string(), "password reset");
+
+        Ok((StatusCode::OK, Json(ResetPasswordOk)))
+    }
+}
+
 #[async_trait]
 impl PublicApiRequest for CreateUser {
     type Response = (StatusCode, Json<CreateUserOk>);
@@ -88,15 +428,18 @@ impl PublicApiRequest for CreateUser {
     async fn process_request(
         self,
         DbConnection(mut conn): DbConnection,
+        mut client: ClientInfo,
         state: AppState,
     ) -> ApiResult<Self::Response> {
+        client.device_id = self.device_id.or(client.device_id);
+
         let password_hash = uchat_crypto::hash_password(self.password)?;
         let user_id = uchat_query::user::new(&mut conn, password_hash, &self.username)
             .map_err(|_| ServerError::account_exists())?;
 
         tracing::info!(username = self.username.as_ref(), "new user created");
 
-        let (session, signature, duration) = new_session(&state, &mut conn, user_id)?;
+        let (session, signature, duration) = new_session(&state, &mut conn, user_id, &client)?;
 
         Ok((
             StatusCode::CREATED,
@@ -118,8 +461,11 @@ impl PublicApiRequest for Login {
     async fn process_request(
         self,
         DbConnection(mut conn): DbConnection,
+        mut client: ClientInfo,
         state: AppState,
     ) -> ApiResult<Self::Response> {
+        client.device_id = self.device_id.or(client.device_id);
+
         let _span =
             tracing::span!(tracing::Level::INFO, "logging in", user = %self.username.as_ref())
                 .entered();
@@ -135,20 +481,287 @@ impl PublicApiRequest for Login {
         let user = uchat_query::user::find(&mut conn, &self.username)
             .map_err(|_| ServerError::missing_login())?;
 
-        let (session, signature, duration) = new_session(&state, &mut conn, user.id)?;
+        let two_factor =
+            uchat_query::two_factor::get(&mut conn, user.id)?.filter(TwoFactor::is_enabled);
+        if let Some(two_factor) = two_factor {
+            ensure_not_locked_out(&two_factor)?;
 
-        let profile_image_url = user.profile_image.as_ref().map(|id| profile_id_to_url(id));
+            let pending_token = uchat_crypto::token::new_token(&mut rand::thread_rng());
+            uchat_query::two_factor::new_pending_login(
+                &mut conn,
+                user.id,
+                &uchat_crypto::token::hash_token(&pending_token),
+                Utc::now() + pending_login_duration(),
+            )?;
+
+            return Ok((
+                StatusCode::OK,
+                Json(LoginOk::TwoFactorRequired { pending_token }),
+            ));
+        }
+
+        let logged_in = logged_in(&state, &mut conn, user, &client)?;
+
+        Ok((StatusCode::OK, Json(LoginOk::LoggedIn(logged_in))))
+    }
+}
+
+#[async_trait]
+impl PublicApiRequest for CompleteLogin {
+    type Response = (StatusCode, Json<CompleteLoginOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        client: ClientInfo,
+        state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let expired = || {
+            ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "login expired, please log in again",
+            )
+        };
+
+        let token_hash = uchat_crypto::token::hash_token(&self.pending_token);
+        let user_id = uchat_query::two_factor::get_pending_login(
+            &mut conn,
+            &token_hash,
+            MAX_SECOND_FACTOR_ATTEMPTS,
+        )?
+        .ok_or_else(expired)?;
+        let two_factor = uchat_query::two_factor::get(&mut conn, user_id)?
+            .filter(TwoFactor::is_enabled)
+            .ok_or_else(expired)?;
+        ensure_not_locked_out(&two_factor)?;
+
+        if !verify_second_factor(&mut conn, &two_factor, &self.code)? {
+            uchat_query::two_factor::fail_pending_login(&mut conn, &token_hash)?;
+            uchat_query::two_factor::fail_attempt(&mut conn, user_id)?;
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "invalid code"));
+        }
+        uchat_query::two_factor::finish_pending_login(&mut conn, &token_hash)?;
+        uchat_query::two_factor::reset_failed_attempts(&mut conn, user_id)?;
+
+        let user = uchat_query::user::get(&mut conn, user_id)?;
+        let session = logged_in(&state, &mut conn, user, &client)?;
+
+        Ok((StatusCode::OK, Json(CompleteLoginOk { session })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for EnableTwoFactor {
+    type Response = (StatusCode, Json<EnableTwoFactorOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let secret = uchat_crypto::totp::new_secret(&mut rand::thread_rng());
+
+        if !uchat_query::two_factor::begin_enrollment(&mut conn, session.user_id, &secret)? {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "two-factor login is already enabled",
+            ));
+        }
+
+        let user = uchat_query::user::get(&mut conn, session.user_id)?;
+        let otpauth_uri = uchat_crypto::totp::otpauth_uri(&secret, "uchat", &user.handle);
 
         Ok((
             StatusCode::OK,
-            Json(LoginOk {
-                session_signature: signature.0,
-                session_id: session.id,
-                session_expires: Utc::now() + duration,
-                display_name: user.display_name,
-                email: user.email,
-                profile_image: profile_image_url,
-                user_id: user.id,
+            Json(EnableTwoFactorOk {
+                secret,
+                otpauth_uri,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ConfirmTwoFactor {
+    type Response = (StatusCode, Json<ConfirmTwoFactorOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let two_factor = match uchat_query::two_factor::get(&mut conn, session.user_id)? {
+            Some(two_factor) if !two_factor.is_enabled() => two_factor,
+            Some(_) => {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "two-factor login is already enabled",
+                ))
+            }
+            None => {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "two-factor setup has not been started",
+                ))
+            }
+        };
+
+        let now = Utc::now().timestamp() as u64;
+        let step = uchat_crypto::totp::verify(&two_factor.secret, &self.code, now)?
+            .ok_or_else(|| ApiError::new(StatusCode::BAD_REQUEST, "invalid code"))?;
+
+        let recovery_codes =
+            uchat_crypto::totp::new_recovery_codes(&mut rand::thread_rng(), RECOVERY_CODE_COUNT);
+        let code_hashes = recovery_codes
+            .iter()
+            .map(|code| {
+                uchat_crypto::token::hash_token(uchat_crypto::totp::normalize_recovery_code(code))
+            })
+            .collect();
+        uchat_query::two_factor::confirm_enrollment(
+            &mut conn,
+            session.user_id,
+            step as i64,
+            code_hashes,
+        )?;
+
+        tracing::info!(
+            user_id = session.user_id.to_string(),
+            "two-factor login enabled"
+        );
+
+        Ok((StatusCode::OK, Json(ConfirmTwoFactorOk { recovery_codes })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for DisableTwoFactor {
+    type Response = (StatusCode, Json<DisableTwoFactorOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let two_factor = uchat_query::two_factor::get(&mut conn, session.user_id)?
+            .filter(TwoFactor::is_enabled)
+            .ok_or_else(|| {
+                ApiError::new(StatusCode::BAD_REQUEST, "two-factor login is not enabled")
+            })?;
+
+        ensure_not_locked_out(&two_factor)?;
+
+        // a stolen session alone must not be enough to turn the second factor off
+        let user = uchat_query::user::get(&mut conn, session.user_id)?;
+        let hash = uchat_crypto::password::deserialize_hash(&user.password_hash)?;
+        uchat_crypto::verify_password(self.password, &hash)
+            .map_err(|_| ServerError::wrong_password())?;
+
+        if !verify_second_factor(&mut conn, &two_factor, &self.code)? {
+            uchat_query::two_factor::fail_attempt(&mut conn, session.user_id)?;
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "invalid code"));
+        }
+        uchat_query::two_factor::disable(&mut conn, session.user_id)?;
+
+        tracing::info!(
+            user_id = session.user_id.to_string(),
+            "two-factor login disabled"
+        );
+
+        Ok((StatusCode::OK, Json(DisableTwoFactorOk)))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for Logout {
+    type Response = (StatusCode, Json<LogoutOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        uchat_query::session::delete(&mut conn, session.user_id, session.session_id)?;
+
+        Ok((StatusCode::OK, Json(LogoutOk)))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ListSessions {
+    type Response = (StatusCode, Json<ListSessionsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let sessions = uchat_query::session::list(&mut conn, session.user_id)?
+            .into_iter()
+            .map(|listed| SessionInfo {
+                id: listed.id,
+                created_at: listed.created_at,
+                expires_at: listed.expires_at,
+                device: listed.user_agent().map(str::to_string),
+                current: listed.id == session.session_id,
+            })
+            .collect();
+
+        Ok((StatusCode::OK, Json(ListSessionsOk { sessions })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for RevokeSession {
+    type Response = (StatusCode, Json<RevokeSessionOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        use uchat_query::post::DeleteStatus;
+
+        match uchat_query::session::delete(&mut conn, session.user_id, self.session_id)? {
+            DeleteStatus::Deleted => Ok((
+                StatusCode::OK,
+                Json(RevokeSessionOk {
+                    session_id: self.session_id,
+                }),
+            )),
+            DeleteStatus::NotFound => {
+                Err(ApiError::new(StatusCode::NOT_FOUND, "session not found"))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for RevokeAllOtherSessions {
+    type Response = (StatusCode, Json<RevokeAllOtherSessionsOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let revoked = uchat_query::session::delete_all_except(
+            &mut conn,
+            session.user_id,
+            session.session_id,
+        )?;
+
+        Ok((
+            StatusCode::OK,
+            Json(RevokeAllOtherSessionsOk {
+                revoked: revoked as u64,
             }),
         ))
     }
@@ -165,6 +778,7 @@ impl AuthorizedApiRequest for GetMyProfile {
         _state: AppState,
     ) -> ApiResult<Self::Response> {
         let user = uchat_query::user::get(&mut conn, session.user_id)?;
+        let two_factor_enabled = uchat_query::two_factor::is_enabled(&mut conn, user.id)?;
 
         let profile_image_url = user.profile_image.as_ref().map(|id| profile_id_to_url(id));
 
@@ -173,6 +787,8 @@ impl AuthorizedApiRequest for GetMyProfile {
             Json(GetMyProfileOk {
                 display_name: user.display_name,
                 email: user.email,
+                email_confirmed: user.email_confirmed.is_some(),
+                two_factor_enabled,
                 profile_image: profile_image_url,
                 user_id: user.id,
             }),
@@ -188,7 +804,7 @@ impl AuthorizedApiRequest for UpdateProfile {
         self,
         DbConnection(mut conn): DbConnection,
         session: UserSession,
-        _state: AppState,
+        state: AppState,
     ) -> ApiResult<Self::Response> {
         let mut payload = self;
         let password = if let Update::Change(ref password) = payload.password {
@@ -211,10 +827,24 @@ impl AuthorizedApiRequest for UpdateProfile {
             profile_image: payload.profile_image.clone(),
         };
 
+        let previous_email = uchat_query::user::get(&mut conn, session.user_id)?.email;
         uchat_query::user::update_profile(&mut conn, query_params)?;
 
         let user = uchat_query::user::get(&mut conn, session.user_id)?;
 
+        if user.email != previous_email {
+            if let Some(email) = &user.email {
+                // the profile is already saved, and the link can be resent later
+                if let Err(e) = send_email_confirmation(&state, user.id, email).await {
+                    tracing::error!(
+                        err = %e.err,
+                        user_id = ?user.id,
+                        "failed to send email confirmation"
+                    );
+                }
+            }
+        }
+
         let profile_image_url = user.profile_image.as_ref().map(|id| profile_id_to_url(id));
 
         Ok((
@@ -237,21 +867,30 @@ impl AuthorizedApiRequest for ViewProfile {
         _state: AppState,
     ) -> ApiResult<Self::Response> {
         let profile = uchat_query::user::get(&mut conn, self.for_user)?;
-        let profile = to_public(&mut conn, Some(&session), profile)?;
+        let profile =
+            uchat_query::user::hydrate(&mut conn, vec![profile], Some(session.user_id))?.remove(0);
+        let profile = to_public(profile);
 
-        let mut posts = vec![];
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let page = uchat_query::post::get_public_posts(
+            &mut conn,
+            session.user_id,
+            self.for_user,
+            after,
+            self.page.size(),
+        )?;
+        let next_cursor = super::post::next_cursor(&self.page, &page);
 
-        for post in uchat_query::post::get_public_posts(&mut conn, self.for_user)? {
-            let post_id = post.id;
-            match super::post::to_public(&mut conn, post, Some(&session)) {
-                Ok(post) => posts.push(post),
-                Err(e) => {
-                    tracing::error!(err = %e.err, post_id = ?post_id, "post contains invalid data");
-                }
-            }
-        }
+        let posts = super::post::hydrate_posts(&mut conn, page, Some(&session))?;
 
-        Ok((StatusCode::OK, Json(ViewProfileOk { profile, posts })))
+        Ok((
+            StatusCode::OK,
+            Json(ViewProfileOk {
+                profile,
+                posts,
+                next_cursor,
+            }),
+        ))
     }
 }
 
@@ -275,7 +914,22 @@ impl AuthorizedApiRequest for FollowUser {
         }
         match self.action {
             FollowAction::Follow => {
+                ensure_not_blocked(&mut conn, session.user_id, self.user_id)?;
+                let already_following =
+                    uchat_query::user::is_following(&mut conn, session.user_id, self.user_id)?;
                 uchat_query::user::follow(&mut conn, session.user_id, self.user_id)?;
+                if !already_following {
+                    notify(
+                        &mut conn,
+                        NewNotification {
+                            user_id: self.user_id,
+                            actor: session.user_id,
+                            kind: NotificationKind::Follow,
+                            post_id: None,
+                            created_at: Utc::now(),
+                        },
+                    );
+                }
             }
             FollowAction::Unfollow => {
                 uchat_query::user::unfollow(&mut conn, session.user_id, self.user_id)?;
@@ -290,3 +944,293 @@ impl AuthorizedApiRequest for FollowUser {
         ))
     }
 }
+
+fn next_follow_cursor(page: &Page, follows: &[Follow]) -> Option<Cursor> {
+    if follows.len() as i64 == page.size() {
+        follows
+            .last()
+            .map(|follow| TimeCursor::from(follow).encode())
+    } else {
+        None
+    }
+}
+
+fn hydrate_follows(
+    conn: &mut uchat_query::AsyncConnection,
+    follows: Vec<Follow>,
+    viewer: UserId,
+) -> ApiResult<Vec<PublicUserProfile>> {
+    let users = follows.into_iter().map(|follow| follow.user).collect();
+    Ok(uchat_query::user::hydrate(conn, users, Some(viewer))?
+        .into_iter()
+        .map(to_public)
+        .collect())
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ListFollowers {
+    type Response = (StatusCode, Json<ListFollowersOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let follows =
+            uchat_query::user::get_followers(&mut conn, self.user_id, after, self.page.size())?;
+        let next_cursor = next_follow_cursor(&self.page, &follows);
+        let users = hydrate_follows(&mut conn, follows, session.user_id)?;
+
+        Ok((StatusCode::OK, Json(ListFollowersOk { users, next_cursor })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ListFollowing {
+    type Response = (StatusCode, Json<ListFollowingOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let follows =
+            uchat_query::user::get_following(&mut conn, self.user_id, after, self.page.size())?;
+        let next_cursor = next_follow_cursor(&self.page, &follows);
+        let users = hydrate_follows(&mut conn, follows, session.user_id)?;
+
+        Ok((StatusCode::OK, Json(ListFollowingOk { users, next_cursor })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for BlockUser {
+    type Response = (StatusCode, Json<BlockUserOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        if self.user_id == session.user_id {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "cannot block self"));
+        }
+        uchat_query::user::get(&mut conn, self.user_id)
+            .map_err(|_| ApiError::new(StatusCode::NOT_FOUND, "user not found"))?;
+        uchat_query::user::block(&mut conn, session.user_id, self.user_id)?;
+
+        Ok((
+            StatusCode::OK,
+            Json(BlockUserOk {
+                user_id: self.user_id,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for UnblockUser {
+    type Response = (StatusCode, Json<UnblockUserOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        uchat_query::user::unblock(&mut conn, session.user_id, self.user_id)?;
+
+        Ok((
+            StatusCode::OK,
+            Json(UnblockUserOk {
+                user_id: self.user_id,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for ListBlocked {
+    type Response = (StatusCode, Json<ListBlockedOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let after = decode_cursor(&self.page, TimeCursor::decode)?;
+        let blocked =
+            uchat_query::user::get_blocked(&mut conn, session.user_id, after, self.page.size())?;
+        let next_cursor = match blocked.len() as i64 == self.page.size() {
+            true => blocked
+                .last()
+                .map(|blocked| TimeCursor::from(blocked).encode()),
+            false => None,
+        };
+
+        let users = blocked
+            .into_iter()
+            .map(|Blocked { user, .. }| user)
+            .collect();
+        let users = uchat_query::user::hydrate(&mut conn, users, Some(session.user_id))?
+            .into_iter()
+            .map(to_public)
+            .collect();
+
+        Ok((StatusCode::OK, Json(ListBlockedOk { users, next_cursor })))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for MuteUser {
+    type Response = (StatusCode, Json<MuteUserOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        if self.user_id == session.user_id {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "cannot mute self"));
+        }
+        uchat_query::user::get(&mut conn, self.user_id)
+            .map_err(|_| ApiError::new(StatusCode::NOT_FOUND, "user not found"))?;
+
+        let expires_at = self
+            .duration
+            .duration()
+            .map(|duration| Utc::now() + duration);
+        uchat_query::user::mute(
+            &mut conn,
+            session.user_id,
+            MuteTarget::User(self.user_id),
+            expires_at,
+        )?;
+
+        Ok((
+            StatusCode::OK,
+            Json(MuteUserOk {
+                user_id: self.user_id,
+                expires_at,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for UnmuteUser {
+    type Response = (StatusCode, Json<UnmuteUserOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        uchat_query::user::unmute(&mut conn, session.user_id, MuteTarget::User(self.user_id))?;
+
+        Ok((
+            StatusCode::OK,
+            Json(UnmuteUserOk {
+                user_id: self.user_id,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for MuteThread {
+    type Response = (StatusCode, Json<MuteThreadOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let not_found = || ApiError::new(StatusCode::NOT_FOUND, "post not found");
+
+        let post = uchat_query::post::get(&mut conn, self.post_id).map_err(|_| not_found())?;
+        if post.direct_message_to.is_some()
+            || !super::post::is_visible_to(&post, session.user_id)
+            || uchat_query::user::is_blocked_between(&mut conn, session.user_id, post.user_id)?
+        {
+            return Err(not_found());
+        }
+
+        let expires_at = self
+            .duration
+            .duration()
+            .map(|duration| Utc::now() + duration);
+        uchat_query::user::mute(
+            &mut conn,
+            session.user_id,
+            MuteTarget::Thread(post.id),
+            expires_at,
+        )?;
+
+        Ok((
+            StatusCode::OK,
+            Json(MuteThreadOk {
+                post_id: post.id,
+                expires_at,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorizedApiRequest for UnmuteThread {
+    type Response = (StatusCode, Json<UnmuteThreadOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        uchat_query::user::unmute(&mut conn, session.user_id, MuteTarget::Thread(self.post_id))?;
+
+        Ok((
+            StatusCode::OK,
+            Json(UnmuteThreadOk {
+                post_id: self.post_id,
+            }),
+        ))
+    }
+}
+
+/// Most users returned by a single search.
+const USER_SEARCH_LIMIT: i64 = 20;
+
+#[async_trait]
+impl AuthorizedApiRequest for SearchUsers {
+    type Response = (StatusCode, Json<SearchUsersOk>);
+
+    async fn process_request(
+        self,
+        DbConnection(mut conn): DbConnection,
+        session: UserSession,
+        _state: AppState,
+    ) -> ApiResult<Self::Response> {
+        let query = self.query.trim();
+        if query.is_empty() {
+            return Ok((StatusCode::OK, Json(SearchUsersOk { users: vec![] })));
+        }
+
+        let users = uchat_query::user::search(&mut conn, query, USER_SEARCH_LIMIT)?;
+        let users = uchat_query::user::hydrate(&mut conn, users, Some(session.user_id))?
+            .into_iter()
+            .map(to_public)
+            .collect();
+
+        Ok((StatusCode::OK, Json(SearchUsersOk { users })))
+    }
+}
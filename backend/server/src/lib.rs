@@ -1,10 +1,15 @@
+use std::sync::Arc;
+
 use axum::extract::FromRef;
 use uchat_query::{AsyncConnection, AsyncConnectionPool, QueryError};
+use url::Url;
 
+pub mod background;
 pub mod error;
 pub mod extractor;
 pub mod handler;
 pub mod logging;
+pub mod mailer;
 pub mod router;
 
 #[derive(FromRef, Clone)]
@@ -12,6 +17,11 @@ pub struct AppState {
     pub db_pool: AsyncConnectionPool,
     pub signing_keys: uchat_crypto::sign::Keys,
     pub rng: rand::rngs::StdRng,
+    pub reaction_emojis: ReactionEmojis,
+    pub session_binding: SessionBinding,
+    pub mailer: Arc<dyn mailer::Mailer>,
+    /// Where the frontend is served, for links sent in emails.
+    pub frontend_url: Url,
 }
 
 impl AppState {
@@ -20,6 +30,56 @@ impl AppState {
     }
 }
 
+/// Emoji used when no reaction set is configured.
+pub const DEFAULT_REACTION_EMOJIS: [&str; 6] = ["👍", "❤️", "😂", "😮", "😢", "🎉"];
+
+/// The emoji which posts can be reacted to with.
+#[derive(Clone, Debug)]
+pub struct ReactionEmojis(Arc<[String]>);
+
+impl ReactionEmojis {
+    pub fn new<I, T>(emojis: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let mut unique: Vec<String> = vec![];
+        for emoji in emojis {
+            let emoji = emoji.into().trim().to_string();
+            if !emoji.is_empty() && !unique.contains(&emoji) {
+                unique.push(emoji);
+            }
+        }
+        Self(unique.into())
+    }
+
+    pub fn contains(&self, emoji: &str) -> bool {
+        self.0.iter().any(|allowed| allowed == emoji)
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.0.to_vec()
+    }
+}
+
+impl Default for ReactionEmojis {
+    fn default() -> Self {
+        Self::new(DEFAULT_REACTION_EMOJIS)
+    }
+}
+
+/// What to do when a session is used from a device other than the one which logged in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SessionBinding {
+    /// Sessions may be used from any device.
+    #[default]
+    Off,
+    /// Mismatched sessions are allowed, but logged as suspicious.
+    Flag,
+    /// Mismatched sessions are treated as logged out.
+    Reject,
+}
+
 pub mod cli {
     use color_eyre::{eyre::Context, Help};
     use rand_core::{CryptoRng, RngCore};
@@ -60,12 +120,12 @@ pub mod tests {
             Router,
         };
         use hyper::Request;
-        use serde::Serialize;
+        use serde::{de::DeserializeOwned, Serialize};
         use tower::ServiceExt;
         use uchat_crypto::sign::Keys;
         use uchat_query::AsyncConnectionPool;
 
-        use crate::AppState;
+        use crate::{mailer::MemoryMailer, AppState, ReactionEmojis, SessionBinding};
 
         pub async fn new_state() -> AppState {
             let connection_url = dotenvy::var("TEST_DATABASE_URL")
@@ -76,6 +136,10 @@ pub mod tests {
                 db_pool: AsyncConnectionPool::new(connection_url).await.unwrap(),
                 signing_keys: Keys::generate(&mut rng).unwrap().1,
                 rng,
+                reaction_emojis: ReactionEmojis::default(),
+                session_binding: SessionBinding::default(),
+                mailer: std::sync::Arc::new(MemoryMailer::default()),
+                frontend_url: "http://localhost:8080".parse().unwrap(),
             }
         }
 
@@ -109,6 +173,69 @@ pub mod tests {
             let router = new_router().await;
             api_request_with_router(router, uri, payload).await
         }
+
+        /// Sends `payload` with the session `cookies` from [`new_user_session`].
+        pub async fn authorized_request_with_router<P: Serialize>(
+            router: Router,
+            uri: &str,
+            payload: P,
+            cookies: &str,
+        ) -> Response {
+            let payload = serde_json::to_string(&payload).unwrap();
+
+            router
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .header("Content-Type", "application/json")
+                        .header("Cookie", cookies)
+                        .uri(uri)
+                        .body(payload.into())
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+                .into_response()
+        }
+
+        pub async fn response_json<T: DeserializeOwned>(response: Response) -> T {
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            serde_json::from_slice(&body).unwrap()
+        }
+
+        /// Creates a user with a random name and the password "password", returning the name and
+        /// the cookies of its session.
+        pub async fn new_user_session(router: Router) -> (String, String) {
+            use rand::distributions::Alphanumeric;
+            use rand::{thread_rng, Rng};
+            use uchat_domain::{Password, Username};
+            use uchat_endpoint::{
+                user::{CreateUser, CreateUserOk},
+                Endpoint,
+            };
+
+            let username: String = thread_rng()
+                .sample_iter(Alphanumeric)
+                .take(20)
+                .map(char::from)
+                .collect();
+            let payload = CreateUser {
+                username: Username::new(&username).unwrap(),
+                password: Password::new("password").unwrap(),
+                device_id: None,
+            };
+            let response = api_request_with_router(router, CreateUser::URL, payload).await;
+            let created: CreateUserOk = response_json(response).await;
+
+            let cookies = format!(
+                "{}={}; {}={}",
+                uchat_cookie::SESSION_ID,
+                created.session_id.to_string(),
+                uchat_cookie::SESSION_SIGNATURE,
+                created.session_signature
+            );
+            (username, cookies)
+        }
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -127,6 +254,7 @@ pub mod tests {
             let payload = CreateUser {
                 password: Password::new("password")?,
                 username: Username::new(&username)?,
+                device_id: None,
             };
 
             let response = util::api_request(CreateUser::URL, payload).await;
@@ -144,6 +272,7 @@ pub mod tests {
             let payload = CreateUser {
                 password: Password::new("password")?,
                 username: Username::new(username)?,
+                device_id: None,
             };
             let response = util::api_request(CreateUser::URL, payload).await;
 
@@ -152,4 +281,525 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn reset_password_by_email() -> Result<()> {
+        use std::sync::Arc;
+
+        use rand::distributions::Alphanumeric;
+        use rand::{thread_rng, Rng};
+        use uchat_endpoint::{
+            user::{Login, RequestPasswordReset, ResetPassword},
+            Update,
+        };
+        use uchat_query::user::UpdateProfileParams;
+
+        use crate::mailer::MemoryMailer;
+
+        let new_username = || -> String {
+            thread_rng()
+                .sample_iter(Alphanumeric)
+                .take(20)
+                .map(char::from)
+                .collect()
+        };
+        let username = new_username();
+        let other_username = new_username();
+
+        let mailer = MemoryMailer::default();
+        let mut state = util::new_state().await;
+        state.mailer = Arc::new(mailer.clone());
+
+        // users with confirmed email addresses
+        for username in [&username, &other_username] {
+            let mut conn = state.connect().await?;
+            let email = format!("{username}@example.com");
+            let hash = uchat_crypto::hash_password("password")?;
+            let user_id = uchat_query::user::new(&mut conn, hash, username)?;
+            uchat_query::user::update_profile(
+                &mut conn,
+                UpdateProfileParams {
+                    id: user_id,
+                    display_name: Update::NoChange,
+                    email: Update::Change(email.clone()),
+                    password_hash: Update::NoChange,
+                    profile_image: Update::NoChange,
+                },
+            )?;
+            uchat_query::user::confirm_email(&mut conn, user_id, &email)?;
+        }
+        let router = crate::router::new_router(state);
+
+        // request links
+        for username in [&username, &other_username] {
+            let payload = RequestPasswordReset {
+                email: format!("{username}@example.com"),
+            };
+            let response =
+                util::api_request_with_router(router.clone(), RequestPasswordReset::URL, payload)
+                    .await;
+            assert_eq!(StatusCode::OK, response.status());
+        }
+
+        // emails are sent in the background
+        let mut sent = mailer.sent();
+        for _ in 0..100 {
+            if sent.len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            sent = mailer.sent();
+        }
+        assert_eq!(sent.len(), 2);
+        let token_for = |username: &str| {
+            let email = sent
+                .iter()
+                .find(|email| email.to == format!("{username}@example.com"))
+                .expect("missing reset email");
+            email
+                .body
+                .split("reset_password/")
+                .nth(1)
+                .and_then(|rest| rest.split_whitespace().next())
+                .expect("missing reset link")
+                .to_string()
+        };
+        let token = token_for(&username);
+
+        // each link is only good for its own account
+        assert_ne!(token, token_for(&other_username));
+
+        // reset
+        let payload = ResetPassword {
+            token: token.clone(),
+            password: Password::new("new password")?,
+        };
+        let response =
+            util::api_request_with_router(router.clone(), ResetPassword::URL, payload).await;
+        assert_eq!(StatusCode::OK, response.status());
+
+        let payload = Login {
+            username: Username::new(&username)?,
+            password: Password::new("new password")?,
+            device_id: None,
+        };
+        let response = util::api_request_with_router(router.clone(), Login::URL, payload).await;
+        assert_eq!(StatusCode::OK, response.status());
+
+        // links only work once
+        let payload = ResetPassword {
+            token,
+            password: Password::new("another password")?,
+        };
+        let response = util::api_request_with_router(router, ResetPassword::URL, payload).await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn login_with_two_factor() -> Result<()> {
+        use rand::distributions::Alphanumeric;
+        use rand::{thread_rng, Rng};
+        use uchat_endpoint::user::{CompleteLogin, CompleteLoginOk, Login, LoginOk};
+
+        let username: String = thread_rng()
+            .sample_iter(Alphanumeric)
+            .take(20)
+            .map(char::from)
+            .collect();
+
+        let state = util::new_state().await;
+
+        // user with an authenticator app and a recovery code
+        let secret = {
+            let mut conn = state.connect().await?;
+            let hash = uchat_crypto::hash_password("password")?;
+            let user_id = uchat_query::user::new(&mut conn, hash, &username)?;
+            let secret = uchat_crypto::totp::new_secret(&mut thread_rng());
+            uchat_query::two_factor::begin_enrollment(&mut conn, user_id, &secret)?;
+            let code_hashes = vec![uchat_crypto::token::hash_token("recoverycode")];
+            uchat_query::two_factor::confirm_enrollment(&mut conn, user_id, 0, code_hashes)?;
+            secret
+        };
+        let router = crate::router::new_router(state);
+
+        let login = || Login {
+            username: Username::new(&username).unwrap(),
+            password: Password::new("password").unwrap(),
+            device_id: None,
+        };
+        let pending_login =
+            |response: hyper::body::Bytes| match serde_json::from_slice(&response).unwrap() {
+                LoginOk::TwoFactorRequired { pending_token } => pending_token,
+                LoginOk::LoggedIn(_) => panic!("logged in without a second factor"),
+            };
+
+        // the password alone is not enough
+        let response = util::api_request_with_router(router.clone(), Login::URL, login()).await;
+        assert_eq!(StatusCode::OK, response.status());
+        let pending_token = pending_login(hyper::body::to_bytes(response.into_body()).await?);
+
+        let now = chrono::Utc::now().timestamp() as u64;
+        let code = uchat_crypto::totp::code_at(&secret, now)?;
+        let payload = CompleteLogin {
+            pending_token: pending_token.clone(),
+            code: code.clone(),
+        };
+        let response =
+            util::api_request_with_router(router.clone(), CompleteLogin::URL, payload).await;
+        assert_eq!(StatusCode::OK, response.status());
+        let response = hyper::body::to_bytes(response.into_body()).await?;
+        let _: CompleteLoginOk = serde_json::from_slice(&response)?;
+
+        // neither pending tokens nor codes can be reused
+        let payload = CompleteLogin {
+            pending_token,
+            code: code.clone(),
+        };
+        let response =
+            util::api_request_with_router(router.clone(), CompleteLogin::URL, payload).await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+        let response = util::api_request_with_router(router.clone(), Login::URL, login()).await;
+        let pending_token = pending_login(hyper::body::to_bytes(response.into_body()).await?);
+        let payload = CompleteLogin {
+            pending_token: pending_token.clone(),
+            code,
+        };
+        let response =
+            util::api_request_with_router(router.clone(), CompleteLogin::URL, payload).await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+        // recovery codes work in place of the app
+        let payload = CompleteLogin {
+            pending_token,
+            code: "RECO-VERY-CODE".to_string(),
+        };
+        let response = util::api_request_with_router(router, CompleteLogin::URL, payload).await;
+        assert_eq!(StatusCode::OK, response.status());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn wrong_codes_lock_out_the_second_factor() -> Result<()> {
+        use rand::distributions::Alphanumeric;
+        use rand::{thread_rng, Rng};
+        use uchat_endpoint::user::{CompleteLogin, Login, LoginOk};
+
+        let username: String = thread_rng()
+            .sample_iter(Alphanumeric)
+            .take(20)
+            .map(char::from)
+            .collect();
+
+        let state = util::new_state().await;
+
+        let secret = {
+            let mut conn = state.connect().await?;
+            let hash = uchat_crypto::hash_password("password")?;
+            let user_id = uchat_query::user::new(&mut conn, hash, &username)?;
+            let secret = uchat_crypto::totp::new_secret(&mut thread_rng());
+            uchat_query::two_factor::begin_enrollment(&mut conn, user_id, &secret)?;
+            uchat_query::two_factor::confirm_enrollment(&mut conn, user_id, 0, vec![])?;
+            secret
+        };
+        let router = crate::router::new_router(state);
+
+        let login = || Login {
+            username: Username::new(&username).unwrap(),
+            password: Password::new("password").unwrap(),
+            device_id: None,
+        };
+
+        // every login gets a new pending token, but wrong codes add up for the user
+        let mut pending_token = String::new();
+        for _ in 0..5 {
+            let response = util::api_request_with_router(router.clone(), Login::URL, login()).await;
+            assert_eq!(StatusCode::OK, response.status());
+            let response = hyper::body::to_bytes(response.into_body()).await?;
+            pending_token = match serde_json::from_slice(&response)? {
+                LoginOk::TwoFactorRequired { pending_token } => pending_token,
+                LoginOk::LoggedIn(_) => panic!("logged in without a second factor"),
+            };
+
+            let payload = CompleteLogin {
+                pending_token: pending_token.clone(),
+                code: "WRONG-CODE".to_string(),
+            };
+            let response =
+                util::api_request_with_router(router.clone(), CompleteLogin::URL, payload).await;
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        }
+
+        // no new logins are started
+        let response = util::api_request_with_router(router.clone(), Login::URL, login()).await;
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+
+        // and even the right code is refused for logins already started
+        let now = chrono::Utc::now().timestamp() as u64;
+        let payload = CompleteLogin {
+            pending_token,
+            code: uchat_crypto::totp::code_at(&secret, now)?,
+        };
+        let response = util::api_request_with_router(router, CompleteLogin::URL, payload).await;
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn two_factor_is_separate_for_each_user() -> Result<()> {
+        use uchat_endpoint::user::{
+            CompleteLogin, CompleteLoginOk, ConfirmTwoFactor, ConfirmTwoFactorOk, DisableTwoFactor,
+            EnableTwoFactor, EnableTwoFactorOk, Login, LoginOk,
+        };
+
+        let router = util::new_router().await;
+        let first = util::new_user_session(router.clone()).await;
+        let second = util::new_user_session(router.clone()).await;
+
+        // enroll both users
+        let mut enrolled = vec![];
+        for (_, cookies) in [&first, &second] {
+            let response = util::authorized_request_with_router(
+                router.clone(),
+                EnableTwoFactor::URL,
+                EnableTwoFactor,
+                cookies,
+            )
+            .await;
+            assert_eq!(StatusCode::OK, response.status());
+            let enrollment: EnableTwoFactorOk = util::response_json(response).await;
+
+            let now = chrono::Utc::now().timestamp() as u64;
+            let payload = ConfirmTwoFactor {
+                code: uchat_crypto::totp::code_at(&enrollment.secret, now)?,
+            };
+            let response = util::authorized_request_with_router(
+                router.clone(),
+                ConfirmTwoFactor::URL,
+                payload,
+                cookies,
+            )
+            .await;
+            assert_eq!(StatusCode::OK, response.status());
+            let confirmed: ConfirmTwoFactorOk = util::response_json(response).await;
+
+            enrolled.push((enrollment.secret, confirmed.recovery_codes));
+        }
+        assert_ne!(enrolled[0].0, enrolled[1].0);
+        assert!(enrolled[0]
+            .1
+            .iter()
+            .all(|code| !enrolled[1].1.contains(code)));
+
+        // both users can be waiting for their second factor at once
+        let mut pending_tokens = vec![];
+        for (username, _) in [&first, &second] {
+            let payload = Login {
+                username: Username::new(username)?,
+                password: Password::new("password")?,
+                device_id: None,
+            };
+            let response = util::api_request_with_router(router.clone(), Login::URL, payload).await;
+            assert_eq!(StatusCode::OK, response.status());
+            match util::response_json(response).await {
+                LoginOk::TwoFactorRequired { pending_token } => pending_tokens.push(pending_token),
+                LoginOk::LoggedIn(_) => panic!("logged in without a second factor"),
+            }
+        }
+        assert_ne!(pending_tokens[0], pending_tokens[1]);
+
+        // recovery codes only work for their own user
+        let payload = CompleteLogin {
+            pending_token: pending_tokens[0].clone(),
+            code: enrolled[1].1[0].clone(),
+        };
+        let response =
+            util::api_request_with_router(router.clone(), CompleteLogin::URL, payload).await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+        for (pending_token, (_, recovery_codes)) in pending_tokens.into_iter().zip(&enrolled) {
+            let payload = CompleteLogin {
+                pending_token,
+                code: recovery_codes[0].clone(),
+            };
+            let response =
+                util::api_request_with_router(router.clone(), CompleteLogin::URL, payload).await;
+            assert_eq!(StatusCode::OK, response.status());
+            let _: CompleteLoginOk = util::response_json(response).await;
+        }
+
+        // turning two-factor login off needs the password, and wrong codes are limited
+        let (_, cookies) = &first;
+        let payload = DisableTwoFactor {
+            password: Password::new("wrong password")?,
+            code: enrolled[0].1[1].clone(),
+        };
+        let response = util::authorized_request_with_router(
+            router.clone(),
+            DisableTwoFactor::URL,
+            payload,
+            cookies,
+        )
+        .await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+        let mut statuses = vec![];
+        for _ in 0..6 {
+            let payload = DisableTwoFactor {
+                password: Password::new("password")?,
+                code: "wrong-code".to_string(),
+            };
+            let response = util::authorized_request_with_router(
+                router.clone(),
+                DisableTwoFactor::URL,
+                payload,
+                cookies,
+            )
+            .await;
+            statuses.push(response.status());
+        }
+        assert_eq!(statuses.last(), Some(&StatusCode::TOO_MANY_REQUESTS));
+
+        let payload = DisableTwoFactor {
+            password: Password::new("password")?,
+            code: enrolled[0].1[1].clone(),
+        };
+        let response =
+            util::authorized_request_with_router(router, DisableTwoFactor::URL, payload, cookies)
+                .await;
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn renewed_sessions_report_expiry_on_errors() -> Result<()> {
+        use uchat_domain::ids::SessionId;
+        use uchat_endpoint::user::DisableTwoFactor;
+
+        let state = util::new_state().await;
+        let router = crate::router::new_router(state.clone());
+        let (_, cookies) = util::new_user_session(router.clone()).await;
+
+        // a session close to expiring
+        let session_id: SessionId = cookies
+            .split("; ")
+            .find_map(|cookie| cookie.strip_prefix(&format!("{}=", uchat_cookie::SESSION_ID)))
+            .expect("missing session id")
+            .parse()?;
+        let mut conn = state.connect().await?;
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+        uchat_query::session::renew(&mut conn, session_id, expires_at)?;
+        drop(conn);
+
+        // two-factor login isn't enabled, so the request fails
+        let payload = DisableTwoFactor {
+            password: Password::new("password")?,
+            code: "123456".to_string(),
+        };
+        let response =
+            util::authorized_request_with_router(router, DisableTwoFactor::URL, payload, &cookies)
+                .await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert!(response
+            .headers()
+            .contains_key(uchat_cookie::SESSION_EXPIRES_HEADER));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn confirm_email_by_link() -> Result<()> {
+        use std::sync::Arc;
+
+        use uchat_endpoint::{
+            user::{ConfirmEmail, ConfirmEmailOk, GetMyProfile, GetMyProfileOk, UpdateProfile},
+            Update,
+        };
+
+        use crate::mailer::MemoryMailer;
+
+        let mailer = MemoryMailer::default();
+        let mut state = util::new_state().await;
+        state.mailer = Arc::new(mailer.clone());
+        let router = crate::router::new_router(state.clone());
+        let (username, cookies) = util::new_user_session(router.clone()).await;
+        let email = format!("{}@example.com", username.to_lowercase());
+
+        // changing the address sends a link, to the address as it is stored
+        let payload = UpdateProfile {
+            display_name: Update::NoChange,
+            email: Update::Change(format!(" {username}@Example.com")),
+            profile_image: Update::NoChange,
+            password: Update::NoChange,
+        };
+        let response = util::authorized_request_with_router(
+            router.clone(),
+            UpdateProfile::URL,
+            payload,
+            &cookies,
+        )
+        .await;
+        assert_eq!(StatusCode::OK, response.status());
+        let sent = mailer.sent();
+        let link = sent
+            .iter()
+            .find(|sent| sent.to == email)
+            .expect("missing confirmation email");
+        let token = link
+            .body
+            .split("confirm_email/")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .expect("missing confirmation link")
+            .to_string();
+
+        // signatures made for other purposes are rejected
+        let response = util::authorized_request_with_router(
+            router.clone(),
+            GetMyProfile::URL,
+            GetMyProfile,
+            &cookies,
+        )
+        .await;
+        let profile: GetMyProfileOk = util::response_json(response).await;
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(1);
+        let payload = format!(
+            "{}:{}:{email}",
+            profile.user_id.to_string(),
+            expires_at.timestamp()
+        );
+        let signature = state
+            .signing_keys
+            .sign(&mut state.rng.clone(), payload.as_bytes());
+        let forged = format!(
+            "{}.{}",
+            uchat_crypto::encode_base64_url(payload),
+            uchat_crypto::encode_base64_url(signature)
+        );
+        let payload = ConfirmEmail { token: forged };
+        let response =
+            util::api_request_with_router(router.clone(), ConfirmEmail::URL, payload).await;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+        // confirm
+        let payload = ConfirmEmail { token };
+        let response =
+            util::api_request_with_router(router.clone(), ConfirmEmail::URL, payload).await;
+        assert_eq!(StatusCode::OK, response.status());
+        let confirmed: ConfirmEmailOk = util::response_json(response).await;
+        assert_eq!(confirmed.email, email);
+
+        let response =
+            util::authorized_request_with_router(router, GetMyProfile::URL, GetMyProfile, &cookies)
+                .await;
+        let profile: GetMyProfileOk = util::response_json(response).await;
+        assert!(profile.email_confirmed);
+
+        Ok(())
+    }
 }
@@ -3,7 +3,11 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use hyper::{header::CONTENT_TYPE, http::HeaderValue, Method};
+use hyper::{
+    header::{HeaderName, CONTENT_TYPE},
+    http::HeaderValue,
+    Method,
+};
 use tower::ServiceBuilder;
 use tower_http::{
     cors::CorsLayer,
@@ -13,11 +17,20 @@ use tower_http::{
 };
 use tracing::Level;
 use uchat_endpoint::{
+    notification::{ListNotifications, MarkNotificationsRead, UnreadCount},
     post::{
-        Bookmark, BookmarkedPosts, Boost, HomePosts, LikedPosts, NewPost, React, TrendingPosts,
-        Vote,
+        Bookmark, BookmarkedPosts, Boost, CancelScheduledPost, DeletePost, EditPost, EmojiReact,
+        GetConversation, GetPostRevisions, GetReactionEmojis, GetThread, HomePosts, LikedPosts,
+        ListConversations, ListScheduledPosts, MentionedPosts, NewPost, React, ReschedulePost,
+        SearchPosts, TagTimeline, TrendingPosts, TrendingTags, Vote,
+    },
+    user::{
+        BlockUser, CompleteLogin, ConfirmEmail, ConfirmTwoFactor, CreateUser, DisableTwoFactor,
+        EnableTwoFactor, FollowUser, GetMyProfile, ListBlocked, ListFollowers, ListFollowing,
+        ListSessions, Login, Logout, MuteThread, MuteUser, RequestPasswordReset,
+        ResendEmailConfirmation, ResetPassword, RevokeAllOtherSessions, RevokeSession, SearchUsers,
+        UnblockUser, UnmuteThread, UnmuteUser, UpdateProfile, ViewProfile,
     },
-    user::{CreateUser, FollowUser, GetMyProfile, Login, UpdateProfile, ViewProfile},
     Endpoint,
 };
 
@@ -35,21 +48,103 @@ pub fn new_router(state: AppState) -> Router {
         .route("/", get(move || async { "this is the root page" }))
         .route(&format!("/{img_route}:id"), get(load_image))
         .route(CreateUser::URL, post(with_public_handler::<CreateUser>))
-        .route(Login::URL, post(with_public_handler::<Login>));
+        .route(Login::URL, post(with_public_handler::<Login>))
+        .route(
+            CompleteLogin::URL,
+            post(with_public_handler::<CompleteLogin>),
+        )
+        .route(ConfirmEmail::URL, post(with_public_handler::<ConfirmEmail>))
+        .route(
+            RequestPasswordReset::URL,
+            post(with_public_handler::<RequestPasswordReset>),
+        )
+        .route(
+            ResetPassword::URL,
+            post(with_public_handler::<ResetPassword>),
+        );
     let authorized_routes = Router::new()
         .route(NewPost::URL, post(with_handler::<NewPost>))
+        .route(DeletePost::URL, post(with_handler::<DeletePost>))
+        .route(EditPost::URL, post(with_handler::<EditPost>))
+        .route(
+            GetPostRevisions::URL,
+            post(with_handler::<GetPostRevisions>),
+        )
         .route(Bookmark::URL, post(with_handler::<Bookmark>))
         .route(Boost::URL, post(with_handler::<Boost>))
         .route(Vote::URL, post(with_handler::<Vote>))
         .route(React::URL, post(with_handler::<React>))
+        .route(EmojiReact::URL, post(with_handler::<EmojiReact>))
+        .route(
+            GetReactionEmojis::URL,
+            post(with_handler::<GetReactionEmojis>),
+        )
+        .route(GetThread::URL, post(with_handler::<GetThread>))
         .route(TrendingPosts::URL, post(with_handler::<TrendingPosts>))
         .route(HomePosts::URL, post(with_handler::<HomePosts>))
         .route(LikedPosts::URL, post(with_handler::<LikedPosts>))
         .route(BookmarkedPosts::URL, post(with_handler::<BookmarkedPosts>))
+        .route(MentionedPosts::URL, post(with_handler::<MentionedPosts>))
+        .route(TagTimeline::URL, post(with_handler::<TagTimeline>))
+        .route(TrendingTags::URL, post(with_handler::<TrendingTags>))
+        .route(SearchPosts::URL, post(with_handler::<SearchPosts>))
+        .route(
+            ListScheduledPosts::URL,
+            post(with_handler::<ListScheduledPosts>),
+        )
+        .route(ReschedulePost::URL, post(with_handler::<ReschedulePost>))
+        .route(
+            CancelScheduledPost::URL,
+            post(with_handler::<CancelScheduledPost>),
+        )
+        .route(
+            ListConversations::URL,
+            post(with_handler::<ListConversations>),
+        )
+        .route(GetConversation::URL, post(with_handler::<GetConversation>))
+        .route(
+            ResendEmailConfirmation::URL,
+            post(with_handler::<ResendEmailConfirmation>),
+        )
+        .route(EnableTwoFactor::URL, post(with_handler::<EnableTwoFactor>))
+        .route(
+            ConfirmTwoFactor::URL,
+            post(with_handler::<ConfirmTwoFactor>),
+        )
+        .route(
+            DisableTwoFactor::URL,
+            post(with_handler::<DisableTwoFactor>),
+        )
+        .route(Logout::URL, post(with_handler::<Logout>))
+        .route(ListSessions::URL, post(with_handler::<ListSessions>))
+        .route(RevokeSession::URL, post(with_handler::<RevokeSession>))
+        .route(
+            RevokeAllOtherSessions::URL,
+            post(with_handler::<RevokeAllOtherSessions>),
+        )
         .route(GetMyProfile::URL, post(with_handler::<GetMyProfile>))
         .route(UpdateProfile::URL, post(with_handler::<UpdateProfile>))
         .route(ViewProfile::URL, post(with_handler::<ViewProfile>))
         .route(FollowUser::URL, post(with_handler::<FollowUser>))
+        .route(ListFollowers::URL, post(with_handler::<ListFollowers>))
+        .route(ListFollowing::URL, post(with_handler::<ListFollowing>))
+        .route(BlockUser::URL, post(with_handler::<BlockUser>))
+        .route(UnblockUser::URL, post(with_handler::<UnblockUser>))
+        .route(ListBlocked::URL, post(with_handler::<ListBlocked>))
+        .route(MuteUser::URL, post(with_handler::<MuteUser>))
+        .route(UnmuteUser::URL, post(with_handler::<UnmuteUser>))
+        .route(MuteThread::URL, post(with_handler::<MuteThread>))
+        .route(UnmuteThread::URL, post(with_handler::<UnmuteThread>))
+        .route(SearchUsers::URL, post(with_handler::<SearchUsers>))
+        .route(
+            ListNotifications::URL,
+            post(with_handler::<ListNotifications>),
+        )
+        .route(
+            MarkNotificationsRead::URL,
+            post(with_handler::<MarkNotificationsRead>),
+        )
+        .route(UnreadCount::URL, post(with_handler::<UnreadCount>))
         .layer(DefaultBodyLimit::disable())
         .layer(RequestBodyLimitLayer::new(8 * 1024 * 1024));
 
@@ -78,7 +173,10 @@ pub fn new_router(state: AppState) -> Router {
                                 .parse::<HeaderValue>()
                                 .unwrap(),
                         )
-                        .allow_headers([CONTENT_TYPE]),
+                        .allow_headers([CONTENT_TYPE])
+                        .expose_headers([HeaderName::from_static(
+                            uchat_cookie::SESSION_EXPIRES_HEADER,
+                        )]),
                 )
                 .layer(axum::Extension(state.clone())),
         )
@@ -13,12 +13,17 @@ pub static TOASTER: AtomRef<Toaster> = |_| Toaster::default();
 pub static POST_MANAGER: AtomRef<PostManager> = |_| PostManager::default();
 pub static LOCAL_PROFILE: AtomRef<LocalProfile> = |_| LocalProfile::default();
 pub static SIDEBAR: AtomRef<SidebarManager> = |_| SidebarManager::default();
+/// Emoji available for reactions; loaded the first time a reaction picker is opened.
+pub static REACTION_EMOJIS: AtomRef<Vec<String>> = |_| Vec::new();
+/// Number of unread notifications, shown on the navbar.
+pub static UNREAD_NOTIFICATIONS: AtomRef<i64> = |_| 0;
 
 pub fn Init(cx: Scope) -> Element {
     let api_client = ApiClient::global();
     let router = use_router(cx);
     let toaster = use_toaster(cx);
     let local_profile = use_local_profile(cx);
+    let on_account_page = use_route(cx).url().path().starts_with(page::ACCOUNT_ROOT);
 
     let _fetch_local_profile = {
         to_owned![api_client, toaster, router, local_profile];
@@ -30,6 +35,7 @@ pub fn Init(cx: Scope) -> Element {
                     local_profile.write().image = res.profile_image;
                     local_profile.write().user_id = Some(res.user_id);
                 }
+                Err(_) if on_account_page => (),
                 Err(_) => {
                     toaster.write().error(
                         "Please login or create an account to continue.",
@@ -63,15 +69,31 @@ pub fn App(cx: Scope) -> Element {
                 ",
                 Route { to: page::ACCOUNT_REGISTER, page::Register{} },
                 Route { to: page::ACCOUNT_LOGIN, page::Login{} },
+                Route { to: page::ACCOUNT_CONFIRM_EMAIL, page::EmailConfirmation{} },
+                Route { to: page::ACCOUNT_FORGOT_PASSWORD, page::ForgotPassword{} },
+                Route { to: page::ACCOUNT_RESET_PASSWORD, page::NewPassword{} },
                 Route { to: page::HOME, page::Home{} },
                 Route { to: page::HOME_BOOKMARKED, page::HomeBookmarked{} },
                 Route { to: page::HOME_LIKED, page::HomeLiked{} },
+                Route { to: page::HOME_MENTIONS, page::HomeMentions{} },
+                Route { to: page::MESSAGES, page::Inbox{} },
+                Route { to: page::MESSAGES_CONVERSATION, page::Conversation{} },
+                Route { to: page::NOTIFICATIONS, page::Notifications{} },
                 Route { to: page::POST_NEW_CHAT, page::NewChat{} },
                 Route { to: page::POST_NEW_IMAGE, page::NewImage{} },
                 Route { to: page::POST_NEW_POLL, page::NewPoll{} },
+                Route { to: page::POST_REVISIONS, page::PostRevisions{} },
+                Route { to: page::POST_THREAD, page::Thread{} },
+                Route { to: page::POSTS_SCHEDULED, page::Scheduled{} },
+                Route { to: page::POSTS_SEARCH, page::SearchPosts{} },
+                Route { to: page::POSTS_TAGGED, page::TaggedPosts{} },
                 Route { to: page::POSTS_TRENDING, page::Trending{} },
                 Route { to: page::PROFILE_EDIT, page::EditProfile{} },
+                Route { to: page::PROFILE_FOLLOWERS, page::Followers{} },
+                Route { to: page::PROFILE_FOLLOWING, page::Following{} },
                 Route { to: page::PROFILE_VIEW, page::ViewProfile{} },
+                Route { to: page::USERS_BLOCKED, page::Blocked{} },
+                Route { to: page::USERS_SEARCH, page::SearchUsers{} },
             }
 
             ToasterRoot { toaster: toaster },
@@ -0,0 +1,48 @@
+#![allow(non_snake_case)]
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use dioxus::prelude::*;
+
+/// Format used by `datetime-local` inputs.
+const INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// Parses the value of a `datetime-local` input, which is in the local time zone.
+fn parse_local(value: &str) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(value, INPUT_FORMAT).ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Picks a date and time in the local time zone. Clearing the input reports `None`.
+#[inline_props]
+pub fn DateTimeInput<'a>(
+    cx: Scope<'a>,
+    id: String,
+    label: &'a str,
+    value: Option<DateTime<Utc>>,
+    on_change: EventHandler<'a, Option<DateTime<Utc>>>,
+) -> Element<'a> {
+    let value = value
+        .map(|time| time.with_timezone(&Local).format(INPUT_FORMAT).to_string())
+        .unwrap_or_default();
+    let min = Local::now().format(INPUT_FORMAT);
+
+    cx.render(rsx! {
+        div {
+            label {
+                r#for: "{id}",
+                div { "{label}" },
+                input {
+                    class: "input-field",
+                    id: "{id}",
+                    r#type: "datetime-local",
+                    min: "{min}",
+                    value: "{value}",
+                    oninput: move |ev| on_change.call(parse_local(&ev.value)),
+                }
+            }
+        }
+    })
+}
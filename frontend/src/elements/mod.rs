@@ -1,14 +1,20 @@
 pub mod appbar;
+mod date_time_input;
 mod keyed_notification_box;
 mod local_profile;
+mod mute_button;
 mod navbar;
 mod post;
 mod sidebar;
 mod toaster;
+mod user_entry;
 
+pub use date_time_input::*;
 pub use keyed_notification_box::*;
 pub use local_profile::*;
+pub use mute_button::*;
 pub use navbar::*;
 pub use post::*;
 pub use sidebar::*;
 pub use toaster::*;
+pub use user_entry::*;
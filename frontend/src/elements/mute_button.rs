@@ -0,0 +1,65 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use uchat_endpoint::user::MuteDuration;
+
+/// Mutes for a chosen duration, or unmutes when already `muted`. Unmuting reports `None`.
+#[inline_props]
+pub fn MuteButton<'a>(
+    cx: Scope<'a>,
+    muted: bool,
+    on_change: EventHandler<'a, Option<MuteDuration>>,
+) -> Element<'a> {
+    let choosing = use_state(cx, || false);
+
+    if *muted {
+        return cx.render(rsx! {
+            button {
+                class: "btn",
+                onclick: move |_| on_change.call(None),
+                "Unmute"
+            }
+        });
+    }
+
+    if !*choosing.get() {
+        return cx.render(rsx! {
+            button {
+                class: "btn",
+                onclick: move |_| choosing.set(true),
+                "Mute"
+            }
+        });
+    }
+
+    let Durations = [
+        (MuteDuration::Day, "24 hours"),
+        (MuteDuration::Week, "7 days"),
+        (MuteDuration::Forever, "Forever"),
+    ]
+    .into_iter()
+    .map(|(duration, label)| {
+        rsx! {
+            button {
+                class: "btn",
+                onclick: move |_| {
+                    choosing.set(false);
+                    on_change.call(Some(duration));
+                },
+                "{label}"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-row justify-center gap-2",
+            Durations,
+            button {
+                class: "btn",
+                onclick: move |_| choosing.set(false),
+                "Cancel"
+            }
+        }
+    })
+}
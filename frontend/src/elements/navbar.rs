@@ -2,6 +2,7 @@
 
 use crate::prelude::*;
 use dioxus::prelude::*;
+use fermi::use_atom_ref;
 
 #[inline_props]
 pub fn NewPostPopup(cx: Scope, hide: UseState<bool>) -> Element {
@@ -89,6 +90,8 @@ pub fn NavButton<'a>(cx: Scope<'a, NavButtonProps<'a>>) -> Element<'a> {
 }
 
 pub fn NavBar(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let unread_notifications = use_atom_ref(cx, crate::app::UNREAD_NOTIFICATIONS);
     let hide_new_post_popup = use_state(cx, || true);
     let router = use_router(cx);
     let route = use_route(cx);
@@ -106,10 +109,39 @@ pub fn NavBar(cx: Scope) -> Element {
         }
     });
 
+    // the count is refreshed whenever the user moves to another page
+    use_effect(cx, (&current_route,), |(current_route,)| {
+        to_owned![api_client, unread_notifications];
+        async move {
+            use uchat_endpoint::notification::{UnreadCount, UnreadCountOk};
+
+            if current_route == page::ACCOUNT_LOGIN || current_route == page::ACCOUNT_REGISTER {
+                return;
+            }
+            if let Ok(res) = fetch_json!(<UnreadCountOk>, api_client, UnreadCount) {
+                *unread_notifications.write() = res.unread;
+            }
+        }
+    });
+
     if *hide_navbar.get() {
         return None;
     }
 
+    let unread = *unread_notifications.read();
+    let UnreadBadge = (unread > 0).then(|| {
+        let text = match unread {
+            1..=99 => unread.to_string(),
+            _ => "99+".to_string(),
+        };
+        rsx! {
+            div {
+                class: "px-1 rounded-full bg-red-600 text-white text-xs",
+                "{text}"
+            }
+        }
+    });
+
     cx.render(rsx! {
         nav {
             class: "
@@ -119,7 +151,7 @@ pub fn NavBar(cx: Scope) -> Element {
             ",
 
             div  {
-                class: "grid grid-cols-3 justify-around w-full h-full items-center shadow-inner",
+                class: "grid grid-cols-4 justify-around w-full h-full items-center shadow-inner",
 
                 NavButton {
                     img: "/static/icons/icon-home.svg",
@@ -131,6 +163,12 @@ pub fn NavBar(cx: Scope) -> Element {
                     label: "Trending",
                     onclick: move |_| router.navigate_to(page::POSTS_TRENDING),
                 },
+                NavButton {
+                    img: "/static/icons/icon-notifications.svg",
+                    label: "Alerts",
+                    onclick: move |_| router.navigate_to(page::NOTIFICATIONS),
+                    UnreadBadge
+                },
                 NavButton {
                     img: "/static/icons/icon-new-post.svg",
                     label: "Post",
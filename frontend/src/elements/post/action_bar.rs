@@ -1,9 +1,13 @@
 #![allow(non_snake_case)]
 
-use crate::{elements::post::quick_respond::QuickRespond, prelude::*};
+use crate::{
+    elements::post::{edit::EditChat, quick_respond::QuickRespond},
+    prelude::*,
+};
 use dioxus::prelude::*;
+use fermi::use_atom_ref;
 use uchat_domain::ids::PostId;
-use uchat_endpoint::post::LikeStatus;
+use uchat_endpoint::post::{Content, LikeStatus};
 
 #[inline_props]
 pub fn LikeDislike(
@@ -88,6 +92,133 @@ pub fn LikeDislike(
     })
 }
 
+#[inline_props]
+pub fn EmojiReactions(cx: Scope, post_id: PostId) -> Element {
+    let post_manager = use_post_manager(cx);
+    let reaction_emojis = use_atom_ref(cx, crate::app::REACTION_EMOJIS);
+    let toaster = use_toaster(cx);
+    let api_client = ApiClient::global();
+    let picker_opened = use_state(cx, || false);
+
+    let react_onclick = async_handler!(
+        &cx,
+        [api_client, post_manager, toaster, post_id, picker_opened],
+        move |emoji: String| async move {
+            use uchat_endpoint::post::{EmojiReact, EmojiReactOk};
+
+            let current = post_manager.read().get(&post_id).unwrap().my_emoji.clone();
+            let emoji = match current.as_deref() == Some(emoji.as_str()) {
+                true => None,
+                false => Some(emoji),
+            };
+
+            let request_data = EmojiReact { post_id, emoji };
+            match fetch_json!(<EmojiReactOk>, api_client, request_data) {
+                Ok(res) => {
+                    post_manager.write().update(post_id, |post| {
+                        post.my_emoji = res.my_emoji.clone();
+                        post.emoji_reactions = res.emoji_reactions.clone();
+                    });
+                    picker_opened.set(false);
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to react to post: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let picker_onclick = async_handler!(
+        &cx,
+        [api_client, reaction_emojis, toaster, picker_opened],
+        move |_| async move {
+            use uchat_endpoint::post::{GetReactionEmojis, GetReactionEmojisOk};
+
+            let opened = *picker_opened.get();
+            picker_opened.set(!opened);
+            if opened || !reaction_emojis.read().is_empty() {
+                return;
+            }
+
+            match fetch_json!(<GetReactionEmojisOk>, api_client, GetReactionEmojis) {
+                Ok(res) => *reaction_emojis.write() = res.emojis,
+                Err(e) => toaster.write().error(
+                    format!("Failed to load reactions: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let post_manager = post_manager.read();
+    let this_post = post_manager.get(post_id).unwrap();
+    let my_emoji = this_post.my_emoji.clone();
+
+    let Counts = this_post
+        .emoji_reactions
+        .iter()
+        .map(|reaction| {
+            let emoji = reaction.emoji.clone();
+            let label = format!("{} {}", reaction.emoji, reaction.count);
+            let selected = my_emoji.as_ref() == Some(&reaction.emoji);
+            let selected_class = maybe_class!("border-slate-500 bg-slate-200", selected);
+            rsx! {
+                button {
+                    key: "{reaction.emoji}",
+                    class: "border rounded-full px-2 {selected_class}",
+                    onclick: move |_| react_onclick(emoji.clone()),
+                    "{label}"
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let Picker = match *picker_opened.get() {
+        true => {
+            let Choices = reaction_emojis
+                .read()
+                .iter()
+                .map(|emoji| {
+                    let label = emoji.clone();
+                    let emoji = emoji.clone();
+                    let selected = my_emoji.as_ref() == Some(&emoji);
+                    let selected_class = maybe_class!("bg-slate-200", selected);
+                    rsx! {
+                        button {
+                            key: "{label}",
+                            class: "rounded px-1 text-xl {selected_class}",
+                            onclick: move |_| react_onclick(emoji.clone()),
+                            "{label}"
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            Some(rsx! {
+                div {
+                    class: "flex flex-row flex-wrap gap-1 border rounded p-1",
+                    Choices.into_iter()
+                }
+            })
+        }
+        false => None,
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-row flex-wrap items-center gap-2 mt-2 text-sm",
+            Counts.into_iter(),
+            button {
+                class: "border rounded-full px-2 opacity-70",
+                title: "React with an emoji",
+                onclick: picker_onclick,
+                "+"
+            },
+            Picker
+        }
+    })
+}
+
 #[inline_props]
 pub fn Bookmark(cx: Scope, post_id: PostId, bookmarked: bool) -> Element {
     let post_manager = use_post_manager(cx);
@@ -195,6 +326,50 @@ pub fn Boost(cx: Scope, post_id: PostId, boosted: bool, boosts: i64) -> Element
     })
 }
 
+#[inline_props]
+pub fn Delete(cx: Scope, post_id: PostId) -> Element {
+    let post_manager = use_post_manager(cx);
+    let toaster = use_toaster(cx);
+    let api_client = ApiClient::global();
+
+    let delete_onclick = async_handler!(
+        &cx,
+        [api_client, post_manager, toaster, post_id],
+        move |_| async move {
+            use uchat_endpoint::post::{DeletePost, DeletePostOk};
+
+            if !crate::util::confirm("Delete this post? Replies to it are deleted as well.") {
+                return;
+            }
+
+            let request_data = DeletePost { post_id };
+            match fetch_json!(<DeletePostOk>, api_client, request_data) {
+                Ok(res) => {
+                    post_manager.write().remove(&res.post_id);
+                    toaster
+                        .write()
+                        .success("Post deleted", chrono::Duration::seconds(3));
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to delete post: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    cx.render(rsx! {
+        div {
+            class: "cursor-pointer",
+            onclick: delete_onclick,
+            img {
+                class: "actionbar-icon",
+                src: "/static/icons/icon-delete.svg",
+            }
+        }
+    })
+}
+
 #[inline_props]
 pub fn Comment(cx: Scope, opened: UseState<bool>) -> Element {
     let comment_onclick = sync_handler!([opened], move |_| {
@@ -215,11 +390,42 @@ pub fn Comment(cx: Scope, opened: UseState<bool>) -> Element {
 }
 
 #[inline_props]
-pub fn QuickRespondBox(cx: Scope, opened: UseState<bool>) -> Element {
+pub fn Edit(cx: Scope, opened: UseState<bool>) -> Element {
+    let edit_onclick = sync_handler!([opened], move |_| {
+        let current = *opened.get();
+        opened.set(!current);
+    });
+
+    cx.render(rsx! {
+        div {
+            class: "cursor-pointer",
+            onclick: edit_onclick,
+            img {
+                class: "actionbar-icon",
+                src: "/static/icons/icon-edit.svg"
+            }
+        }
+    })
+}
+
+#[inline_props]
+pub fn EditBox(cx: Scope, post_id: PostId, opened: UseState<bool>) -> Element {
+    let element = match *opened.get() {
+        true => {
+            to_owned![opened];
+            Some(rsx! { EditChat { post_id: *post_id, opened: opened }})
+        }
+        false => None,
+    };
+    cx.render(rsx! {element})
+}
+
+#[inline_props]
+pub fn QuickRespondBox(cx: Scope, post_id: PostId, opened: UseState<bool>) -> Element {
     let element = match *opened.get() {
         true => {
             to_owned![opened];
-            Some(rsx! { QuickRespond { opened: opened }})
+            Some(rsx! { QuickRespond { post_id: *post_id, opened: opened }})
         }
         false => None,
     };
@@ -229,12 +435,20 @@ pub fn QuickRespondBox(cx: Scope, opened: UseState<bool>) -> Element {
 #[inline_props]
 pub fn ActionBar(cx: Scope, post_id: PostId) -> Element {
     let post_manager = use_post_manager(cx);
+    let local_profile = use_local_profile(cx);
     let quick_respond_opened = use_state(cx, || false).clone();
+    let edit_opened = use_state(cx, || false).clone();
 
     let post_manager = post_manager.read();
     let this_post = post_manager.get(&post_id).unwrap();
     let this_post_id = this_post.id;
 
+    let is_author = local_profile.read().user_id == Some(this_post.by_user.id);
+    let DeleteButton = is_author.then(|| rsx! { Delete { post_id: this_post_id } });
+    // only chat messages can be edited in place
+    let can_edit = is_author && matches!(this_post.content, Content::Chat(_));
+    let EditButton = can_edit.then(|| rsx! { Edit { opened: edit_opened.clone() } });
+
     cx.render(rsx! {
         div {
             class: "flex flex-row justify-between w-full opacity-70 mt-4",
@@ -255,10 +469,20 @@ pub fn ActionBar(cx: Scope, post_id: PostId) -> Element {
             },
             Comment {
                 opened: quick_respond_opened.clone()
-            }
+            },
+            EditButton,
+            DeleteButton
+        },
+        EmojiReactions {
+            post_id: this_post_id
         },
         QuickRespondBox {
+            post_id: this_post_id,
             opened: quick_respond_opened
+        },
+        EditBox {
+            post_id: this_post_id,
+            opened: edit_opened
         }
     })
 }
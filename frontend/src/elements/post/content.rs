@@ -4,26 +4,76 @@ use std::collections::HashSet;
 
 use dioxus::prelude::*;
 use itertools::Itertools;
-use uchat_domain::ids::{PollChoiceId, PostId};
-use uchat_endpoint::post::{ImageKind, PublicPost, VoteCast};
+use uchat_domain::{
+    ids::{PollChoiceId, PostId, UserId},
+    split_message, MessageFragment, Username,
+};
+use uchat_endpoint::post::{ImageKind, PollSelection, PublicPost, VoteCast};
 
 use crate::prelude::*;
 
+use super::view_profile_onclick;
+
 #[inline_props]
 pub fn Content<'a>(cx: Scope<'a>, post: &'a PublicPost) -> Element<'a> {
     cx.render(rsx! {
         div {
             match &post.content {
-                uchat_endpoint::post::Content::Chat(content) => rsx! { Chat { content: content } },
+                uchat_endpoint::post::Content::Chat(content) => rsx! { Chat { content: content, mentions: &post.mentions } },
                 uchat_endpoint::post::Content::Image(content) => rsx! { Image { content: content } },
-                uchat_endpoint::post::Content::Poll(content) => rsx! { Poll { post_id: post.id, content: content } },
+                uchat_endpoint::post::Content::Poll(content) => rsx! { Poll { post_id: post.id, author_id: post.by_user.id, content: content } },
             }
         }
     })
 }
 
+/// Shows `text` with each `#tag` linked to its timeline, and each `@handle` found in `mentions`
+/// linked to the mentioned profile.
 #[inline_props]
-pub fn Chat<'a>(cx: Scope<'a>, content: &'a uchat_endpoint::post::Chat) -> Element<'a> {
+pub fn RichText<'a>(
+    cx: Scope<'a>,
+    text: &'a str,
+    mentions: &'a [(Username, UserId)],
+) -> Element<'a> {
+    let router = use_router(cx);
+
+    let Fragments = split_message(text)
+        .into_iter()
+        .map(|fragment| match fragment {
+            MessageFragment::Text(text) => rsx! { "{text}" },
+            MessageFragment::Mention(handle) => {
+                match mentions
+                    .iter()
+                    .find(|(username, _)| username.as_ref() == handle)
+                {
+                    Some((_, user_id)) => rsx! {
+                        span {
+                            class: "link cursor-pointer",
+                            onclick: view_profile_onclick(router, *user_id),
+                            "@{handle}"
+                        }
+                    },
+                    None => rsx! { "@{handle}" },
+                }
+            }
+            MessageFragment::Hashtag(tag) => rsx! {
+                span {
+                    class: "link cursor-pointer",
+                    onclick: move |_| router.navigate_to(&page::tagged_posts(tag)),
+                    "#{tag}"
+                }
+            },
+        });
+
+    cx.render(rsx! { Fragments })
+}
+
+#[inline_props]
+pub fn Chat<'a>(
+    cx: Scope<'a>,
+    content: &'a uchat_endpoint::post::Chat,
+    mentions: &'a [(Username, UserId)],
+) -> Element<'a> {
     let Headline = content.headline.as_ref().map(|headline| {
         rsx! {
             div {
@@ -36,7 +86,12 @@ pub fn Chat<'a>(cx: Scope<'a>, content: &'a uchat_endpoint::post::Chat) -> Eleme
     cx.render(rsx! {
         div {
             Headline,
-            p { "{content.message.as_ref()}" }
+            p {
+                RichText {
+                    text: content.message.as_ref(),
+                    mentions: mentions
+                }
+            }
         }
     })
 }
@@ -47,10 +102,9 @@ pub fn Image<'a>(cx: Scope<'a>, content: &'a uchat_endpoint::post::Image) -> Ele
         return cx.render(rsx! { "image not found" });
     };
 
-    let Caption = content
-        .caption
-        .as_ref()
-        .map(|caption| rsx! { figcaption { em { "{caption.as_ref()}" } } });
+    let Caption = content.caption.as_ref().map(
+        |caption| rsx! { figcaption { em { RichText { text: caption.as_ref(), mentions: &[] } } } },
+    );
 
     cx.render(rsx! {
         figure {
@@ -68,29 +122,71 @@ pub fn Image<'a>(cx: Scope<'a>, content: &'a uchat_endpoint::post::Image) -> Ele
 pub fn Poll<'a>(
     cx: Scope<'a>,
     post_id: PostId,
+    author_id: UserId,
     content: &'a uchat_endpoint::post::Poll,
 ) -> Element<'a> {
     let toaster = use_toaster(cx);
+    let local_profile = use_local_profile(cx);
+    let post_manager = use_post_manager(cx);
     let api_client = ApiClient::global();
 
+    let now = chrono::Utc::now();
+    let is_author = local_profile.read().user_id == Some(*author_id);
+    let results_hidden = content.results_hidden(is_author, now);
+
     let vote_onclick = async_handler!(
         &cx,
-        [api_client, toaster],
+        [api_client, toaster, post_manager],
         move |post_id, choice_id| async move {
-            use uchat_endpoint::post::{Vote, VoteOk};
+            use uchat_endpoint::post::{Content as PostContent, Vote, VoteOk};
 
-            let request_data = Vote { post_id, choice_id };
+            let (mut choices, max_choices) = match post_manager.read().get(&post_id) {
+                Some(PublicPost {
+                    content: PostContent::Poll(poll),
+                    ..
+                }) => (poll.voted.clone(), poll.max_choices()),
+                _ => return,
+            };
+            // clicking a chosen choice takes the vote for it back
+            if !choices.remove(&choice_id) {
+                if max_choices == 1 {
+                    choices.clear();
+                }
+                choices.insert(choice_id);
+            }
+            if choices.len() > max_choices {
+                toaster.write().error(
+                    format!("At most {max_choices} choices can be selected"),
+                    chrono::Duration::seconds(3),
+                );
+                return;
+            }
+
+            let request_data = Vote { post_id, choices };
             match fetch_json!(<VoteOk>, api_client, request_data) {
-                Ok(VoteOk {
-                    cast: VoteCast::Yes,
-                }) => toaster
-                    .write()
-                    .success("Vote cast!", chrono::Duration::seconds(3)),
-                Ok(VoteOk {
-                    cast: VoteCast::AlreadyVoted,
-                }) => toaster
-                    .write()
-                    .success("Already voted", chrono::Duration::seconds(5)),
+                Ok(res) => {
+                    post_manager.write().update(post_id, |post| {
+                        if let PostContent::Poll(poll) = &mut post.content {
+                            for choice in &mut poll.choices {
+                                let before = poll.voted.contains(&choice.id) as i64;
+                                let after = res.voted.contains(&choice.id) as i64;
+                                choice.num_votes += after - before;
+                            }
+                            poll.voted = res.voted.clone();
+                        }
+                    });
+                    match res.cast {
+                        VoteCast::Yes => toaster
+                            .write()
+                            .success("Vote cast!", chrono::Duration::seconds(3)),
+                        VoteCast::Retracted => toaster
+                            .write()
+                            .success("Vote retracted", chrono::Duration::seconds(3)),
+                        VoteCast::Closed => toaster
+                            .write()
+                            .error("Voting has closed", chrono::Duration::seconds(5)),
+                    }
+                }
                 Err(e) => toaster.write().error(
                     format!("Failed to cast vote: {e}"),
                     chrono::Duration::seconds(3),
@@ -105,7 +201,9 @@ pub fn Poll<'a>(
         .map(|choice| choice.num_votes)
         .sum::<i64>();
 
-    let leader_ids = {
+    let leader_ids = if results_hidden {
+        HashSet::new()
+    } else {
         let leaders = content
             .choices
             .iter()
@@ -122,6 +220,11 @@ pub fn Poll<'a>(
             "0%".to_string()
         };
 
+        let shown_percent = match results_hidden {
+            true => "?".to_string(),
+            false => percent.clone(),
+        };
+
         let background_color = if leader_ids.contains(&choice.id) {
             "bg-blue-300"
         } else {
@@ -129,11 +232,12 @@ pub fn Poll<'a>(
         };
 
         let foreground_styles = maybe_class!("font-bold", leader_ids.contains(&choice.id));
+        let voted_styles = maybe_class!("border-2 border-blue-500", content.voted.contains(&choice.id));
 
         rsx! {
             li {
                 key: "{choice.id.to_string()}",
-                class: "relative p-2 m-2 cursor-pointer grid grid-cols-[3rem_1fr] border rounded border-slate-400",
+                class: "relative p-2 m-2 cursor-pointer grid grid-cols-[3rem_1fr] border rounded border-slate-400 {voted_styles}",
                 onclick: move |_| vote_onclick(*post_id, choice.id),
                 div {
                     class: "absolute left-0 {background_color} h-full rounded z-[-1]",
@@ -141,7 +245,7 @@ pub fn Poll<'a>(
                 },
                 div {
                     class: "{foreground_styles}",
-                    "{percent}"
+                    "{shown_percent}"
                 },
                 div {
                     class: "{foreground_styles}",
@@ -151,12 +255,42 @@ pub fn Poll<'a>(
         }
     });
 
-    let Headline = rsx! { figcaption { "{content.headline.as_ref()}" } };
+    let Headline =
+        rsx! { figcaption { RichText { text: content.headline.as_ref(), mentions: &[] } } };
+
+    let Selection = match content.selection {
+        PollSelection::Single => None,
+        PollSelection::Multiple { max_choices } => Some(rsx! {
+            div {
+                class: "text-sm opacity-70",
+                "Select up to {max_choices} choices"
+            }
+        }),
+    };
+
+    let Deadline = content.closes_at.map(|closes_at| {
+        let closes_at = closes_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M");
+        let status = match (content.is_closed_at(now), results_hidden) {
+            (true, _) => format!("Voting closed {closes_at}"),
+            (false, true) => format!("Voting closes {closes_at}, results are shown afterwards"),
+            (false, false) => format!("Voting closes {closes_at}"),
+        };
+        rsx! {
+            div {
+                class: "text-sm opacity-70",
+                "{status}"
+            }
+        }
+    });
 
     cx.render(rsx! {
         Headline,
+        Selection,
         ul {
             Choices.into_iter()
-        }
+        },
+        Deadline
     })
 }
@@ -0,0 +1,96 @@
+#![allow(non_snake_case)]
+
+use crate::{elements::post::quick_respond::MessageInput, prelude::*};
+use chrono::Duration;
+use dioxus::prelude::*;
+use uchat_domain::{ids::PostId, Message};
+use uchat_endpoint::post::{Content, PublicPost};
+
+#[inline_props]
+pub fn EditChat(cx: Scope, post_id: PostId, opened: UseState<bool>) -> Element {
+    let api_client = ApiClient::global();
+    let toaster = use_toaster(cx);
+    let post_manager = use_post_manager(cx);
+
+    let message = use_state(cx, || match post_manager.read().get(post_id) {
+        Some(PublicPost {
+            content: Content::Chat(chat),
+            ..
+        }) => chat.message.as_ref().to_string(),
+        _ => "".to_string(),
+    });
+
+    let post_id = *post_id;
+    let form_onsubmit = async_handler!(
+        &cx,
+        [api_client, toaster, post_manager, message, opened],
+        move |_| async move {
+            use uchat_endpoint::post::{Chat, EditPost, EditPostOk};
+
+            let headline = match post_manager.read().get(&post_id) {
+                Some(PublicPost {
+                    content: Content::Chat(chat),
+                    ..
+                }) => chat.headline.clone(),
+                _ => return,
+            };
+            let message = match Message::new(message.get()) {
+                Ok(message) => message,
+                Err(_) => return,
+            };
+
+            let request = EditPost {
+                post_id,
+                content: Chat { headline, message }.into(),
+            };
+            let response = fetch_json!(<EditPostOk>, api_client, request);
+            match response {
+                Ok(res) => {
+                    post_manager
+                        .write()
+                        .update(post_id, |post| *post = res.post.clone());
+                    toaster
+                        .write()
+                        .success("Post updated", Duration::seconds(3));
+                    opened.set(false);
+                }
+                Err(e) => {
+                    toaster
+                        .write()
+                        .error(format!("Edit failed: {e}"), Duration::seconds(3));
+                }
+            }
+        }
+    );
+
+    let can_submit = Message::new(message.get()).is_ok();
+    let submit_btn_style = maybe_class!("btn-disabled", !can_submit);
+
+    cx.render(rsx! {
+        form {
+            onsubmit: form_onsubmit,
+            prevent_default: "onsubmit",
+            MessageInput {
+                message: message,
+                on_input: move |ev: FormEvent| {
+                    message.set(ev.value.clone());
+                }
+            }
+            div {
+                class: "w-full flex flex-row justify-end gap-2",
+                button {
+                    class: "mt-2 btn",
+                    r#type: "button",
+                    onclick: move |_| opened.set(false),
+                    "Cancel"
+                },
+                button {
+                    class: "mt-2 btn {submit_btn_style}",
+                    r#type: "submit",
+                    disabled: !can_submit,
+                    "Save"
+                }
+            }
+        }
+    })
+}
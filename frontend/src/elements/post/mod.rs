@@ -2,12 +2,13 @@
 
 mod action_bar;
 mod content;
+mod edit;
 mod quick_respond;
 
 use crate::{
     app::POST_MANAGER,
     elements::post::{action_bar::ActionBar, content::Content},
-    page::profile_view,
+    page::{post_revisions, post_thread, profile_view},
     sync_handler,
 };
 use dioxus::prelude::*;
@@ -15,7 +16,7 @@ use dioxus_router::{use_router, RouterContext};
 use fermi::{use_atom_ref, UseAtomRef};
 use indexmap::IndexMap;
 use uchat_domain::ids::{PostId, UserId};
-use uchat_endpoint::post::PublicPost;
+use uchat_endpoint::{post::PublicPost, Cursor, Page};
 
 pub fn use_post_manager(cx: &ScopeState) -> &UseAtomRef<PostManager> {
     use_atom_ref(cx, POST_MANAGER)
@@ -24,6 +25,7 @@ pub fn use_post_manager(cx: &ScopeState) -> &UseAtomRef<PostManager> {
 #[derive(Default)]
 pub struct PostManager {
     pub posts: IndexMap<PostId, PublicPost>,
+    pub next_cursor: Option<Cursor>,
 }
 
 impl PostManager {
@@ -44,7 +46,6 @@ impl PostManager {
     where
         T: Iterator<Item = PublicPost>,
     {
-        self.clear();
         for post in posts {
             self.posts.insert(post.id, post);
         }
@@ -52,6 +53,18 @@ impl PostManager {
 
     pub fn clear(&mut self) {
         self.posts.clear();
+        self.next_cursor = None;
+    }
+
+    /// Returns the page to request for `page_number`, or `None` once the feed is exhausted.
+    ///
+    /// Page `0` starts the feed over.
+    pub fn next_page(&mut self, page_number: usize) -> Option<Page> {
+        if page_number == 0 {
+            self.clear();
+            return Some(Page::default());
+        }
+        self.next_cursor.clone().map(Page::after)
     }
 
     pub fn get(&self, post_id: &PostId) -> Option<&PublicPost> {
@@ -113,6 +126,8 @@ pub fn ProfileImage<'a>(cx: Scope<'a>, post: &'a PublicPost) -> Element<'a> {
 
 #[inline_props]
 pub fn Header<'a>(cx: Scope<'a>, post: &'a PublicPost) -> Element<'a> {
+    let router = use_router(cx);
+
     let (post_date, post_time) = {
         let date = post.time_posted.format("%Y-%m-%d");
         let time = post.time_posted.format("%H-%M-%S");
@@ -128,6 +143,18 @@ pub fn Header<'a>(cx: Scope<'a>, post: &'a PublicPost) -> Element<'a> {
 
     let handle = post.by_user.handle.as_str();
 
+    let Edited = post.edited_at.map(|edited_at| {
+        let edited_at = edited_at.format("%Y-%m-%d %H:%M");
+        rsx! {
+            div {
+                class: "text-sm font-light cursor-pointer",
+                title: "Edited {edited_at}. View history",
+                onclick: move |_| router.navigate_to(&post_revisions(post.id)),
+                "(edited)"
+            }
+        }
+    });
+
     cx.render(rsx! {
         div {
             class: "flex flex-row justify-between",
@@ -142,20 +169,49 @@ pub fn Header<'a>(cx: Scope<'a>, post: &'a PublicPost) -> Element<'a> {
             },
             div {
                 class: "text-right",
-                div { "{post_date}" },
-                div { "{post_time}" },
+                div {
+                    class: "cursor-pointer",
+                    title: "View thread",
+                    onclick: move |_| router.navigate_to(&post_thread(post.id)),
+                    div { "{post_date}" },
+                    div { "{post_time}" },
+                },
+                Edited
             }
         }
     })
 }
 
+#[inline_props]
+pub fn ReplyTo<'a>(cx: Scope<'a>, post: &'a PublicPost) -> Element<'a> {
+    let router = use_router(cx);
+
+    let Some((handle, _, parent_id)) = &post.reply_to else {
+        return None;
+    };
+    let parent_id = *parent_id;
+
+    cx.render(rsx! {
+        div {
+            class: "text-sm font-light cursor-pointer",
+            onclick: move |_| router.navigate_to(&post_thread(parent_id)),
+            "replying to {handle.as_ref()}"
+        }
+    })
+}
+
 #[inline_props]
 pub fn PublicPostEntry(cx: Scope, post_id: PostId) -> Element {
     let post_manager = use_post_manager(cx);
 
     let this_post = {
         let post = post_manager.read().get(&post_id).unwrap().clone();
-        use_state(cx, || post)
+        let this_post = use_state(cx, || post.clone());
+        // pick up edits made through the post manager
+        if *this_post.get() != post {
+            this_post.set(post);
+        }
+        this_post
     };
 
     cx.render(rsx! {
@@ -166,7 +222,7 @@ pub fn PublicPostEntry(cx: Scope, post_id: PostId) -> Element {
             div {
                 class: "flex flex-col gap-3",
                 Header { post: this_post },
-                // reply to
+                ReplyTo { post: this_post },
                 Content { post: this_post },
                 ActionBar { post_id: this_post.id },
                 hr {}
@@ -3,7 +3,7 @@
 use crate::prelude::*;
 use chrono::Duration;
 use dioxus::prelude::*;
-use uchat_domain::Message;
+use uchat_domain::{ids::PostId, Message};
 
 fn can_submit(message: &str) -> bool {
     message.len() <= Message::MAX_CHARS && !message.is_empty()
@@ -38,12 +38,13 @@ pub fn MessageInput<'a>(
 }
 
 #[inline_props]
-pub fn QuickRespond(cx: Scope, opened: UseState<bool>) -> Element {
+pub fn QuickRespond(cx: Scope, post_id: PostId, opened: UseState<bool>) -> Element {
     let api_client = ApiClient::global();
     let toaster = use_toaster(cx);
 
     let message = use_state(cx, || "".to_string());
 
+    let post_id = *post_id;
     let form_onsubmit = async_handler!(
         &cx,
         [api_client, toaster, message, opened],
@@ -57,7 +58,10 @@ pub fn QuickRespond(cx: Scope, opened: UseState<bool>) -> Element {
                     message: Message::new(message.get()).unwrap(),
                 }
                 .into(),
-                options: NewPostOptions::default(),
+                options: NewPostOptions {
+                    reply_to: Some(post_id),
+                    ..NewPostOptions::default()
+                },
             };
             let response = fetch_json!(<NewPostOk>, api_client, request);
             match response {
@@ -31,6 +31,29 @@ pub fn Sidebar(cx: Scope) -> Element {
     let sidebar = use_sidebar(cx);
     let router = use_router(cx);
     let local_profile = use_local_profile(cx);
+    let api_client = ApiClient::global();
+
+    let logout_onclick = async_handler!(
+        &cx,
+        [api_client, local_profile, router, sidebar],
+        move |_| async move {
+            use chrono::Utc;
+            use uchat_domain::ids::SessionId;
+            use uchat_endpoint::user::{Logout, LogoutOk};
+
+            // the session is forgotten locally even if the server can't be reached
+            if let Err(e) = fetch_json!(<LogoutOk>, api_client, Logout) {
+                log::warn!("failed to end session: {e}");
+            }
+
+            crate::util::cookie::set_session("".to_string(), SessionId::new(), Utc::now());
+            local_profile.write().user_id = None;
+            local_profile.write().image = None;
+
+            sidebar.write().close();
+            router.navigate_to(page::ACCOUNT_LOGIN);
+        }
+    );
 
     let sidebar_width = if sidebar.read().is_open() {
         "w-[var(--sidebar-width)]"
@@ -100,15 +123,46 @@ pub fn Sidebar(cx: Scope) -> Element {
             a {
                 class: "sidebar-navlink",
                 onclick: move |_| {
-                    use chrono::Utc;
-                    use uchat_domain::ids::SessionId;
-                    crate::util::cookie::set_session("".to_string(), SessionId::new(), Utc::now());
-                    local_profile.write().user_id = None;
-                    local_profile.write().image = None;
-
                     sidebar.write().close();
-                    router.navigate_to(page::ACCOUNT_LOGIN);
+                    router.navigate_to(page::HOME_MENTIONS);
+                },
+                "Mentions"
+            },
+            a {
+                class: "sidebar-navlink",
+                onclick: move |_| {
+                    sidebar.write().close();
+                    router.navigate_to(page::MESSAGES);
+                },
+                "Messages"
+            },
+            a {
+                class: "sidebar-navlink",
+                onclick: move |_| {
+                    sidebar.write().close();
+                    router.navigate_to(page::POSTS_SCHEDULED);
                 },
+                "Scheduled"
+            },
+            a {
+                class: "sidebar-navlink",
+                onclick: move |_| {
+                    sidebar.write().close();
+                    router.navigate_to(page::USERS_SEARCH);
+                },
+                "Find Users"
+            },
+            a {
+                class: "sidebar-navlink",
+                onclick: move |_| {
+                    sidebar.write().close();
+                    router.navigate_to(page::USERS_BLOCKED);
+                },
+                "Blocked Users"
+            },
+            a {
+                class: "sidebar-navlink",
+                onclick: logout_onclick,
                 "Logout"
             }
         }
@@ -0,0 +1,56 @@
+#![allow(non_snake_case)]
+
+use crate::prelude::*;
+use dioxus::prelude::*;
+use uchat_endpoint::user::PublicUserProfile;
+
+/// A single row in a list of users. Clicking it opens the user's profile.
+#[inline_props]
+pub fn UserEntry(cx: Scope, profile: PublicUserProfile) -> Element {
+    let router = use_router(cx);
+
+    let profile_img_src = profile
+        .profile_image
+        .as_ref()
+        .map(|url| url.as_str())
+        .unwrap_or_else(|| "");
+    let display_name = profile
+        .display_name
+        .as_ref()
+        .map(|name| name.as_ref())
+        .unwrap_or_else(|| "");
+    let handle = profile.handle.as_str();
+    let relationship = match (profile.am_following, profile.follows_me) {
+        (true, true) => Some("Following each other"),
+        (true, false) => Some("Following"),
+        (false, true) => Some("Follows you"),
+        (false, false) => None,
+    };
+    let relationship = relationship.map(|text| {
+        rsx! {
+            div {
+                class: "text-sm font-light",
+                "{text}"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        div {
+            class: "grid grid-cols-[50px_1fr_auto] gap-2 items-center py-2 border-b cursor-pointer",
+            onclick: move |_| router.navigate_to(&page::profile_view(profile.id)),
+            img {
+                class: "profile-portrait",
+                src: "{profile_img_src}"
+            },
+            div {
+                div { "{display_name}" },
+                div {
+                    class: "font-light",
+                    "{handle}"
+                }
+            },
+            relationship
+        }
+    })
+}
@@ -33,11 +33,13 @@ fn main() {
 pub mod prelude {
     pub use crate::elements::appbar::{self, Appbar, AppbarImgButton};
     pub use crate::elements::{
-        use_local_profile, use_post_manager, use_sidebar, use_toaster, LocalProfile,
+        use_local_profile, use_post_manager, use_sidebar, use_toaster, DateTimeInput, LocalProfile,
         PublicPostEntry, Sidebar, SidebarManager,
     };
     pub use crate::fetch_json;
     pub use crate::page;
-    pub use crate::util::{async_handler, maybe_class, sync_handler, ApiClient};
+    pub use crate::util::{
+        async_handler, maybe_class, sync_handler, use_scroll_to_bottom, ApiClient,
+    };
     pub use dioxus_router::{use_route, use_router};
 }
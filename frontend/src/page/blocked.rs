@@ -0,0 +1,128 @@
+#![allow(non_snake_case)]
+
+use chrono::Duration;
+use dioxus::prelude::*;
+use uchat_domain::ids::UserId;
+use uchat_endpoint::{user::PublicUserProfile, Cursor, Page};
+
+use crate::{elements::UserEntry, prelude::*};
+
+#[inline_props]
+fn BlockedEntry(
+    cx: Scope,
+    profile: PublicUserProfile,
+    users: UseRef<Vec<PublicUserProfile>>,
+) -> Element {
+    let api_client = ApiClient::global();
+    let toaster = use_toaster(cx);
+
+    let user_id: UserId = profile.id;
+
+    let unblock_onclick = async_handler!(&cx, [api_client, toaster, users], move |_| async move {
+        use uchat_endpoint::user::{UnblockUser, UnblockUserOk};
+
+        let request_data = UnblockUser { user_id };
+        match fetch_json!(<UnblockUserOk>, api_client, request_data) {
+            Ok(res) => {
+                users.with_mut(|users| users.retain(|user| user.id != res.user_id));
+                toaster
+                    .write()
+                    .success("User unblocked", Duration::seconds(3));
+            }
+            Err(e) => toaster
+                .write()
+                .error(format!("Failed to unblock user: {e}"), Duration::seconds(3)),
+        }
+    });
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-row items-center gap-2",
+            div {
+                class: "grow",
+                UserEntry { profile: profile.clone() }
+            },
+            button {
+                class: "btn",
+                onclick: unblock_onclick,
+                "Unblock"
+            }
+        }
+    })
+}
+
+/// Users blocked by the logged in user.
+pub fn Blocked(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+
+    let users = use_ref(cx, Vec::new);
+    let next_cursor = use_ref(cx, || None::<Cursor>);
+    let page_number = use_scroll_to_bottom(cx);
+
+    use_effect(cx, (page_number.get(),), |(page_number,)| {
+        to_owned![api_client, toaster, users, next_cursor];
+        async move {
+            use uchat_endpoint::user::{ListBlocked, ListBlockedOk};
+
+            let page = match (page_number, next_cursor.read().clone()) {
+                (0, _) => Page::default(),
+                (_, Some(cursor)) => Page::after(cursor),
+                (_, None) => return,
+            };
+
+            let request_data = ListBlocked { page };
+            match fetch_json!(<ListBlockedOk>, api_client, request_data) {
+                Ok(res) => {
+                    if page_number == 0 {
+                        users.write().clear();
+                    }
+                    users.write().extend(res.users);
+                    next_cursor.set(res.next_cursor);
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to retrieve blocked users: {e}"),
+                    Duration::seconds(3),
+                ),
+            }
+        }
+    });
+
+    let Users = users
+        .read()
+        .iter()
+        .map(|profile| {
+            rsx! {
+                BlockedEntry {
+                    key: "{profile.id.to_string()}",
+                    profile: profile.clone(),
+                    users: users.clone()
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let NoUsers = Users.is_empty().then(|| {
+        rsx! {
+            div {
+                class: "text-center my-6",
+                "You haven't blocked anyone."
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Blocked Users",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        NoUsers,
+        Users.into_iter()
+    })
+}
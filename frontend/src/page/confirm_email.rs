@@ -0,0 +1,53 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::Link;
+
+use crate::prelude::*;
+
+/// Opened from the link in a confirmation email. Works without being logged in.
+pub fn EmailConfirmation(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let outcome = use_state(cx, || None::<Result<String, String>>);
+
+    let token = use_route(cx).last_segment().map(|token| token.to_string());
+
+    let _confirm_email = {
+        to_owned![api_client, outcome];
+        use_future(cx, (&token,), |(token,)| async move {
+            use uchat_endpoint::user::{ConfirmEmail, ConfirmEmailOk};
+
+            let Some(token) = token else {
+                return;
+            };
+
+            let request_data = ConfirmEmail { token };
+            let response = fetch_json!(<ConfirmEmailOk>, api_client, request_data);
+            outcome.set(Some(match response {
+                Ok(res) => Ok(res.email),
+                Err(e) => Err(e.to_string()),
+            }));
+        })
+    };
+
+    let Message = match outcome.get() {
+        None => rsx! { "Confirming your email address..." },
+        Some(Ok(email)) => rsx! { "{email} is confirmed." },
+        Some(Err(e)) => rsx! { "Failed to confirm your email address: {e}" },
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col gap-5",
+            div {
+                class: "text-center my-6",
+                Message
+            },
+            Link {
+                class: "link text-center",
+                to: page::HOME,
+                "Continue to uchat"
+            }
+        }
+    })
+}
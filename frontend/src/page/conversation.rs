@@ -0,0 +1,205 @@
+#![allow(non_snake_case)]
+
+use std::str::FromStr;
+
+use chrono::Duration;
+use dioxus::prelude::*;
+use uchat_domain::{ids::UserId, Message};
+use uchat_endpoint::{post::PublicPost, user::PublicUserProfile, Cursor, Page};
+
+use crate::prelude::*;
+
+#[inline_props]
+fn MessageBubble(cx: Scope, message: PublicPost, mine: bool) -> Element {
+    let align = match mine {
+        true => "self-end bg-slate-600 text-white",
+        false => "self-start bg-slate-200",
+    };
+    let text = super::inbox::message_preview(&message.content);
+    let sent = message.time_posted.format("%Y-%m-%d %H:%M");
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col max-w-[80%] px-3 py-2 rounded {align}",
+            span { "{text}" },
+            span {
+                class: "text-xs font-light text-right",
+                "{sent}"
+            }
+        }
+    })
+}
+
+pub fn Conversation(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let local_profile = use_local_profile(cx);
+
+    let user_id = use_route(cx)
+        .last_segment()
+        .and_then(|id| UserId::from_str(id).ok())
+        .unwrap_or_default();
+
+    let with_user = use_ref(cx, || None::<PublicUserProfile>);
+    // newest first, as returned by the server
+    let messages = use_ref(cx, Vec::<PublicPost>::new);
+    let next_cursor = use_ref(cx, || None::<Cursor>);
+    let page_number = use_state(cx, || 0_usize);
+    let generation = use_state(cx, || 0_usize);
+    let draft = use_state(cx, String::new);
+
+    use_effect(
+        cx,
+        (&user_id, page_number.get(), generation.get()),
+        |(user_id, page_number, _)| {
+            to_owned![api_client, toaster, with_user, messages, next_cursor];
+            async move {
+                use uchat_endpoint::post::{GetConversation, GetConversationOk};
+
+                let page = match page_number {
+                    0 => Page::default(),
+                    _ => match next_cursor.read().clone() {
+                        Some(cursor) => Page::after(cursor),
+                        None => return,
+                    },
+                };
+
+                let request_data = GetConversation {
+                    with_user: user_id,
+                    page,
+                };
+                let response = fetch_json!(<GetConversationOk>, api_client, request_data);
+                match response {
+                    Ok(res) => {
+                        with_user.set(Some(res.with_user));
+                        if page_number == 0 {
+                            messages.write().clear();
+                        }
+                        messages.write().extend(res.messages);
+                        next_cursor.set(res.next_cursor);
+                    }
+                    Err(e) => toaster.write().error(
+                        format!("Failed to retrieve messages: {e}"),
+                        Duration::seconds(3),
+                    ),
+                }
+            }
+        },
+    );
+
+    let send_onclick = async_handler!(
+        &cx,
+        [api_client, toaster, draft, page_number, generation],
+        move |_| async move {
+            use uchat_endpoint::post::{Chat, NewPost, NewPostOk, NewPostOptions};
+
+            let message = match Message::new(draft.get()) {
+                Ok(message) => message,
+                Err(_) => return,
+            };
+            let request_data = NewPost {
+                content: Chat {
+                    headline: None,
+                    message,
+                }
+                .into(),
+                options: NewPostOptions {
+                    direct_message_to: Some(user_id),
+                    ..NewPostOptions::default()
+                },
+            };
+
+            match fetch_json!(<NewPostOk>, api_client, request_data) {
+                Ok(_) => {
+                    draft.set(String::new());
+                    // reload the newest messages
+                    page_number.set(0);
+                    generation.modify(|n| n + 1);
+                }
+                Err(e) => toaster
+                    .write()
+                    .error(format!("Failed to send message: {e}"), Duration::seconds(3)),
+            }
+        }
+    );
+
+    let my_id = local_profile.read().user_id;
+    let Messages = messages
+        .read()
+        .iter()
+        .rev()
+        .map(|message| {
+            rsx! {
+                MessageBubble {
+                    key: "{message.id.to_string()}",
+                    message: message.clone(),
+                    mine: Some(message.by_user.id) == my_id,
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let LoadOlder = next_cursor.read().is_some().then(|| {
+        rsx! {
+            button {
+                class: "btn self-center",
+                onclick: move |_| page_number.modify(|n| n + 1),
+                "Load older messages"
+            }
+        }
+    });
+
+    let title = with_user
+        .read()
+        .as_ref()
+        .map(|user| {
+            user.display_name
+                .as_ref()
+                .map(|name| name.as_ref().to_string())
+                .unwrap_or_else(|| user.handle.clone())
+        })
+        .unwrap_or_default();
+
+    let can_send = Message::new(draft.get()).is_ok();
+    let send_btn_style = maybe_class!("btn-disabled", !can_send);
+
+    cx.render(rsx! {
+        Appbar {
+            title: "{title}",
+            AppbarImgButton {
+                click_handler: move |_| router.navigate_to(&page::profile_view(user_id)),
+                img: "/static/icons/icon-following.svg",
+                label: "Profile",
+                title: "View profile"
+            },
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        div {
+            class: "flex flex-col gap-2 my-3",
+            LoadOlder,
+            Messages.into_iter()
+        },
+        div {
+            class: "flex flex-row gap-3",
+            textarea {
+                class: "input-field grow",
+                rows: 2,
+                placeholder: "Message",
+                value: "{draft.get()}",
+                oninput: move |ev| draft.set(ev.value.clone()),
+            },
+            button {
+                class: "btn {send_btn_style}",
+                disabled: !can_send,
+                onclick: send_onclick,
+                "Send"
+            }
+        }
+    })
+}
@@ -7,6 +7,7 @@ use crate::{
 };
 use dioxus::prelude::*;
 use uchat_domain::UserFacingError;
+use uchat_endpoint::user::SessionInfo;
 use web_sys::HtmlInputElement;
 
 #[derive(Clone, Debug)]
@@ -20,6 +21,10 @@ pub struct PageState {
     form_errors: KeyedNotifications,
     display_name: String,
     email: String,
+    /// The email address currently saved on the profile.
+    saved_email: String,
+    email_confirmed: bool,
+    two_factor_enabled: bool,
     password: String,
     password_confirmation: String,
     profile_image: Option<PreviewImageData>,
@@ -171,6 +176,45 @@ pub fn PasswordInput(cx: Scope, page_state: UseRef<PageState>) -> Element {
 pub fn EmailInput(cx: Scope, page_state: UseRef<PageState>) -> Element {
     use uchat_domain::Email;
 
+    let api_client = ApiClient::global();
+    let toaster = use_toaster(cx);
+
+    let resend_onclick = async_handler!(&cx, [api_client, toaster], move |_| async move {
+        use uchat_endpoint::user::{ResendEmailConfirmation, ResendEmailConfirmationOk};
+
+        let request_data = ResendEmailConfirmation;
+        match fetch_json!(<ResendEmailConfirmationOk>, api_client, request_data) {
+            Ok(_) => toaster.write().success(
+                "Confirmation link sent, check your inbox",
+                chrono::Duration::seconds(5),
+            ),
+            Err(e) => toaster.write().error(
+                format!("Failed to send confirmation link: {e}"),
+                chrono::Duration::seconds(5),
+            ),
+        }
+    });
+
+    let Unconfirmed = page_state.with(|state| {
+        let unconfirmed = !state.saved_email.is_empty()
+            && !state.email_confirmed
+            && state.email == state.saved_email;
+        unconfirmed.then(|| {
+            rsx! {
+                div {
+                    class: "flex flex-row justify-between text-sm",
+                    span { "This address is not confirmed yet." },
+                    button {
+                        class: "link",
+                        prevent_default: "onclick",
+                        onclick: resend_onclick,
+                        "Resend link"
+                    }
+                }
+            }
+        })
+    });
+
     cx.render(rsx! {
         div {
             label {
@@ -199,7 +243,8 @@ pub fn EmailInput(cx: Scope, page_state: UseRef<PageState>) -> Element {
                         page_state.with_mut(|state| state.email  = ev.value.clone());
                     },
                 }
-            }
+            },
+            Unconfirmed
         }
     })
 }
@@ -249,6 +294,367 @@ pub fn DisplayNameInput(cx: Scope, page_state: UseRef<PageState>) -> Element {
     })
 }
 
+/// Devices logged in to the account, which can be logged out remotely.
+/// QR code of an `otpauth://` URI, for scanning with an authenticator app.
+fn qr_code_svg(uri: &str) -> Option<String> {
+    use qrcode::{render::svg, QrCode};
+
+    let code = QrCode::new(uri.as_bytes()).ok()?;
+    Some(code.render::<svg::Color>().min_dimensions(200, 200).build())
+}
+
+#[inline_props]
+pub fn TwoFactorSettings(cx: Scope, page_state: UseRef<PageState>) -> Element {
+    use uchat_endpoint::user::EnableTwoFactorOk;
+
+    let api_client = ApiClient::global();
+    let toaster = use_toaster(cx);
+    let enrollment = use_state(cx, || None::<EnableTwoFactorOk>);
+    let code = use_state(cx, String::new);
+    let recovery_codes = use_state(cx, Vec::<String>::new);
+    let password = use_state(cx, String::new);
+
+    let enable_onclick = async_handler!(
+        &cx,
+        [api_client, toaster, enrollment],
+        move |_| async move {
+            use uchat_endpoint::user::EnableTwoFactor;
+            match fetch_json!(<EnableTwoFactorOk>, api_client, EnableTwoFactor) {
+                Ok(res) => enrollment.set(Some(res)),
+                Err(e) => toaster.write().error(
+                    format!("Failed to set up two-factor login: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let confirm_onclick = async_handler!(
+        &cx,
+        [
+            api_client,
+            toaster,
+            page_state,
+            enrollment,
+            code,
+            recovery_codes
+        ],
+        move |_| async move {
+            use uchat_endpoint::user::{ConfirmTwoFactor, ConfirmTwoFactorOk};
+            let request_data = ConfirmTwoFactor {
+                code: code.current().to_string(),
+            };
+            match fetch_json!(<ConfirmTwoFactorOk>, api_client, request_data) {
+                Ok(res) => {
+                    page_state.with_mut(|state| state.two_factor_enabled = true);
+                    enrollment.set(None);
+                    code.set(String::new());
+                    recovery_codes.set(res.recovery_codes);
+                    toaster
+                        .write()
+                        .success("Two-factor login enabled", chrono::Duration::seconds(3));
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to enable two-factor login: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let disable_onclick = async_handler!(
+        &cx,
+        [
+            api_client,
+            toaster,
+            page_state,
+            code,
+            recovery_codes,
+            password
+        ],
+        move |_| async move {
+            use uchat_domain::Password;
+            use uchat_endpoint::user::{DisableTwoFactor, DisableTwoFactorOk};
+            if !util::confirm("Turn off two-factor login?") {
+                return;
+            }
+            let request_data = match Password::new(password.current().as_str()) {
+                Ok(password) => DisableTwoFactor {
+                    password,
+                    code: code.current().to_string(),
+                },
+                Err(e) => {
+                    toaster.write().error(
+                        e.formatted_error().to_string(),
+                        chrono::Duration::seconds(3),
+                    );
+                    return;
+                }
+            };
+            match fetch_json!(<DisableTwoFactorOk>, api_client, request_data) {
+                Ok(_) => {
+                    page_state.with_mut(|state| state.two_factor_enabled = false);
+                    code.set(String::new());
+                    password.set(String::new());
+                    recovery_codes.set(Vec::new());
+                    toaster
+                        .write()
+                        .success("Two-factor login disabled", chrono::Duration::seconds(3));
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to disable two-factor login: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let CodeInput = rsx! {
+        input {
+            class: "input-field",
+            placeholder: "Code",
+            autocomplete: "one-time-code",
+            value: "{code.current()}",
+            oninput: move |ev| code.set(ev.value.clone())
+        }
+    };
+
+    let RecoveryCodes = (!recovery_codes.is_empty()).then(|| {
+        let codes = recovery_codes.iter().map(|recovery_code| {
+            rsx! {
+                li {
+                    key: "{recovery_code}",
+                    class: "font-mono",
+                    "{recovery_code}"
+                }
+            }
+        });
+        rsx! {
+            div {
+                class: "flex flex-col gap-2",
+                div {
+                    "Keep these recovery codes somewhere safe. Each can be used once to log in without your authenticator app, and they won't be shown again."
+                },
+                ul { codes }
+            }
+        }
+    });
+
+    let two_factor_enabled = page_state.with(|state| state.two_factor_enabled);
+    let Settings = match (two_factor_enabled, enrollment.get()) {
+        (true, _) => rsx! {
+            div {
+                "Two-factor login is on. Enter your password and a code from your app, or a recovery code, to turn it off."
+            },
+            div {
+                class: "flex flex-row gap-2",
+                input {
+                    class: "input-field",
+                    r#type: "password",
+                    placeholder: "Password",
+                    value: "{password.current()}",
+                    oninput: move |ev| password.set(ev.value.clone())
+                },
+                CodeInput,
+                button {
+                    class: "btn",
+                    onclick: disable_onclick,
+                    "Turn off"
+                }
+            }
+        },
+        (false, Some(enrollment)) => {
+            let QrCode = qr_code_svg(&enrollment.otpauth_uri).map(|svg| {
+                rsx! {
+                    div {
+                        class: "flex flex-row justify-center",
+                        dangerous_inner_html: "{svg}"
+                    }
+                }
+            });
+            rsx! {
+                div {
+                    "Scan this code with your authenticator app, then enter the code it shows."
+                },
+                QrCode,
+                div {
+                    class: "text-sm font-light break-all",
+                    "Or enter this key by hand: {enrollment.secret}"
+                },
+                div {
+                    class: "flex flex-row gap-2",
+                    CodeInput,
+                    button {
+                        class: "btn",
+                        onclick: confirm_onclick,
+                        "Confirm"
+                    }
+                }
+            }
+        }
+        (false, None) => rsx! {
+            div {
+                "Require a code from an authenticator app when logging in."
+            },
+            div {
+                class: "flex flex-row justify-end",
+                button {
+                    class: "btn",
+                    onclick: enable_onclick,
+                    "Set up"
+                }
+            }
+        },
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col gap-2 mt-6",
+            h2 {
+                class: "font-bold",
+                "Two-Factor Login"
+            },
+            Settings,
+            RecoveryCodes
+        }
+    })
+}
+
+pub fn SessionList(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let toaster = use_toaster(cx);
+    let sessions = use_ref(cx, Vec::<SessionInfo>::new);
+
+    let _fetch_sessions = {
+        to_owned![api_client, toaster, sessions];
+        use_future(cx, (), |_| async move {
+            use uchat_endpoint::user::{ListSessions, ListSessionsOk};
+            let response = fetch_json!(<ListSessionsOk>, api_client, ListSessions);
+            match response {
+                Ok(res) => sessions.set(res.sessions),
+                Err(e) => toaster.write().error(
+                    format!("Failed to retrieve sessions: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        })
+    };
+
+    let revoke_onclick = async_handler!(
+        &cx,
+        [api_client, toaster, sessions],
+        move |session_id| async move {
+            use uchat_endpoint::user::{RevokeSession, RevokeSessionOk};
+            let request_data = RevokeSession { session_id };
+            match fetch_json!(<RevokeSessionOk>, api_client, request_data) {
+                Ok(res) => {
+                    sessions.with_mut(|sessions| {
+                        sessions.retain(|session| session.id != res.session_id)
+                    });
+                    toaster
+                        .write()
+                        .success("Device logged out", chrono::Duration::seconds(3));
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to log out device: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let revoke_others_onclick =
+        async_handler!(&cx, [api_client, toaster, sessions], move |_| async move {
+            use uchat_endpoint::user::{RevokeAllOtherSessions, RevokeAllOtherSessionsOk};
+            if !util::confirm("Log out every other device?") {
+                return;
+            }
+            let response =
+                fetch_json!(<RevokeAllOtherSessionsOk>, api_client, RevokeAllOtherSessions);
+            match response {
+                Ok(res) => {
+                    sessions.with_mut(|sessions| sessions.retain(|session| session.current));
+                    toaster.write().success(
+                        format!("Other devices logged out: {}", res.revoked),
+                        chrono::Duration::seconds(3),
+                    );
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to log out other devices: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        });
+
+    let Sessions = sessions
+        .read()
+        .clone()
+        .into_iter()
+        .map(|session| {
+            let device = session
+                .device
+                .unwrap_or_else(|| "Unknown device".to_string());
+            let created_at = session
+                .created_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M");
+            let expires_at = session
+                .expires_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M");
+            let Action = match session.current {
+                true => rsx! {
+                    span {
+                        class: "text-sm font-light",
+                        "This device"
+                    }
+                },
+                false => rsx! {
+                    button {
+                        class: "btn",
+                        onclick: move |_| revoke_onclick(session.id),
+                        "Log out"
+                    }
+                },
+            };
+            rsx! {
+                div {
+                    key: "{session.id.to_string()}",
+                    class: "flex flex-row items-center justify-between gap-3 py-2 border-b",
+                    div {
+                        class: "flex flex-col",
+                        div { "{device}" },
+                        div {
+                            class: "text-sm font-light",
+                            "Logged in {created_at}, expires {expires_at}"
+                        }
+                    },
+                    Action
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col gap-2 mt-6",
+            h2 {
+                class: "font-bold",
+                "Sessions"
+            },
+            Sessions.into_iter(),
+            div {
+                class: "flex flex-row justify-end",
+                button {
+                    class: "btn",
+                    onclick: revoke_others_onclick,
+                    "Log out other devices"
+                }
+            }
+        }
+    })
+}
+
 pub fn EditProfile(cx: Scope) -> Element {
     let api_client = ApiClient::global();
     let page_state = use_ref(cx, || PageState::default());
@@ -272,6 +678,9 @@ pub fn EditProfile(cx: Scope) -> Element {
                     page_state.with_mut(|state| {
                         state.display_name = res.display_name.unwrap_or_default();
                         state.email = res.email.unwrap_or_default();
+                        state.saved_email = state.email.clone();
+                        state.email_confirmed = res.email_confirmed;
+                        state.two_factor_enabled = res.two_factor_enabled;
                         state.profile_image = res
                             .profile_image
                             .map(|img| PreviewImageData::Remote(img.to_string()));
@@ -336,6 +745,14 @@ pub fn EditProfile(cx: Scope) -> Element {
                     toaster
                         .write()
                         .success("Profile updated", chrono::Duration::seconds(3));
+                    let email_changed = page_state
+                        .with(|state| !state.email.is_empty() && state.email != state.saved_email);
+                    if email_changed {
+                        toaster.write().info(
+                            "Check your inbox to confirm your new email address",
+                            chrono::Duration::seconds(5),
+                        );
+                    }
                     local_profile.write().image = res.profile_image;
                     router.navigate_to(page::HOME);
                 }
@@ -387,6 +804,8 @@ pub fn EditProfile(cx: Scope) -> Element {
                     "Submit"
                 }
             }
-        }
+        },
+        TwoFactorSettings { page_state: page_state.clone() },
+        SessionList {}
     })
 }
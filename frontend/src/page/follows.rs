@@ -0,0 +1,136 @@
+#![allow(non_snake_case)]
+
+use std::str::FromStr;
+
+use chrono::Duration;
+use dioxus::prelude::*;
+use uchat_domain::ids::UserId;
+use uchat_endpoint::{Cursor, Page};
+
+use crate::{elements::UserEntry, prelude::*};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FollowKind {
+    Followers,
+    Following,
+}
+
+pub fn Followers(cx: Scope) -> Element {
+    cx.render(rsx! { FollowList { kind: FollowKind::Followers } })
+}
+
+pub fn Following(cx: Scope) -> Element {
+    cx.render(rsx! { FollowList { kind: FollowKind::Following } })
+}
+
+/// Users following, or followed by, the user in the route.
+#[inline_props]
+pub fn FollowList(cx: Scope, kind: FollowKind) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+
+    let user_id = use_route(cx)
+        .last_segment()
+        .and_then(|id| UserId::from_str(id).ok());
+
+    let users = use_ref(cx, Vec::new);
+    let next_cursor = use_ref(cx, || None::<Cursor>);
+    let page_number = use_scroll_to_bottom(cx);
+    let loaded = use_ref(cx, || None);
+
+    use_effect(
+        cx,
+        (&user_id, kind, page_number.get()),
+        |(user_id, kind, page_number)| {
+            to_owned![api_client, toaster, users, next_cursor, loaded];
+            async move {
+                use uchat_endpoint::user::{
+                    ListFollowers, ListFollowersOk, ListFollowing, ListFollowingOk,
+                };
+
+                let Some(user_id) = user_id else {
+                    return;
+                };
+                // a different list always starts from the first page
+                let page_number = match *loaded.read() == Some((user_id, kind)) {
+                    true => page_number,
+                    false => 0,
+                };
+                let page = match (page_number, next_cursor.read().clone()) {
+                    (0, _) => Page::default(),
+                    (_, Some(cursor)) => Page::after(cursor),
+                    (_, None) => return,
+                };
+                loaded.set(Some((user_id, kind)));
+
+                let response = match kind {
+                    FollowKind::Followers => {
+                        let request_data = ListFollowers { user_id, page };
+                        fetch_json!(<ListFollowersOk>, api_client, request_data)
+                            .map(|res| (res.users, res.next_cursor))
+                    }
+                    FollowKind::Following => {
+                        let request_data = ListFollowing { user_id, page };
+                        fetch_json!(<ListFollowingOk>, api_client, request_data)
+                            .map(|res| (res.users, res.next_cursor))
+                    }
+                };
+                match response {
+                    Ok((page_users, cursor)) => {
+                        if page_number == 0 {
+                            users.write().clear();
+                        }
+                        users.write().extend(page_users);
+                        next_cursor.set(cursor);
+                    }
+                    Err(e) => toaster.write().error(
+                        format!("Failed to retrieve users: {e}"),
+                        Duration::seconds(3),
+                    ),
+                }
+            }
+        },
+    );
+
+    let (title, empty) = match kind {
+        FollowKind::Followers => ("Followers", "No followers yet."),
+        FollowKind::Following => ("Following", "Not following anyone yet."),
+    };
+
+    let Users = users
+        .read()
+        .iter()
+        .map(|profile| {
+            rsx! {
+                UserEntry {
+                    key: "{profile.id.to_string()}",
+                    profile: profile.clone()
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let NoUsers = Users.is_empty().then(|| {
+        rsx! {
+            div {
+                class: "text-center my-6",
+                "{empty}"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: title,
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        NoUsers,
+        Users.into_iter()
+    })
+}
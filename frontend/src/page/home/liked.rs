@@ -9,19 +9,30 @@ pub fn HomeLiked(cx: Scope) -> Element {
     let toaster = use_toaster(cx);
     let api_client = ApiClient::global();
     let post_manager = use_post_manager(cx);
+    let page_number = use_scroll_to_bottom(cx);
     let router = use_router(cx);
 
     let _fetch_posts = {
         to_owned![api_client, toaster, post_manager];
-        use_future(cx, (), |_| async move {
+        use_future(cx, (page_number.get(),), |(page_number,)| async move {
             use uchat_endpoint::post::{LikedPosts, LikedPostsOk};
-            toaster
-                .write()
-                .info("Retrieving posts", Duration::seconds(3));
-            post_manager.write().clear();
-            let response = fetch_json!(<LikedPostsOk>, api_client, LikedPosts);
+            let page = match post_manager.write().next_page(page_number) {
+                Some(page) => page,
+                None => return,
+            };
+            if page_number == 0 {
+                toaster
+                    .write()
+                    .info("Retrieving posts", Duration::seconds(3));
+            }
+            let request_data = LikedPosts { page };
+            let response = fetch_json!(<LikedPostsOk>, api_client, request_data);
             match response {
-                Ok(res) => post_manager.write().populate(res.posts.into_iter()),
+                Ok(res) => {
+                    let mut post_manager = post_manager.write();
+                    post_manager.populate(res.posts.into_iter());
+                    post_manager.next_cursor = res.next_cursor;
+                }
                 Err(e) => toaster.write().error(
                     format!("Failed to retrieve posts: {e}"),
                     Duration::seconds(3),
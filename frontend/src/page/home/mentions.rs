@@ -0,0 +1,76 @@
+#![allow(non_snake_case)]
+
+use chrono::Duration;
+use dioxus::prelude::*;
+
+use crate::prelude::*;
+
+/// Posts which mention the logged in user.
+pub fn HomeMentions(cx: Scope) -> Element {
+    let toaster = use_toaster(cx);
+    let api_client = ApiClient::global();
+    let post_manager = use_post_manager(cx);
+    let page_number = use_scroll_to_bottom(cx);
+    let router = use_router(cx);
+
+    let _fetch_posts = {
+        to_owned![api_client, toaster, post_manager];
+        use_future(cx, (page_number.get(),), |(page_number,)| async move {
+            use uchat_endpoint::post::{MentionedPosts, MentionedPostsOk};
+            let page = match post_manager.write().next_page(page_number) {
+                Some(page) => page,
+                None => return,
+            };
+            if page_number == 0 {
+                toaster
+                    .write()
+                    .info("Retrieving posts", Duration::seconds(3));
+            }
+            let request_data = MentionedPosts { page };
+            let response = fetch_json!(<MentionedPostsOk>, api_client, request_data);
+            match response {
+                Ok(res) => {
+                    let mut post_manager = post_manager.write();
+                    post_manager.populate(res.posts.into_iter());
+                    post_manager.next_cursor = res.next_cursor;
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to retrieve posts: {e}"),
+                    Duration::seconds(3),
+                ),
+            }
+        })
+    };
+
+    let Posts = {
+        let posts = post_manager.read().all_to_public();
+        if posts.is_empty() {
+            rsx! {
+                div {
+                    class: "
+                        flex flex-col text-center justify-center
+                        h-[calc(100vh_-_var(--navbar-height)_-_var(--appbar-height))]
+                    ",
+                    span {
+                        "Nobody has mentioned you yet."
+                    }
+                }
+            }
+        } else {
+            rsx! { posts.into_iter() }
+        }
+    };
+
+    cx.render(rsx! {
+        Appbar  {
+            title: "Mentions",
+            AppbarImgButton {
+                click_handler: move |_| router.navigate_to(page::HOME),
+                img: "/static/icons/icon-home.svg",
+                label: "Home",
+                title: "Go to the home page"
+            }
+        },
+        Posts
+    })
+}
@@ -0,0 +1,138 @@
+#![allow(non_snake_case)]
+
+use chrono::Duration;
+use dioxus::prelude::*;
+use uchat_endpoint::{
+    post::{Content, ConversationPreview},
+    Cursor, Page,
+};
+
+use crate::prelude::*;
+
+/// Short, single line description of a message.
+pub fn message_preview(content: &Content) -> String {
+    match content {
+        Content::Chat(chat) => chat.message.as_ref().to_string(),
+        Content::Image(_) => "[Image]".to_string(),
+        Content::Poll(poll) => format!("[Poll] {}", poll.headline.as_ref()),
+    }
+}
+
+#[inline_props]
+fn ConversationEntry(cx: Scope, preview: ConversationPreview) -> Element {
+    let router = use_router(cx);
+
+    let with_user = &preview.with_user;
+    let profile_img_src = with_user
+        .profile_image
+        .as_ref()
+        .map(|url| url.as_str())
+        .unwrap_or_else(|| "");
+    let name = with_user
+        .display_name
+        .as_ref()
+        .map(|name| name.as_ref())
+        .unwrap_or_else(|| with_user.handle.as_str());
+    let message = message_preview(&preview.last_message.content);
+    let sent = preview.last_message.time_posted.format("%Y-%m-%d");
+
+    cx.render(rsx! {
+        div {
+            class: "grid grid-cols-[50px_1fr_auto] gap-2 items-center py-2 border-b cursor-pointer",
+            onclick: move |_| router.navigate_to(&page::conversation(with_user.id)),
+            img {
+                class: "profile-portrait",
+                src: "{profile_img_src}"
+            },
+            div {
+                class: "overflow-hidden",
+                div { "{name}" },
+                div {
+                    class: "font-light truncate",
+                    "{message}"
+                }
+            },
+            div {
+                class: "text-sm font-light",
+                "{sent}"
+            }
+        }
+    })
+}
+
+pub fn Inbox(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let page_number = use_scroll_to_bottom(cx);
+
+    let conversations = use_ref(cx, Vec::<ConversationPreview>::new);
+    let next_cursor = use_ref(cx, || None::<Cursor>);
+
+    let _fetch_conversations = {
+        to_owned![api_client, toaster, conversations, next_cursor];
+        use_future(cx, (page_number.get(),), |(page_number,)| async move {
+            use uchat_endpoint::post::{ListConversations, ListConversationsOk};
+
+            let page = match page_number {
+                0 => {
+                    conversations.write().clear();
+                    Page::default()
+                }
+                _ => match next_cursor.read().clone() {
+                    Some(cursor) => Page::after(cursor),
+                    None => return,
+                },
+            };
+
+            let request_data = ListConversations { page };
+            let response = fetch_json!(<ListConversationsOk>, api_client, request_data);
+            match response {
+                Ok(res) => {
+                    conversations.write().extend(res.conversations);
+                    next_cursor.set(res.next_cursor);
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to retrieve messages: {e}"),
+                    Duration::seconds(3),
+                ),
+            }
+        })
+    };
+
+    let Conversations = conversations
+        .read()
+        .iter()
+        .map(|preview| {
+            rsx! {
+                ConversationEntry {
+                    key: "{preview.with_user.id.to_string()}",
+                    preview: preview.clone()
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let Empty = Conversations.is_empty().then(|| {
+        rsx! {
+            div {
+                class: "text-center my-6",
+                "No messages yet. Visit a profile to start a conversation."
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Messages",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        Empty,
+        Conversations.into_iter()
+    })
+}
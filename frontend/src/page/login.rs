@@ -14,6 +14,9 @@ use crate::{
 struct PageState {
     username: UseState<String>,
     password: UseState<String>,
+    /// Set once the password was accepted, while waiting for the second factor.
+    pending_token: UseState<Option<String>>,
+    code: UseState<String>,
     form_errors: KeyedNotifications,
     server_messages: KeyedNotifications,
 }
@@ -23,12 +26,17 @@ impl PageState {
         Self {
             username: use_state(cx, String::new).clone(),
             password: use_state(cx, String::new).clone(),
+            pending_token: use_state(cx, || None).clone(),
+            code: use_state(cx, String::new).clone(),
             form_errors: KeyedNotifications::default(),
             server_messages: KeyedNotifications::default(),
         }
     }
 
     fn can_submit(&self) -> bool {
+        if self.pending_token.is_some() {
+            return !self.code.current().trim().is_empty();
+        }
         !(self.form_errors.has_messages()
             || self.username.current().is_empty()
             || self.password.current().is_empty())
@@ -86,6 +94,32 @@ fn PasswordInput<'a>(
     })
 }
 
+#[inline_props]
+fn CodeInput<'a>(
+    cx: Scope<'a>,
+    state: UseState<String>,
+    oninput: EventHandler<'a, FormEvent>,
+) -> Element<'a> {
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col",
+            label {
+                r#for: "code",
+                "Code from your authenticator app, or a recovery code"
+            },
+            input {
+                id: "code",
+                name: "code",
+                class: "input-field",
+                placeholder: "Code",
+                autocomplete: "one-time-code",
+                value: "{state.current()}",
+                oninput: move |ev| oninput.call(ev)
+            }
+        }
+    })
+}
+
 fn RegisterLink(cx: Scope) -> Element {
     cx.render(rsx! {
         Link {
@@ -96,6 +130,16 @@ fn RegisterLink(cx: Scope) -> Element {
     })
 }
 
+fn ForgotPasswordLink(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Link {
+            class: "link text-center",
+            to: page::ACCOUNT_FORGOT_PASSWORD,
+            "Forgot Password?"
+        }
+    })
+}
+
 pub fn Login(cx: Scope) -> Element {
     let api_client = ApiClient::global();
     let page_state = PageState::new(cx);
@@ -107,7 +151,33 @@ pub fn Login(cx: Scope) -> Element {
         &cx,
         [api_client, page_state, router, local_profile],
         move |_| async move {
-            use uchat_endpoint::user::{Login, LoginOk};
+            use uchat_endpoint::user::{CompleteLogin, CompleteLoginOk, LoggedIn, Login, LoginOk};
+
+            let logged_in = |res: LoggedIn| {
+                crate::util::cookie::set_session(
+                    res.session_signature,
+                    res.session_id,
+                    res.session_expires,
+                );
+                local_profile.write().image = res.profile_image;
+                local_profile.write().user_id = Some(res.user_id);
+                router.navigate_to(page::HOME);
+            };
+
+            let pending_token = page_state.with(|state| state.pending_token.get().clone());
+            if let Some(pending_token) = pending_token {
+                let request_data = CompleteLogin {
+                    pending_token,
+                    code: page_state.with(|state| state.code.current().trim().to_string()),
+                };
+                let response = fetch_json!(<CompleteLoginOk>, api_client, request_data);
+                match response {
+                    Ok(res) => logged_in(res.session),
+                    Err(e) => page_state
+                        .with_mut(|state| state.server_messages.set("logn-fail", e.to_string())),
+                }
+                return;
+            }
 
             let request_data = {
                 use uchat_domain::{Password, Username};
@@ -120,21 +190,17 @@ pub fn Login(cx: Scope) -> Element {
                         page_state.with(|state| state.password.current().to_string()),
                     )
                     .unwrap(),
+                    device_id: Some(crate::util::cookie::device_id()),
                 }
             };
 
             let response = fetch_json!(<LoginOk>, api_client, request_data);
             match response {
-                Ok(res) => {
-                    crate::util::cookie::set_session(
-                        res.session_signature,
-                        res.session_id,
-                        res.session_expires,
-                    );
-                    local_profile.write().image = res.profile_image;
-                    local_profile.write().user_id = Some(res.user_id);
-                    router.navigate_to(page::HOME);
-                }
+                Ok(LoginOk::LoggedIn(res)) => logged_in(res),
+                Ok(LoginOk::TwoFactorRequired { pending_token }) => page_state.with_mut(|state| {
+                    state.server_messages.remove("logn-fail");
+                    state.pending_token.set(Some(pending_token));
+                }),
                 Err(e) => page_state
                     .with_mut(|state| state.server_messages.set("logn-fail", e.to_string())),
             }
@@ -159,20 +225,21 @@ pub fn Login(cx: Scope) -> Element {
         page_state.with_mut(|state| state.password.set(ev.value.clone()));
     });
 
+    let code_oninput = sync_handler!([page_state], move |ev: FormEvent| {
+        page_state.with_mut(|state| state.code.set(ev.value.clone()));
+    });
+
     let submit_btn_style =
         maybe_class!("btn-disabled", !page_state.with(|state| state.can_submit()));
 
-    cx.render(rsx! {
-        form {
-            class: "flex flex-col gap-5",
-            prevent_default: "onsubmit",
-            onsubmit: form_onsubmit,
-
-            KeyedNotificationBox {
-                legend: "Login errors",
-                notifications: page_state.with(|state| state.server_messages.clone())
-            },
-
+    let Inputs = match page_state.with(|state| state.pending_token.is_some()) {
+        true => rsx! {
+            CodeInput {
+                state: page_state.with(|state| state.code.clone()),
+                oninput: code_oninput,
+            }
+        },
+        false => rsx! {
             UsernameInput {
                 state: page_state.with(|state| state.username.clone()),
                 oninput: username_oninput,
@@ -185,6 +252,23 @@ pub fn Login(cx: Scope) -> Element {
 
             RegisterLink {},
 
+            ForgotPasswordLink {},
+        },
+    };
+
+    cx.render(rsx! {
+        form {
+            class: "flex flex-col gap-5",
+            prevent_default: "onsubmit",
+            onsubmit: form_onsubmit,
+
+            KeyedNotificationBox {
+                legend: "Login errors",
+                notifications: page_state.with(|state| state.server_messages.clone())
+            },
+
+            Inputs,
+
             KeyedNotificationBox {
                 legend: "Form errors",
                 notifications: page_state.with(|state| state.form_errors.clone())
@@ -1,17 +1,43 @@
+mod blocked;
+mod confirm_email;
+mod conversation;
 mod edit_profile;
+mod follows;
 mod home;
+mod inbox;
 mod login;
 mod new_post;
+mod notifications;
+mod post_revisions;
 mod register;
+mod reset_password;
 mod route;
+mod scheduled;
+mod search_posts;
+mod search_users;
+mod tagged;
+mod thread;
 mod trending;
 mod view_profile;
 
+pub use blocked::Blocked;
+pub use confirm_email::EmailConfirmation;
+pub use conversation::Conversation;
 pub use edit_profile::EditProfile;
-pub use home::{Home, HomeBookmarked, HomeLiked};
+pub use follows::{Followers, Following};
+pub use home::{Home, HomeBookmarked, HomeLiked, HomeMentions};
+pub use inbox::Inbox;
 pub use login::Login;
 pub use new_post::*;
+pub use notifications::Notifications;
+pub use post_revisions::PostRevisions;
 pub use register::Register;
+pub use reset_password::{ForgotPassword, NewPassword};
 pub use route::*;
+pub use scheduled::Scheduled;
+pub use search_posts::SearchPosts;
+pub use search_users::SearchUsers;
+pub use tagged::TaggedPosts;
+pub use thread::Thread;
 pub use trending::Trending;
 pub use view_profile::ViewProfile;
@@ -1,6 +1,6 @@
 #![allow(non_snake_case)]
 
-use chrono::Duration;
+use chrono::{DateTime, Duration, Utc};
 use dioxus::prelude::*;
 use dioxus_router::use_router;
 use serde::{Deserialize, Serialize};
@@ -12,6 +12,8 @@ use crate::{fetch_json, prelude::*};
 pub struct PageState {
     pub message: String,
     pub headline: String,
+    /// Publishes the post later when set.
+    pub time_posted: Option<DateTime<Utc>>,
 }
 
 impl PageState {
@@ -107,6 +109,8 @@ pub fn NewChat(cx: Scope) -> Element {
         move |_| async move {
             use uchat_endpoint::post::{Chat, NewPost, NewPostOk, NewPostOptions};
 
+            let scheduled = page_state.read().time_posted.is_some();
+
             let request = NewPost {
                 content: Chat {
                     headline: {
@@ -120,11 +124,18 @@ pub fn NewChat(cx: Scope) -> Element {
                     message: Message::new(&page_state.read().message).unwrap(),
                 }
                 .into(),
-                options: NewPostOptions::default(),
+                options: NewPostOptions {
+                    time_posted: page_state.read().time_posted.unwrap_or_else(Utc::now),
+                    ..NewPostOptions::default()
+                },
             };
 
             let response = fetch_json!(<NewPostOk>, api_client, request);
             match response {
+                Ok(_) if scheduled => {
+                    toaster.write().success("Scheduled", Duration::seconds(3));
+                    router.replace_route(page::POSTS_SCHEDULED, None, None);
+                }
                 Ok(_) => {
                     toaster.write().success("Posted", Duration::seconds(3));
                     router.replace_route(page::HOME, None, None);
@@ -175,6 +186,12 @@ pub fn NewChat(cx: Scope) -> Element {
 
             MessageInput { page_state: page_state.clone() },
             HeadlineInput { page_state: page_state.clone() },
+            DateTimeInput {
+                id: "time-posted".to_string(),
+                label: "Schedule for later (optional)",
+                value: page_state.read().time_posted,
+                on_change: move |time| page_state.with_mut(|state| state.time_posted = time),
+            },
 
             button {
                 class: "btn {submit_btn_style}",
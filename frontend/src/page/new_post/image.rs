@@ -1,6 +1,6 @@
 #![allow(non_snake_case)]
 
-use chrono::Duration;
+use chrono::{DateTime, Duration, Utc};
 use dioxus::prelude::*;
 use dioxus_router::use_router;
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,8 @@ use crate::{prelude::*, util};
 pub struct PageState {
     pub caption: String,
     pub image: Option<String>,
+    /// Publishes the post later when set.
+    pub time_posted: Option<DateTime<Utc>>,
 }
 
 impl PageState {
@@ -136,6 +138,8 @@ pub fn NewImage(cx: Scope) -> Element {
         move |_| async move {
             use uchat_endpoint::post::{Image, ImageKind, NewPost, NewPostOk, NewPostOptions};
 
+            let scheduled = page_state.read().time_posted.is_some();
+
             let request = NewPost {
                 content: Image {
                     caption: {
@@ -152,11 +156,18 @@ pub fn NewImage(cx: Scope) -> Element {
                     },
                 }
                 .into(),
-                options: NewPostOptions::default(),
+                options: NewPostOptions {
+                    time_posted: page_state.read().time_posted.unwrap_or_else(Utc::now),
+                    ..NewPostOptions::default()
+                },
             };
 
             let response = fetch_json!(<NewPostOk>, api_client, request);
             match response {
+                Ok(_) if scheduled => {
+                    toaster.write().success("Scheduled", Duration::seconds(3));
+                    router.replace_route(page::POSTS_SCHEDULED, None, None);
+                }
                 Ok(_) => {
                     toaster.write().success("Posted", Duration::seconds(3));
                     router.replace_route(page::HOME, None, None);
@@ -208,6 +219,12 @@ pub fn NewImage(cx: Scope) -> Element {
             ImageInput { page_state: page_state.clone() },
             ImagePreview { page_state: page_state.clone() },
             CaptionInput { page_state: page_state.clone() },
+            DateTimeInput {
+                id: "time-posted".to_string(),
+                label: "Schedule for later (optional)",
+                value: page_state.read().time_posted,
+                on_change: move |time| page_state.with_mut(|state| state.time_posted = time),
+            },
 
             button {
                 class: "btn {submit_btn_style}",
@@ -2,12 +2,12 @@
 
 use std::collections::BTreeMap;
 
-use chrono::Duration;
+use chrono::{DateTime, Duration, Utc};
 use dioxus::prelude::*;
 use dioxus_router::use_router;
 use serde::{Deserialize, Serialize};
 use uchat_domain::{ids::PollChoiceId, PollChoiceDescription, PollHeadline};
-use uchat_endpoint::post::{Poll, PollChoice};
+use uchat_endpoint::post::{Poll, PollChoice, PollSelection};
 
 use crate::prelude::*;
 
@@ -16,6 +16,13 @@ pub struct PageState {
     pub headline: String,
     pub poll_choices: BTreeMap<usize, String>,
     pub next_id: usize,
+    /// Publishes the post later when set.
+    pub time_posted: Option<DateTime<Utc>>,
+    /// Stops accepting votes when set.
+    pub closes_at: Option<DateTime<Utc>>,
+    pub hide_results: bool,
+    /// Allows voting for up to this many choices when set.
+    pub max_choices: Option<u32>,
 }
 
 impl Default for PageState {
@@ -26,6 +33,10 @@ impl Default for PageState {
                 .into_iter()
                 .collect(),
             next_id: 2,
+            time_posted: None,
+            closes_at: None,
+            hide_results: false,
+            max_choices: None,
         }
     }
 }
@@ -40,6 +51,27 @@ impl PageState {
                 .map(PollChoiceDescription::new)
                 .collect::<Result<Vec<PollChoiceDescription>, _>>()
                 .is_ok()
+            && self.closes_after_posting()
+            && self.max_choices_in_range()
+    }
+
+    pub fn max_choices_in_range(&self) -> bool {
+        self.max_choices.map_or(true, |max_choices| {
+            (1..=self.poll_choices.len()).contains(&(max_choices as usize))
+        })
+    }
+
+    pub fn selection(&self) -> PollSelection {
+        match self.max_choices {
+            Some(max_choices) => PollSelection::Multiple { max_choices },
+            None => PollSelection::Single,
+        }
+    }
+
+    /// Whether the poll stays open for some time once it is posted.
+    pub fn closes_after_posting(&self) -> bool {
+        let posted = self.time_posted.unwrap_or_else(Utc::now).max(Utc::now());
+        self.closes_at.map_or(true, |closes_at| closes_at > posted)
     }
 
     pub fn push_choice<T: Into<String>>(&mut self, choice: T) {
@@ -152,6 +184,91 @@ pub fn PollChoices(cx: Scope, page_state: UseRef<PageState>) -> Element {
     })
 }
 
+#[inline_props]
+pub fn Selection(cx: Scope, page_state: UseRef<PageState>) -> Element {
+    let max_choices = page_state.read().max_choices;
+    let multiple = max_choices.is_some();
+    let wrong_max = maybe_class!("err-text-color", !page_state.read().max_choices_in_range());
+
+    let MaxChoices = max_choices.map(|max_choices| {
+        rsx! {
+            label {
+                class: "flex flex-row gap-2 items-center {wrong_max}",
+                r#for: "max-choices",
+                "Up to",
+                input {
+                    class: "input-field w-16",
+                    id: "max-choices",
+                    r#type: "number",
+                    min: "1",
+                    max: "{page_state.read().poll_choices.len()}",
+                    value: "{max_choices}",
+                    oninput: move |ev| {
+                        if let Ok(max_choices) = ev.value.parse() {
+                            page_state.with_mut(|state| state.max_choices = Some(max_choices))
+                        }
+                    },
+                },
+                "choices"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-row gap-4 items-center h-8",
+            label {
+                class: "flex flex-row gap-2 items-center",
+                r#for: "multiple-choices",
+                input {
+                    id: "multiple-choices",
+                    r#type: "checkbox",
+                    checked: "{multiple}",
+                    oninput: move |ev| {
+                        let max_choices = (ev.value == "true").then_some(2);
+                        page_state.with_mut(|state| state.max_choices = max_choices)
+                    },
+                },
+                "Allow multiple choices"
+            },
+            MaxChoices
+        }
+    })
+}
+
+#[inline_props]
+pub fn Deadline(cx: Scope, page_state: UseRef<PageState>) -> Element {
+    let has_deadline = page_state.read().closes_at.is_some();
+    let hide_results = page_state.read().hide_results && has_deadline;
+    let wrong_deadline = maybe_class!("err-text-color", !page_state.read().closes_after_posting());
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col gap-2 {wrong_deadline}",
+            DateTimeInput {
+                id: "closes-at".to_string(),
+                label: "Close voting at (optional)",
+                value: page_state.read().closes_at,
+                on_change: move |time| page_state.with_mut(|state| state.closes_at = time),
+            },
+            label {
+                class: "flex flex-row gap-2 items-center",
+                r#for: "hide-results",
+                input {
+                    id: "hide-results",
+                    r#type: "checkbox",
+                    checked: "{hide_results}",
+                    disabled: !has_deadline,
+                    oninput: move |ev| {
+                        page_state.with_mut(|state| state.hide_results = ev.value == "true")
+                    },
+                },
+                "Hide results until voting closes"
+            }
+        }
+    })
+}
+
 pub fn NewPoll(cx: Scope) -> Element {
     let api_client = ApiClient::global();
     let page_state = use_ref(cx, || PageState::default());
@@ -165,6 +282,8 @@ pub fn NewPoll(cx: Scope) -> Element {
         move |_| async move {
             use uchat_endpoint::post::{NewPost, NewPostOk, NewPostOptions};
 
+            let scheduled = page_state.read().time_posted.is_some();
+
             let request = NewPost {
                 content: Poll {
                     headline: {
@@ -186,14 +305,27 @@ pub fn NewPoll(cx: Scope) -> Element {
                             })
                             .collect::<Vec<PollChoice>>()
                     },
-                    voted: None,
+                    selection: page_state.read().selection(),
+                    voted: Default::default(),
+                    closes_at: page_state.read().closes_at,
+                    hide_results: {
+                        let state = page_state.read();
+                        state.hide_results && state.closes_at.is_some()
+                    },
                 }
                 .into(),
-                options: NewPostOptions::default(),
+                options: NewPostOptions {
+                    time_posted: page_state.read().time_posted.unwrap_or_else(Utc::now),
+                    ..NewPostOptions::default()
+                },
             };
 
             let response = fetch_json!(<NewPostOk>, api_client, request);
             match response {
+                Ok(_) if scheduled => {
+                    toaster.write().success("Scheduled", Duration::seconds(3));
+                    router.replace_route(page::POSTS_SCHEDULED, None, None);
+                }
                 Ok(_) => {
                     toaster.write().success("Posted", Duration::seconds(3));
                     router.replace_route(page::HOME, None, None);
@@ -244,6 +376,14 @@ pub fn NewPoll(cx: Scope) -> Element {
 
             HeadlineInput { page_state: page_state.clone() },
             PollChoices { page_state: page_state.clone() },
+            Selection { page_state: page_state.clone() },
+            Deadline { page_state: page_state.clone() },
+            DateTimeInput {
+                id: "time-posted".to_string(),
+                label: "Schedule for later (optional)",
+                value: page_state.read().time_posted,
+                on_change: move |time| page_state.with_mut(|state| state.time_posted = time),
+            },
             button {
                 class: "btn {submit_btn_style}",
                 r#type: "submit",
@@ -0,0 +1,182 @@
+#![allow(non_snake_case)]
+
+use chrono::Duration;
+use dioxus::prelude::*;
+use fermi::use_atom_ref;
+use uchat_endpoint::{
+    notification::{Notification, NotificationKind},
+    Cursor, Page,
+};
+
+use crate::prelude::*;
+
+/// Describes who did what, such as "alice and 4 others liked your post".
+fn summary(notification: &Notification) -> String {
+    let name = notification
+        .actors
+        .first()
+        .map(|actor| match &actor.display_name {
+            Some(name) => name.as_ref().to_string(),
+            None => actor.handle.clone(),
+        })
+        .unwrap_or_else(|| "Someone".to_string());
+    let who = match notification.actor_count - 1 {
+        n if n < 1 => name,
+        1 => format!("{name} and 1 other"),
+        n => format!("{name} and {n} others"),
+    };
+    let what = match notification.kind {
+        NotificationKind::Follow => "followed you",
+        NotificationKind::Like => "liked your post",
+        NotificationKind::Boost => "boosted your post",
+        NotificationKind::Reply => "replied to your post",
+        NotificationKind::Vote => "voted in your poll",
+        NotificationKind::DirectMessage => "sent you a message",
+    };
+    format!("{who} {what}")
+}
+
+#[inline_props]
+fn NotificationEntry(cx: Scope, notification: Notification) -> Element {
+    let router = use_router(cx);
+
+    let text = summary(notification);
+    let time = notification
+        .latest
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M");
+    let unread = maybe_class!("font-bold", notification.unread);
+
+    let target = match (notification.kind, notification.post_id) {
+        (NotificationKind::Follow, _) => notification
+            .actors
+            .first()
+            .map(|actor| page::profile_view(actor.id)),
+        (NotificationKind::DirectMessage, _) => notification
+            .actors
+            .first()
+            .map(|actor| page::conversation(actor.id)),
+        (_, Some(post_id)) => Some(page::post_thread(post_id)),
+        (_, None) => None,
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col py-2 border-b cursor-pointer",
+            onclick: move |_| {
+                if let Some(target) = &target {
+                    router.navigate_to(target);
+                }
+            },
+            div {
+                class: "{unread}",
+                "{text}"
+            },
+            div {
+                class: "text-sm font-light",
+                "{time}"
+            }
+        }
+    })
+}
+
+pub fn Notifications(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let unread_notifications = use_atom_ref(cx, crate::app::UNREAD_NOTIFICATIONS);
+    let page_number = use_scroll_to_bottom(cx);
+
+    let notifications = use_ref(cx, Vec::<Notification>::new);
+    let next_cursor = use_ref(cx, || None::<Cursor>);
+
+    let _fetch_notifications = {
+        to_owned![
+            api_client,
+            toaster,
+            notifications,
+            next_cursor,
+            unread_notifications
+        ];
+        use_future(cx, (page_number.get(),), |(page_number,)| async move {
+            use uchat_endpoint::notification::{
+                ListNotifications, ListNotificationsOk, MarkNotificationsRead,
+                MarkNotificationsReadOk,
+            };
+
+            let page = match page_number {
+                0 => {
+                    notifications.write().clear();
+                    Page::default()
+                }
+                _ => match next_cursor.read().clone() {
+                    Some(cursor) => Page::after(cursor),
+                    None => return,
+                },
+            };
+
+            let request_data = ListNotifications { page };
+            let response = fetch_json!(<ListNotificationsOk>, api_client, request_data);
+            match response {
+                Ok(res) => {
+                    notifications.write().extend(res.notifications);
+                    next_cursor.set(res.next_cursor);
+                }
+                Err(e) => {
+                    toaster.write().error(
+                        format!("Failed to retrieve notifications: {e}"),
+                        Duration::seconds(3),
+                    );
+                    return;
+                }
+            }
+
+            // notifications arriving while the page is open stay unread
+            let newest = notifications
+                .read()
+                .first()
+                .map(|notification| notification.latest);
+            if let (0, Some(until)) = (page_number, newest) {
+                let request_data = MarkNotificationsRead { until };
+                if let Ok(res) = fetch_json!(<MarkNotificationsReadOk>, api_client, request_data) {
+                    *unread_notifications.write() = res.unread;
+                }
+            }
+        })
+    };
+
+    let Entries = notifications
+        .read()
+        .iter()
+        .map(|notification| {
+            rsx! {
+                NotificationEntry {
+                    notification: notification.clone()
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let NoNotifications = Entries.is_empty().then(|| {
+        rsx! {
+            div {
+                class: "text-center my-6",
+                "No notifications yet."
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Notifications",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        NoNotifications,
+        Entries.into_iter()
+    })
+}
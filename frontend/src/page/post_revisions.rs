@@ -0,0 +1,107 @@
+#![allow(non_snake_case)]
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use dioxus::prelude::*;
+use uchat_domain::ids::PostId;
+use uchat_endpoint::post::{Content, PostRevision, PublicPost};
+
+use crate::prelude::*;
+
+#[inline_props]
+fn RevisionEntry(cx: Scope, label: String, time: DateTime<Utc>, content: Content) -> Element {
+    let text = super::inbox::message_preview(content);
+    let time = time.format("%Y-%m-%d %H:%M");
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col gap-1 py-2 border-b",
+            div {
+                class: "text-sm font-light",
+                "{label} {time}"
+            },
+            div { "{text}" }
+        }
+    })
+}
+
+pub fn PostRevisions(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+
+    let post_id = use_route(cx)
+        .last_segment()
+        .and_then(|id| PostId::from_str(id).ok());
+
+    let post = use_ref(cx, || None::<PublicPost>);
+    let revisions = use_ref(cx, Vec::<PostRevision>::new);
+
+    use_effect(cx, (&post_id,), |(post_id,)| {
+        to_owned![api_client, toaster, post, revisions];
+        async move {
+            use uchat_endpoint::post::{GetPostRevisions, GetPostRevisionsOk};
+
+            let Some(post_id) = post_id else {
+                return;
+            };
+
+            let request_data = GetPostRevisions { post_id };
+            let response = fetch_json!(<GetPostRevisionsOk>, api_client, request_data);
+            match response {
+                Ok(res) => {
+                    post.set(Some(res.post));
+                    revisions.set(res.revisions);
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to retrieve edit history: {e}"),
+                    Duration::seconds(3),
+                ),
+            }
+        }
+    });
+
+    let Current = post.read().clone().map(|post| {
+        let time = post.edited_at.unwrap_or(post.time_posted);
+        rsx! {
+            RevisionEntry {
+                label: "Current version, since".to_string(),
+                time: time,
+                content: post.content,
+            }
+        }
+    });
+
+    let Revisions = revisions
+        .read()
+        .iter()
+        .map(|revision| {
+            rsx! {
+                RevisionEntry {
+                    key: "{revision.revised_at.timestamp_micros()}",
+                    label: "Replaced".to_string(),
+                    time: revision.revised_at,
+                    content: revision.content.clone(),
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Edit History",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        div {
+            class: "flex flex-col",
+            Current,
+            Revisions.into_iter()
+        }
+    })
+}
@@ -120,6 +120,7 @@ pub fn Register(cx: Scope) -> Element {
                         page_state.with(|state| state.password.current().to_string()),
                     )
                     .unwrap(),
+                    device_id: Some(crate::util::cookie::device_id()),
                 }
             };
 
@@ -0,0 +1,201 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::Link;
+use uchat_domain::{Password, UserFacingError};
+
+use crate::prelude::*;
+
+fn LoginLink(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Link {
+            class: "link text-center",
+            to: page::ACCOUNT_LOGIN,
+            "Back to Login"
+        }
+    })
+}
+
+/// Asks for a password reset link to be emailed.
+pub fn ForgotPassword(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let email = use_state(cx, String::new);
+    let message = use_state(cx, || None::<String>);
+
+    let form_onsubmit = async_handler!(&cx, [api_client, email, message], move |_| async move {
+        use uchat_endpoint::user::{RequestPasswordReset, RequestPasswordResetOk};
+
+        let request_data = RequestPasswordReset {
+            email: email.current().to_string(),
+        };
+        let response = fetch_json!(<RequestPasswordResetOk>, api_client, request_data);
+        message.set(Some(match response {
+            Ok(_) => "If this address is confirmed on an account, a reset link is on its way."
+                .to_string(),
+            Err(e) => format!("Failed to request a reset link: {e}"),
+        }));
+    });
+
+    let submit_btn_style = maybe_class!("btn-disabled", email.is_empty());
+
+    let Message = message.get().as_ref().map(|message| {
+        rsx! {
+            div {
+                class: "text-center",
+                "{message}"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        form {
+            class: "flex flex-col gap-5",
+            prevent_default: "onsubmit",
+            onsubmit: form_onsubmit,
+
+            div {
+                class: "flex flex-col",
+                label {
+                    r#for: "email",
+                    "Email Address"
+                },
+                input {
+                    id: "email",
+                    name: "email",
+                    class: "input-field",
+                    placeholder: "Email Address",
+                    value: "{email.current()}",
+                    oninput: move |ev| email.set(ev.value.clone())
+                }
+            },
+
+            Message,
+
+            LoginLink {},
+
+            button {
+                class: "btn {submit_btn_style}",
+                r#type: "submit",
+                disabled: email.is_empty(),
+                "Send Reset Link"
+            }
+        }
+    })
+}
+
+/// Opened from the link in a password reset email, to choose a new password.
+pub fn NewPassword(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let password = use_state(cx, String::new);
+    let confirmation = use_state(cx, String::new);
+    let error = use_state(cx, || None::<String>);
+
+    let token = use_route(cx).last_segment().map(|token| token.to_string());
+
+    let form_onsubmit = async_handler!(
+        &cx,
+        [api_client, router, toaster, password, error, token],
+        move |_| async move {
+            use uchat_endpoint::user::{ResetPassword, ResetPasswordOk};
+
+            let Some(token) = token else {
+                return;
+            };
+            let password = match Password::new(password.current().as_str()) {
+                Ok(password) => password,
+                Err(e) => {
+                    error.set(Some(e.formatted_error().to_string()));
+                    return;
+                }
+            };
+
+            let request_data = ResetPassword { token, password };
+            match fetch_json!(<ResetPasswordOk>, api_client, request_data) {
+                Ok(_) => {
+                    toaster.write().success(
+                        "Password changed, please log in",
+                        chrono::Duration::seconds(5),
+                    );
+                    router.navigate_to(page::ACCOUNT_LOGIN);
+                }
+                Err(e) => error.set(Some(format!("Failed to reset password: {e}"))),
+            }
+        }
+    );
+
+    let mismatched = *password.current() != *confirmation.current();
+    let can_submit = !password.is_empty() && !mismatched;
+    let submit_btn_style = maybe_class!("btn-disabled", !can_submit);
+
+    let Error = match (error.get(), mismatched && !confirmation.is_empty()) {
+        (Some(e), _) => Some(e.clone()),
+        (None, true) => Some("Passwords do not match".to_string()),
+        (None, false) => None,
+    }
+    .map(|e| {
+        rsx! {
+            div {
+                class: "text-center text-red-600",
+                "{e}"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        form {
+            class: "flex flex-col gap-5",
+            prevent_default: "onsubmit",
+            onsubmit: form_onsubmit,
+
+            div {
+                class: "flex flex-col",
+                label {
+                    r#for: "password",
+                    "New Password"
+                },
+                input {
+                    id: "password",
+                    r#type: "password",
+                    name: "password",
+                    class: "input-field",
+                    placeholder: "New Password",
+                    value: "{password.current()}",
+                    oninput: move |ev| {
+                        error.set(None);
+                        password.set(ev.value.clone());
+                    }
+                }
+            },
+
+            div {
+                class: "flex flex-col",
+                label {
+                    r#for: "password-confirmation",
+                    "Confirm Password"
+                },
+                input {
+                    id: "password-confirmation",
+                    r#type: "password",
+                    name: "password-confirmation",
+                    class: "input-field",
+                    placeholder: "Confirm Password",
+                    value: "{confirmation.current()}",
+                    oninput: move |ev| confirmation.set(ev.value.clone())
+                }
+            },
+
+            Error,
+
+            LoginLink {},
+
+            button {
+                class: "btn {submit_btn_style}",
+                r#type: "submit",
+                disabled: !can_submit,
+                "Reset Password"
+            }
+        }
+    })
+}
@@ -1,17 +1,59 @@
-use uchat_domain::ids::UserId;
+use uchat_domain::ids::{PostId, UserId};
 
+/// Pages under this path can be used without logging in.
+pub const ACCOUNT_ROOT: &str = "/account/";
 pub const ACCOUNT_REGISTER: &str = "/account/register";
 pub const ACCOUNT_LOGIN: &str = "/account/login";
+pub const ACCOUNT_CONFIRM_EMAIL: &str = "/account/confirm_email/:token";
+pub const ACCOUNT_FORGOT_PASSWORD: &str = "/account/forgot_password";
+pub const ACCOUNT_RESET_PASSWORD: &str = "/account/reset_password/:token";
 pub const HOME: &str = "/home";
 pub const HOME_BOOKMARKED: &str = "/home/bookmarked";
 pub const HOME_LIKED: &str = "/home/liked";
+pub const HOME_MENTIONS: &str = "/home/mentions";
+pub const MESSAGES: &str = "/messages";
+pub const MESSAGES_CONVERSATION: &str = "/messages/:user";
+pub const NOTIFICATIONS: &str = "/notifications";
 pub const POST_NEW_CHAT: &str = "/post/new_chat";
 pub const POST_NEW_IMAGE: &str = "/post/new_image";
 pub const POST_NEW_POLL: &str = "/post/new_poll";
+pub const POST_REVISIONS: &str = "/post/revisions/:id";
+pub const POST_THREAD: &str = "/post/:id";
+pub const POSTS_SCHEDULED: &str = "/posts/scheduled";
+pub const POSTS_SEARCH: &str = "/posts/search";
+pub const POSTS_TAGGED: &str = "/posts/tagged/:tag";
 pub const POSTS_TRENDING: &str = "/posts/trending";
 pub const PROFILE_EDIT: &str = "/profile/edit";
+pub const PROFILE_FOLLOWERS: &str = "/profile/followers/:user";
+pub const PROFILE_FOLLOWING: &str = "/profile/following/:user";
 pub const PROFILE_VIEW: &str = "/profile/view/:user";
+pub const USERS_BLOCKED: &str = "/users/blocked";
+pub const USERS_SEARCH: &str = "/users/search";
 
 pub fn profile_view(user_id: UserId) -> String {
     PROFILE_VIEW.replace(":user", &user_id.to_string())
 }
+
+pub fn profile_followers(user_id: UserId) -> String {
+    PROFILE_FOLLOWERS.replace(":user", &user_id.to_string())
+}
+
+pub fn profile_following(user_id: UserId) -> String {
+    PROFILE_FOLLOWING.replace(":user", &user_id.to_string())
+}
+
+pub fn conversation(user_id: UserId) -> String {
+    MESSAGES_CONVERSATION.replace(":user", &user_id.to_string())
+}
+
+pub fn post_thread(post_id: PostId) -> String {
+    POST_THREAD.replace(":id", &post_id.to_string())
+}
+
+pub fn post_revisions(post_id: PostId) -> String {
+    POST_REVISIONS.replace(":id", &post_id.to_string())
+}
+
+pub fn tagged_posts(tag: &str) -> String {
+    POSTS_TAGGED.replace(":tag", &uchat_domain::normalize_hashtag(tag))
+}
@@ -0,0 +1,180 @@
+#![allow(non_snake_case)]
+
+use chrono::{DateTime, Duration, Utc};
+use dioxus::prelude::*;
+use uchat_domain::ids::PostId;
+use uchat_endpoint::{post::PublicPost, Cursor, Page};
+
+use crate::{prelude::*, util};
+
+#[inline_props]
+fn ScheduledEntry(cx: Scope, post: PublicPost, posts: UseRef<Vec<PublicPost>>) -> Element {
+    let api_client = ApiClient::global();
+    let toaster = use_toaster(cx);
+
+    let post_id: PostId = post.id;
+    let new_time = use_state(cx, || Some(post.time_posted));
+
+    let reschedule_onclick = async_handler!(
+        &cx,
+        [api_client, toaster, posts, new_time],
+        move |_| async move {
+            use uchat_endpoint::post::{ReschedulePost, ReschedulePostOk};
+
+            let Some(time_posted) = *new_time.get() else {
+                return;
+            };
+
+            let request_data = ReschedulePost {
+                post_id,
+                time_posted,
+            };
+            match fetch_json!(<ReschedulePostOk>, api_client, request_data) {
+                Ok(res) => {
+                    posts.with_mut(|posts| {
+                        if let Some(post) = posts.iter_mut().find(|post| post.id == res.post_id) {
+                            post.time_posted = res.time_posted;
+                        }
+                        posts.sort_by_key(|post| post.time_posted);
+                    });
+                    toaster.write().success("Rescheduled", Duration::seconds(3));
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to reschedule post: {e}"),
+                    Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let cancel_onclick = async_handler!(&cx, [api_client, toaster, posts], move |_| async move {
+        use uchat_endpoint::post::{CancelScheduledPost, CancelScheduledPostOk};
+
+        if !util::confirm("Cancel this post? It will be deleted.") {
+            return;
+        }
+
+        let request_data = CancelScheduledPost { post_id };
+        match fetch_json!(<CancelScheduledPostOk>, api_client, request_data) {
+            Ok(res) => {
+                posts.with_mut(|posts| posts.retain(|post| post.id != res.post_id));
+                toaster
+                    .write()
+                    .success("Post canceled", Duration::seconds(3));
+            }
+            Err(e) => toaster
+                .write()
+                .error(format!("Failed to cancel post: {e}"), Duration::seconds(3)),
+        }
+    });
+
+    let text = super::inbox::message_preview(&post.content);
+    let can_reschedule =
+        matches!(*new_time.get(), Some(time) if time > Utc::now() && time != post.time_posted);
+    let reschedule_btn_style = maybe_class!("btn-disabled", !can_reschedule);
+
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col gap-2 py-3 border-b",
+            div { "{text}" },
+            DateTimeInput {
+                id: format!("time-posted-{}", post_id.to_string()),
+                label: "Publish at",
+                value: *new_time.get(),
+                on_change: move |time: Option<DateTime<Utc>>| new_time.set(time),
+            },
+            div {
+                class: "flex flex-row justify-end gap-2",
+                button {
+                    class: "btn",
+                    onclick: cancel_onclick,
+                    "Cancel post"
+                },
+                button {
+                    class: "btn {reschedule_btn_style}",
+                    disabled: !can_reschedule,
+                    onclick: reschedule_onclick,
+                    "Reschedule"
+                }
+            }
+        }
+    })
+}
+
+pub fn Scheduled(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let page_number = use_scroll_to_bottom(cx);
+
+    let posts = use_ref(cx, Vec::<PublicPost>::new);
+    let next_cursor = use_ref(cx, || None::<Cursor>);
+
+    let _fetch_posts = {
+        to_owned![api_client, toaster, posts, next_cursor];
+        use_future(cx, (page_number.get(),), |(page_number,)| async move {
+            use uchat_endpoint::post::{ListScheduledPosts, ListScheduledPostsOk};
+
+            let page = match page_number {
+                0 => {
+                    posts.write().clear();
+                    Page::default()
+                }
+                _ => match next_cursor.read().clone() {
+                    Some(cursor) => Page::after(cursor),
+                    None => return,
+                },
+            };
+
+            let request_data = ListScheduledPosts { page };
+            let response = fetch_json!(<ListScheduledPostsOk>, api_client, request_data);
+            match response {
+                Ok(res) => {
+                    posts.write().extend(res.posts);
+                    next_cursor.set(res.next_cursor);
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to retrieve scheduled posts: {e}"),
+                    Duration::seconds(3),
+                ),
+            }
+        })
+    };
+
+    let Posts = posts
+        .read()
+        .iter()
+        .map(|post| {
+            rsx! {
+                ScheduledEntry {
+                    key: "{post.id.to_string()}",
+                    post: post.clone(),
+                    posts: posts.clone(),
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let Empty = Posts.is_empty().then(|| {
+        rsx! {
+            div {
+                class: "text-center my-6",
+                "Nothing scheduled. Pick a time when writing a new post to publish it later."
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Scheduled",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        Empty,
+        Posts.into_iter()
+    })
+}
@@ -0,0 +1,139 @@
+#![allow(non_snake_case)]
+
+use std::collections::HashMap;
+
+use chrono::Duration;
+use dioxus::prelude::*;
+use uchat_domain::ids::PostId;
+use uchat_endpoint::post::SnippetFragment;
+
+use crate::{elements::PublicPostEntry, prelude::*};
+
+#[inline_props]
+fn Snippet(cx: Scope, fragments: Vec<SnippetFragment>) -> Element {
+    let Fragments = fragments.iter().map(|fragment| {
+        let text = &fragment.text;
+        if fragment.highlighted {
+            rsx! { mark { class: "bg-yellow-200", "{text}" } }
+        } else {
+            rsx! { span { "{text}" } }
+        }
+    });
+
+    cx.render(rsx! {
+        div {
+            class: "text-sm italic text-slate-600 px-3 pt-2",
+            Fragments
+        }
+    })
+}
+
+pub fn SearchPosts(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let post_manager = use_post_manager(cx);
+    let page_number = use_scroll_to_bottom(cx);
+
+    let input = use_state(cx, String::new);
+    let query = use_state(cx, String::new);
+    let snippets = use_ref(cx, HashMap::<PostId, Vec<SnippetFragment>>::new);
+
+    let _search_posts = {
+        to_owned![api_client, toaster, post_manager, snippets];
+        use_future(
+            cx,
+            (page_number.get(), query.get()),
+            |(page_number, query)| async move {
+                use uchat_endpoint::post::{SearchPosts, SearchPostsOk};
+
+                if query.is_empty() {
+                    post_manager.write().clear();
+                    return;
+                }
+                let page = match post_manager.write().next_page(page_number) {
+                    Some(page) => page,
+                    None => return,
+                };
+                if page_number == 0 {
+                    snippets.write().clear();
+                }
+
+                let request_data = SearchPosts { query, page };
+                let response = fetch_json!(<SearchPostsOk>, api_client, request_data);
+                match response {
+                    Ok(res) => {
+                        let mut post_manager = post_manager.write();
+                        let mut snippets = snippets.write();
+                        let posts = res.results.into_iter().map(|result| {
+                            snippets.insert(result.post.id, result.snippet);
+                            result.post
+                        });
+                        post_manager.populate(posts);
+                        post_manager.next_cursor = res.next_cursor;
+                    }
+                    Err(e) => toaster
+                        .write()
+                        .error(format!("Failed to search posts: {e}"), Duration::seconds(3)),
+                }
+            },
+        )
+    };
+
+    let Results = post_manager
+        .read()
+        .posts
+        .keys()
+        .map(|&id| {
+            let fragments = snippets.read().get(&id).cloned().unwrap_or_default();
+            rsx! {
+                div {
+                    Snippet { fragments: fragments },
+                    PublicPostEntry { post_id: id }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let NoResults = (!query.get().is_empty() && Results.is_empty()).then(|| {
+        rsx! {
+            div {
+                class: "text-center my-6",
+                "No posts matched your search."
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Search Posts",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        form {
+            class: "flex flex-row gap-3 my-3",
+            prevent_default: "onsubmit",
+            onsubmit: move |_| {
+                query.set(input.get().trim().to_string());
+                page_number.set(0);
+            },
+            input {
+                class: "input-field grow",
+                placeholder: "Search posts",
+                value: "{input.get()}",
+                oninput: move |ev| input.set(ev.value.clone()),
+            },
+            button {
+                class: "btn",
+                r#type: "submit",
+                "Search"
+            }
+        },
+        NoResults,
+        Results.into_iter()
+    })
+}
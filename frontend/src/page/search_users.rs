@@ -0,0 +1,84 @@
+#![allow(non_snake_case)]
+
+use chrono::Duration;
+use dioxus::prelude::*;
+
+use crate::{elements::UserEntry, prelude::*};
+
+/// Delay after the last keystroke before searching, in milliseconds.
+const TYPEAHEAD_DELAY_MS: u32 = 250;
+
+pub fn SearchUsers(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+
+    let query = use_state(cx, String::new);
+    let users = use_ref(cx, Vec::new);
+
+    let _search_users = {
+        to_owned![api_client, toaster, users];
+        // a new keystroke restarts this future, so only the final query is sent
+        use_future(cx, (query.get(),), |(query,)| async move {
+            use uchat_endpoint::user::{SearchUsers, SearchUsersOk};
+
+            let query = query.trim().to_string();
+            if query.is_empty() {
+                users.write().clear();
+                return;
+            }
+            gloo_timers::future::TimeoutFuture::new(TYPEAHEAD_DELAY_MS).await;
+
+            let request_data = SearchUsers { query };
+            let response = fetch_json!(<SearchUsersOk>, api_client, request_data);
+            match response {
+                Ok(res) => *users.write() = res.users,
+                Err(e) => toaster
+                    .write()
+                    .error(format!("Failed to search users: {e}"), Duration::seconds(3)),
+            }
+        })
+    };
+
+    let Users = users
+        .read()
+        .iter()
+        .map(|profile| {
+            rsx! {
+                UserEntry {
+                    key: "{profile.id.to_string()}",
+                    profile: profile.clone()
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let NoResults = (!query.get().trim().is_empty() && Users.is_empty()).then(|| {
+        rsx! {
+            div {
+                class: "text-center my-6",
+                "No users found."
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Find Users",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        input {
+            class: "input-field w-full my-3",
+            placeholder: "Search by handle or name",
+            value: "{query.get()}",
+            oninput: move |ev| query.set(ev.value.clone()),
+        },
+        NoResults,
+        Users.into_iter()
+    })
+}
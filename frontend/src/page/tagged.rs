@@ -0,0 +1,90 @@
+#![allow(non_snake_case)]
+
+use chrono::Duration;
+use dioxus::prelude::*;
+
+use crate::prelude::*;
+
+/// Posts using the hashtag given in the route.
+pub fn TaggedPosts(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let post_manager = use_post_manager(cx);
+    let page_number = use_scroll_to_bottom(cx);
+    let loaded_tag = use_ref(cx, || None::<String>);
+
+    let tag = use_route(cx).last_segment().map(|tag| tag.to_string());
+
+    let _fetch_posts = {
+        to_owned![api_client, toaster, post_manager, loaded_tag];
+        use_future(
+            cx,
+            (page_number.get(), &tag),
+            |(page_number, tag)| async move {
+                use uchat_endpoint::post::{TagTimeline, TagTimelineOk};
+
+                let Some(tag) = tag else {
+                    return;
+                };
+
+                // a different tag always starts from the first page
+                let page_number = match loaded_tag.read().as_ref() == Some(&tag) {
+                    true => page_number,
+                    false => 0,
+                };
+                let page = match post_manager.write().next_page(page_number) {
+                    Some(page) => page,
+                    None => return,
+                };
+                loaded_tag.set(Some(tag.clone()));
+
+                let request_data = TagTimeline { tag, page };
+                let response = fetch_json!(<TagTimelineOk>, api_client, request_data);
+                match response {
+                    Ok(res) => {
+                        let mut post_manager = post_manager.write();
+                        post_manager.populate(res.posts.into_iter());
+                        post_manager.next_cursor = res.next_cursor;
+                    }
+                    Err(e) => toaster.write().error(
+                        format!("Failed to retrieve posts: {e}"),
+                        Duration::seconds(3),
+                    ),
+                }
+            },
+        )
+    };
+
+    let Posts = {
+        let posts = post_manager.read().all_to_public();
+        if posts.is_empty() {
+            rsx! {
+                div {
+                    class: "text-center my-6",
+                    "No posts use this tag yet."
+                }
+            }
+        } else {
+            rsx! { posts.into_iter() }
+        }
+    };
+
+    let title = match &tag {
+        Some(tag) => format!("#{tag}"),
+        None => "Tag".to_string(),
+    };
+
+    cx.render(rsx! {
+        Appbar {
+            title: "{title}",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        Posts
+    })
+}
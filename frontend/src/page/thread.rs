@@ -0,0 +1,211 @@
+#![allow(non_snake_case)]
+
+use std::str::FromStr;
+
+use chrono::Duration;
+use dioxus::prelude::*;
+use uchat_domain::ids::PostId;
+use uchat_endpoint::user::MuteDuration;
+
+use crate::{elements::MuteButton, prelude::*};
+
+/// Indentation applied per level of reply nesting.
+const REPLY_INDENT_REM: u32 = 2;
+
+pub fn Thread(cx: Scope) -> Element {
+    let api_client = ApiClient::global();
+    let router = use_router(cx);
+    let toaster = use_toaster(cx);
+    let post_manager = use_post_manager(cx);
+
+    let post_id = use_route(cx)
+        .last_segment()
+        .and_then(|id| PostId::from_str(id).ok());
+
+    let ancestors = use_ref(cx, Vec::<PostId>::new);
+    let focus = use_ref(cx, || None::<PostId>);
+    let replies = use_ref(cx, Vec::<(PostId, u32)>::new);
+    let page_number = use_state(cx, || 0_usize);
+    let loaded_post = use_ref(cx, || None);
+    let muted = use_state(cx, || false);
+
+    use_effect(
+        cx,
+        (&post_id, page_number.get()),
+        |(post_id, page_number)| {
+            to_owned![
+                api_client,
+                toaster,
+                post_manager,
+                ancestors,
+                focus,
+                replies,
+                loaded_post,
+                muted
+            ];
+            async move {
+                use uchat_endpoint::post::{GetThread, GetThreadOk};
+
+                let Some(post_id) = post_id else {
+                    return;
+                };
+
+                // a different thread always starts from the first page
+                let page_number = match *loaded_post.read() == Some(post_id) {
+                    true => page_number,
+                    false => 0,
+                };
+                let page = match post_manager.write().next_page(page_number) {
+                    Some(page) => page,
+                    None => return,
+                };
+                loaded_post.set(Some(post_id));
+
+                let request_data = GetThread {
+                    post_id,
+                    max_depth: None,
+                    page,
+                };
+                let response = fetch_json!(<GetThreadOk>, api_client, request_data);
+                match response {
+                    Ok(res) => {
+                        if page_number == 0 {
+                            ancestors.set(res.ancestors.iter().map(|post| post.id).collect());
+                            focus.set(Some(res.post.id));
+                            replies.write().clear();
+                            muted.set(res.muted);
+                        }
+                        replies
+                            .write()
+                            .extend(res.replies.iter().map(|reply| (reply.post.id, reply.depth)));
+
+                        let mut post_manager = post_manager.write();
+                        post_manager.populate(res.ancestors.into_iter());
+                        post_manager.populate(std::iter::once(res.post));
+                        post_manager.populate(res.replies.into_iter().map(|reply| reply.post));
+                        post_manager.next_cursor = res.next_cursor;
+                    }
+                    Err(e) => toaster.write().error(
+                        format!("Failed to retrieve thread: {e}"),
+                        Duration::seconds(3),
+                    ),
+                }
+            }
+        },
+    );
+
+    let mute_onchange = move |duration: Option<MuteDuration>| {
+        let Some(post_id) = post_id else {
+            return;
+        };
+        to_owned![api_client, toaster, muted];
+        cx.spawn(async move {
+            use uchat_endpoint::user::{MuteThread, MuteThreadOk, UnmuteThread, UnmuteThreadOk};
+
+            let response = match duration {
+                Some(duration) => {
+                    let request_data = MuteThread { post_id, duration };
+                    fetch_json!(<MuteThreadOk>, api_client, request_data).map(|_| true)
+                }
+                None => {
+                    let request_data = UnmuteThread { post_id };
+                    fetch_json!(<UnmuteThreadOk>, api_client, request_data).map(|_| false)
+                }
+            };
+            match response {
+                Ok(is_muted) => {
+                    muted.set(is_muted);
+                    let message = match is_muted {
+                        true => "Replies to this thread are hidden from your feeds",
+                        false => "Thread unmuted",
+                    };
+                    toaster.write().success(message, Duration::seconds(3));
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to update mute status: {e}"),
+                    Duration::seconds(3),
+                ),
+            }
+        });
+    };
+
+    let is_loaded = |id: &PostId| post_manager.read().get(id).is_some();
+
+    let Ancestors = ancestors
+        .read()
+        .iter()
+        .filter(|id| is_loaded(id))
+        .map(|&id| {
+            rsx! {
+                div {
+                    key: "{id.to_string()}",
+                    class: "opacity-80",
+                    PublicPostEntry { post_id: id }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let focus_id = *focus.read();
+    let Focus = focus_id.filter(|id| is_loaded(id)).map(|id| {
+        rsx! {
+            div {
+                key: "{id.to_string()}",
+                class: "border-l-4 border-slate-400 pl-2",
+                PublicPostEntry { post_id: id }
+            }
+        }
+    });
+
+    let Replies = replies
+        .read()
+        .iter()
+        .filter(|(id, _)| is_loaded(id))
+        .map(|&(id, depth)| {
+            let indent = depth * REPLY_INDENT_REM;
+            rsx! {
+                div {
+                    key: "{id.to_string()}",
+                    style: "margin-left: {indent}rem",
+                    PublicPostEntry { post_id: id }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let LoadMore = post_manager.read().next_cursor.is_some().then(|| {
+        rsx! {
+            button {
+                class: "btn self-center",
+                onclick: move |_| page_number.modify(|n| n + 1),
+                "Load more replies"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        Appbar {
+            title: "Thread",
+            AppbarImgButton {
+                click_handler: move |_| router.pop_route(),
+                img: "/static/icons/icon-back.svg",
+                label: "Back",
+                title: "Go to the previous page"
+            }
+        },
+        div {
+            class: "flex flex-col",
+            Ancestors.into_iter(),
+            Focus,
+            div {
+                class: "flex flex-row justify-end my-2",
+                MuteButton {
+                    muted: *muted.get(),
+                    on_change: mute_onchange
+                }
+            },
+            Replies.into_iter(),
+            LoadMore
+        }
+    })
+}
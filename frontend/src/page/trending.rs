@@ -3,6 +3,8 @@
 use chrono::Duration;
 use dioxus::prelude::*;
 
+use uchat_endpoint::post::{TagCount, TrendingWindow};
+
 use crate::{elements::PublicPostEntry, prelude::*};
 
 pub fn Trending(cx: Scope) -> Element {
@@ -10,26 +12,98 @@ pub fn Trending(cx: Scope) -> Element {
     let router = use_router(cx);
     let toaster = use_toaster(cx);
     let post_manager = use_post_manager(cx);
+    let page_number = use_scroll_to_bottom(cx);
+    let window = use_state(cx, TrendingWindow::default);
+    let trending_tags = use_ref(cx, Vec::<TagCount>::new);
 
     let _fetch_trending_posts = {
         to_owned![api_client, toaster, post_manager];
-        use_future(cx, (), |_| async move {
-            use uchat_endpoint::post::{TrendingPosts, TrendingPostsOk};
-            toaster
-                .write()
-                .info("Retrieving trending posts", Duration::seconds(3));
-            post_manager.write().clear();
-            let response = fetch_json!(<TrendingPostsOk>, api_client, TrendingPosts);
+        use_future(
+            cx,
+            (page_number.get(), window.get()),
+            |(page_number, window)| async move {
+                use uchat_endpoint::post::{TrendingPosts, TrendingPostsOk};
+                let page = match post_manager.write().next_page(page_number) {
+                    Some(page) => page,
+                    None => return,
+                };
+                if page_number == 0 {
+                    toaster
+                        .write()
+                        .info("Retrieving trending posts", Duration::seconds(3));
+                }
+                let request_data = TrendingPosts { window, page };
+                let response = fetch_json!(<TrendingPostsOk>, api_client, request_data);
+                match response {
+                    Ok(res) => {
+                        let mut post_manager = post_manager.write();
+                        post_manager.populate(res.posts.into_iter());
+                        post_manager.next_cursor = res.next_cursor;
+                    }
+                    Err(e) => toaster.write().error(
+                        format!("Failed to retrieve posts: {e}"),
+                        Duration::seconds(3),
+                    ),
+                }
+            },
+        )
+    };
+
+    let _fetch_trending_tags = {
+        to_owned![api_client, toaster, trending_tags];
+        use_future(cx, (window.get(),), |(window,)| async move {
+            use uchat_endpoint::post::{TrendingTags, TrendingTagsOk};
+
+            let request_data = TrendingTags { window };
+            let response = fetch_json!(<TrendingTagsOk>, api_client, request_data);
             match response {
-                Ok(res) => post_manager.write().populate(res.posts.into_iter()),
+                Ok(res) => trending_tags.set(res.tags),
                 Err(e) => toaster.write().error(
-                    format!("Failed to retrieve posts: {e}"),
+                    format!("Failed to retrieve trending tags: {e}"),
                     Duration::seconds(3),
                 ),
             }
         })
     };
 
+    let WindowSelector = [
+        (TrendingWindow::Hour, "Past hour"),
+        (TrendingWindow::Day, "Past day"),
+        (TrendingWindow::Week, "Past week"),
+    ]
+    .into_iter()
+    .map(|(option, label)| {
+        let selected = maybe_class!("ring-2 ring-slate-400", *window.get() == option);
+        rsx! {
+            button {
+                class: "btn {selected}",
+                onclick: move |_| {
+                    window.set(option);
+                    page_number.set(0);
+                },
+                "{label}"
+            }
+        }
+    });
+
+    let Tags = trending_tags
+        .read()
+        .clone()
+        .into_iter()
+        .map(|TagCount { tag, posts }| {
+            let route = page::tagged_posts(tag);
+            rsx! {
+                button {
+                    key: "{tag}",
+                    class: "link",
+                    title: "Used by {posts} posts",
+                    onclick: move |_| router.navigate_to(&route),
+                    "#{tag}"
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
     let TrendingPosts = post_manager
         .read()
         .posts
@@ -46,6 +120,12 @@ pub fn Trending(cx: Scope) -> Element {
     cx.render(rsx! {
         Appbar  {
            title: "Trending Posts",
+           AppbarImgButton {
+               click_handler: move |_| router.navigate_to(page::POSTS_SEARCH),
+               img: "/static/icons/icon-search.svg",
+               label: "Search",
+               title: "Search posts"
+           },
            AppbarImgButton {
                click_handler: move |_| router.pop_route(),
                img: "/static/icons/icon-back.svg",
@@ -53,6 +133,14 @@ pub fn Trending(cx: Scope) -> Element {
                title: "Go to the previous page"
            }
         },
+        div {
+            class: "flex flex-row justify-center gap-3 my-3",
+            WindowSelector
+        },
+        div {
+            class: "flex flex-row flex-wrap justify-center gap-3 mb-3",
+            Tags.into_iter()
+        },
         TrendingPosts.into_iter()
     })
 }
@@ -2,9 +2,10 @@
 
 use std::str::FromStr;
 
-use crate::prelude::*;
+use crate::{elements::MuteButton, prelude::*};
 use dioxus::prelude::*;
 use uchat_domain::ids::UserId;
+use uchat_endpoint::user::MuteDuration;
 
 pub fn ViewProfile(cx: Scope) -> Element {
     let api_client = ApiClient::global();
@@ -20,26 +21,48 @@ pub fn ViewProfile(cx: Scope) -> Element {
         .and_then(|id| UserId::from_str(id).ok())
         .unwrap_or_default();
 
-    use_effect(cx, (&user_id,), |(user_id,)| {
-        to_owned![api_client, post_manager, profile, toaster];
-        async move {
-            use uchat_endpoint::user::{ViewProfile, ViewProfileOk};
+    let page_number = use_scroll_to_bottom(cx);
+    let loaded_user = use_ref(cx, || None);
 
-            let request_data = ViewProfile { for_user: user_id };
-            post_manager.write().clear();
-            let response = fetch_json!(<ViewProfileOk>,  api_client, request_data);
-            match response {
-                Ok(res) => {
-                    profile.with_mut(|profile| *profile = Some(res.profile));
-                    post_manager.write().populate(res.posts.into_iter());
+    use_effect(
+        cx,
+        (&user_id, page_number.get()),
+        |(user_id, page_number)| {
+            to_owned![api_client, post_manager, profile, toaster, loaded_user];
+            async move {
+                use uchat_endpoint::user::{ViewProfile, ViewProfileOk};
+
+                // a different profile always starts from the first page
+                let page_number = match *loaded_user.read() == Some(user_id) {
+                    true => page_number,
+                    false => 0,
+                };
+                let page = match post_manager.write().next_page(page_number) {
+                    Some(page) => page,
+                    None => return,
+                };
+                loaded_user.set(Some(user_id));
+
+                let request_data = ViewProfile {
+                    for_user: user_id,
+                    page,
+                };
+                let response = fetch_json!(<ViewProfileOk>,  api_client, request_data);
+                match response {
+                    Ok(res) => {
+                        profile.with_mut(|profile| *profile = Some(res.profile));
+                        let mut post_manager = post_manager.write();
+                        post_manager.populate(res.posts.into_iter());
+                        post_manager.next_cursor = res.next_cursor;
+                    }
+                    Err(e) => toaster.write().error(
+                        format!("Failed to retrieve posts: {e}",),
+                        chrono::Duration::seconds(3),
+                    ),
                 }
-                Err(e) => toaster.write().error(
-                    format!("Failed to retrieve posts: {e}",),
-                    chrono::Duration::seconds(3),
-                ),
             }
-        }
-    });
+        },
+    );
 
     let follow_onclick = async_handler!(&cx, [api_client, toaster, profile], move |_| async move {
         use uchat_endpoint::user::{FollowAction, FollowUser, FollowUserOk};
@@ -59,7 +82,13 @@ pub fn ViewProfile(cx: Scope) -> Element {
         match fetch_json!(<FollowUserOk>, api_client, request_data) {
             Ok(res) => {
                 profile.with_mut(|profile| {
-                    profile.as_mut().map(|p| p.am_following = res.status.into());
+                    if let Some(p) = profile.as_mut() {
+                        let am_following: bool = res.status.into();
+                        if am_following != p.am_following {
+                            p.follower_count += if am_following { 1 } else { -1 };
+                        }
+                        p.am_following = am_following;
+                    }
                 });
             }
             Err(e) => toaster.write().error(
@@ -69,6 +98,89 @@ pub fn ViewProfile(cx: Scope) -> Element {
         }
     });
 
+    let block_onclick = async_handler!(
+        &cx,
+        [api_client, toaster, profile, post_manager],
+        move |_| async move {
+            use uchat_endpoint::user::{BlockUser, BlockUserOk, UnblockUser, UnblockUserOk};
+
+            let am_blocking = match profile.read().as_ref() {
+                Some(profile) => profile.am_blocking,
+                None => false,
+            };
+
+            let response = match am_blocking {
+                true => {
+                    let request_data = UnblockUser { user_id };
+                    fetch_json!(<UnblockUserOk>, api_client, request_data).map(|_| false)
+                }
+                false => {
+                    if !crate::util::confirm("Block this user? Follows between you are removed.") {
+                        return;
+                    }
+                    let request_data = BlockUser { user_id };
+                    fetch_json!(<BlockUserOk>, api_client, request_data).map(|_| true)
+                }
+            };
+            match response {
+                Ok(am_blocking) => {
+                    profile.with_mut(|profile| {
+                        if let Some(p) = profile.as_mut() {
+                            if am_blocking {
+                                if p.am_following {
+                                    p.follower_count -= 1;
+                                }
+                                if p.follows_me {
+                                    p.following_count -= 1;
+                                }
+                                p.am_following = false;
+                                p.follows_me = false;
+                            }
+                            p.am_blocking = am_blocking;
+                        }
+                    });
+                    // posts of blocked users are no longer shown
+                    if am_blocking {
+                        post_manager.write().clear();
+                    }
+                }
+                Err(e) => toaster.write().error(
+                    format!("Failed to update block status: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        }
+    );
+
+    let mute_onchange = move |duration: Option<MuteDuration>| {
+        to_owned![api_client, toaster, profile];
+        cx.spawn(async move {
+            use uchat_endpoint::user::{MuteUser, MuteUserOk, UnmuteUser, UnmuteUserOk};
+
+            let response = match duration {
+                Some(duration) => {
+                    let request_data = MuteUser { user_id, duration };
+                    fetch_json!(<MuteUserOk>, api_client, request_data).map(|_| true)
+                }
+                None => {
+                    let request_data = UnmuteUser { user_id };
+                    fetch_json!(<UnmuteUserOk>, api_client, request_data).map(|_| false)
+                }
+            };
+            match response {
+                Ok(am_muting) => profile.with_mut(|profile| {
+                    if let Some(p) = profile.as_mut() {
+                        p.am_muting = am_muting;
+                    }
+                }),
+                Err(e) => toaster.write().error(
+                    format!("Failed to update mute status: {e}"),
+                    chrono::Duration::seconds(3),
+                ),
+            }
+        });
+    };
+
     let ProfileSection = {
         match profile.with(|profile| profile.clone()) {
             Some(profile) => {
@@ -80,20 +192,56 @@ pub fn ViewProfile(cx: Scope) -> Element {
                     .profile_image
                     .map(|url| url.to_string())
                     .unwrap_or_else(|| "".to_string());
+                let post_count = profile.post_count;
+                let follower_count = profile.follower_count;
+                let following_count = profile.following_count;
+                let FollowsMe = profile.follows_me.then(|| {
+                    rsx! {
+                        div {
+                            class: "text-center text-sm font-light",
+                            "Follows you"
+                        }
+                    }
+                });
                 let follow_button_text = match profile.am_following {
                     true => "Unfollow",
                     false => "Follow",
                 };
+                let block_button_text = match profile.am_blocking {
+                    true => "Unblock",
+                    false => "Block",
+                };
 
                 let FollowButton = local_profile.read().user_id.map(|id| {
                     if id == profile.id {
                         None
                     } else {
                         cx.render(rsx! {
-                            button {
-                                class: "btn",
-                                onclick: follow_onclick,
-                                "{follow_button_text}"
+                            div {
+                                class: "flex flex-row justify-center gap-3",
+                                (!profile.am_blocking).then(|| rsx! {
+                                    button {
+                                        class: "btn",
+                                        onclick: follow_onclick,
+                                        "{follow_button_text}"
+                                    },
+                                    button {
+                                        class: "btn",
+                                        onclick: move |_| {
+                                            router.navigate_to(&page::conversation(profile.id))
+                                        },
+                                        "Message"
+                                    }
+                                }),
+                                MuteButton {
+                                    muted: profile.am_muting,
+                                    on_change: mute_onchange
+                                },
+                                button {
+                                    class: "btn",
+                                    onclick: block_onclick,
+                                    "{block_button_text}"
+                                }
                             }
                         })
                     }
@@ -111,6 +259,25 @@ pub fn ViewProfile(cx: Scope) -> Element {
                         },
                         div { "Handle: {profile.handle}"},
                         div { "Name: {display_name}"},
+                        div {
+                            class: "flex flex-row justify-center gap-4",
+                            span { "{post_count} posts" },
+                            a {
+                                class: "link",
+                                onclick: move |_| {
+                                    router.navigate_to(&page::profile_followers(profile.id))
+                                },
+                                "{follower_count} followers"
+                            },
+                            a {
+                                class: "link",
+                                onclick: move |_| {
+                                    router.navigate_to(&page::profile_following(profile.id))
+                                },
+                                "{following_count} following"
+                            }
+                        },
+                        FollowsMe,
                         FollowButton,
                     }
                 }
@@ -1,6 +1,8 @@
 pub mod api_client;
 pub mod cookie;
+pub mod scroll;
 pub use api_client::ApiClient;
+pub use scroll::use_scroll_to_bottom;
 
 use serde::Deserialize;
 use wasm_bindgen::JsCast;
@@ -109,6 +111,11 @@ pub fn history() -> History {
     window().history().expect("missing History object")
 }
 
+/// Asks the user to confirm an action with a native dialog.
+pub fn confirm(message: &str) -> bool {
+    window().confirm_with_message(message).unwrap_or(false)
+}
+
 pub fn load_history_state<T>() -> Option<T>
 where
     T: serde::de::DeserializeOwned + std::fmt::Debug,
@@ -62,7 +62,22 @@ where
             .send()
             .await
     };
-    make_request(api_request, timeout).await
+    let response = make_request(api_request, timeout).await?;
+    renew_session(&response);
+    Ok(response)
+}
+
+/// Keeps the session cookies in step with sessions which the server renewed.
+#[cfg(target_arch = "wasm32")]
+fn renew_session(response: &reqwest::Response) {
+    let expires = response
+        .headers()
+        .get(uchat_cookie::SESSION_EXPIRES_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| chrono::DateTime::parse_from_rfc3339(value).ok());
+    if let Some(expires) = expires {
+        super::cookie::renew_session(expires.with_timezone(&chrono::Utc));
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -3,7 +3,7 @@
 use std::str::FromStr;
 
 use chrono::{DateTime, Duration, Utc};
-use uchat_domain::ids::SessionId;
+use uchat_domain::ids::{DeviceId, SessionId};
 
 use super::document;
 
@@ -32,6 +32,40 @@ pub fn set_session(signature: String, id: SessionId, expires: DateTime<Utc>) {
     document().set_cookie(&cookie).unwrap();
 }
 
+/// Identifies this browser to the server, generating an ID the first time it is needed.
+pub fn device_id() -> DeviceId {
+    let cookies = document().cookie().unwrap();
+    if let Some(id) = uchat_cookie::get_from_str(&cookies, uchat_cookie::DEVICE_ID)
+        .and_then(|id| DeviceId::from_str(id).ok())
+    {
+        return id;
+    }
+
+    let id = DeviceId::new();
+    let cookie = format_cookie(
+        format_kv(uchat_cookie::DEVICE_ID, id.to_string()),
+        Utc::now() + Duration::days(365 * 10),
+    );
+    document().set_cookie(&cookie).unwrap();
+    id
+}
+
+/// Extends the session cookies to `expires`, after the server renewed the session.
+pub fn renew_session(expires: DateTime<Utc>) {
+    let cookies = document().cookie().unwrap();
+    let id = uchat_cookie::get_from_str(&cookies, uchat_cookie::SESSION_ID);
+    let signature = uchat_cookie::get_from_str(&cookies, uchat_cookie::SESSION_SIGNATURE);
+    if let (Some(id), Some(signature)) = (id, signature) {
+        for (key, value) in [
+            (uchat_cookie::SESSION_ID, id),
+            (uchat_cookie::SESSION_SIGNATURE, signature),
+        ] {
+            let cookie = format_cookie(format_kv(key, value), expires);
+            document().set_cookie(&cookie).unwrap();
+        }
+    }
+}
+
 #[cfg(not(debug_assertions))]
 fn standard_options() -> &'static str {
     "SameSite=Strict; Path=/; Secure"
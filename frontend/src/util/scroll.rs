@@ -0,0 +1,64 @@
+use std::cell::Cell;
+
+use dioxus::prelude::*;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Distance from the bottom of the page, in pixels, at which the bottom is considered reached.
+const BOTTOM_THRESHOLD: f64 = 200.0;
+
+/// Counts how many times the window has been scrolled to the bottom of the page.
+///
+/// Paginated feeds use this as their page number: `0` is the initial load and every increment
+/// requests the next page.
+pub fn use_scroll_to_bottom(cx: &ScopeState) -> &UseState<usize> {
+    let page_number = use_state(cx, || 0);
+    cx.use_hook(|| ScrollListener::new(page_number.clone()));
+    page_number
+}
+
+struct ScrollListener {
+    callback: Closure<dyn FnMut()>,
+}
+
+impl ScrollListener {
+    fn new(page_number: UseState<usize>) -> Self {
+        let at_bottom = Cell::new(false);
+        let callback = Closure::<dyn FnMut()>::new(move || {
+            let reached = is_at_bottom();
+            // only count the moment the bottom is reached, not every scroll event after it
+            if reached && !at_bottom.get() {
+                page_number.modify(|n| n + 1);
+            }
+            at_bottom.set(reached);
+        });
+
+        super::window()
+            .add_event_listener_with_callback("scroll", callback.as_ref().unchecked_ref())
+            .expect("failed to add scroll listener");
+
+        Self { callback }
+    }
+}
+
+impl Drop for ScrollListener {
+    fn drop(&mut self) {
+        let _ = super::window()
+            .remove_event_listener_with_callback("scroll", self.callback.as_ref().unchecked_ref());
+    }
+}
+
+fn is_at_bottom() -> bool {
+    let window = super::window();
+    let height = window
+        .inner_height()
+        .ok()
+        .and_then(|height| height.as_f64())
+        .unwrap_or_default();
+    let scrolled = window.scroll_y().unwrap_or_default();
+    let page_height = super::document()
+        .document_element()
+        .map(|element| element.scroll_height() as f64)
+        .unwrap_or_default();
+
+    height + scrolled >= page_height - BOTTOM_THRESHOLD
+}
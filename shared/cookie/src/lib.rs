@@ -1,5 +1,11 @@
 pub const SESSION_ID: &str = "session_id";
 pub const SESSION_SIGNATURE: &str = "session_signature";
+/// Identifies the browser across logins, so sessions can be told apart per device.
+pub const DEVICE_ID: &str = "device_id";
+
+/// Response header carrying the new expiry (RFC 3339) of a session which was renewed, so the
+/// client can extend its cookies to match.
+pub const SESSION_EXPIRES_HEADER: &str = "x-session-expires";
 
 pub fn get_from_str<'a>(cookies: &'a str, key: &str) -> Option<&'a str> {
     cookies
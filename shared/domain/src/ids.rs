@@ -65,6 +65,7 @@ pub enum IdError {
 
 new_id!(UserId);
 new_id!(SessionId);
+new_id!(DeviceId);
 new_id!(PostId);
 new_id!(ImageId);
 new_id!(PollChoiceId);
@@ -25,6 +25,19 @@ pub struct Message(String);
 
 impl Message {
     pub const MAX_CHARS: usize = 100;
+
+    /// Handles mentioned with `@handle`, without duplicates, in order of first appearance.
+    pub fn mentions(&self) -> Vec<&str> {
+        let mut handles = vec![];
+        for fragment in split_message(self.as_ref()) {
+            if let MessageFragment::Mention(handle) = fragment {
+                if !handles.contains(&handle) {
+                    handles.push(handle);
+                }
+            }
+        }
+        handles
+    }
 }
 
 impl UserFacingError for MessageError {
@@ -36,6 +49,92 @@ impl UserFacingError for MessageError {
     }
 }
 
+/// Part of a message, with `@handle` mentions and `#tag` hashtags separated from the
+/// surrounding text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageFragment<'a> {
+    Text(&'a str),
+    /// The mentioned handle, without the leading `@`.
+    Mention(&'a str),
+    /// The tag as written, without the leading `#`.
+    Hashtag(&'a str),
+}
+
+fn is_handle_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+fn is_hashtag_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The longest prefix of `text` made up of characters matching `pred`.
+fn take_while(text: &str, pred: fn(char) -> bool) -> &str {
+    let end = text.find(|c: char| !pred(c)).unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Splits `text` on `@handle` mentions and `#tag` hashtags.
+///
+/// Neither may directly follow a handle character, so email addresses and the like are left as
+/// text. Trailing `.` and `-` are treated as punctuation rather than part of a handle.
+pub fn split_message(text: &str) -> Vec<MessageFragment<'_>> {
+    let mut fragments = vec![];
+    let mut text_start = 0;
+    let mut search_from = 0;
+
+    while let Some(offset) = text[search_from..].find(['@', '#']) {
+        let at = search_from + offset;
+        let token_start = at + 1;
+        search_from = token_start;
+
+        let follows_handle = text[..at].chars().next_back().map_or(false, is_handle_char);
+        let is_mention = text[at..].starts_with('@');
+        let rest = &text[token_start..];
+        let token = match is_mention {
+            true => take_while(rest, is_handle_char).trim_end_matches(['.', '-']),
+            false => take_while(rest, is_hashtag_char),
+        };
+        if follows_handle || token.is_empty() {
+            continue;
+        }
+
+        if text_start < at {
+            fragments.push(MessageFragment::Text(&text[text_start..at]));
+        }
+        fragments.push(match is_mention {
+            true => MessageFragment::Mention(token),
+            false => MessageFragment::Hashtag(token),
+        });
+        text_start = token_start + token.len();
+        search_from = text_start;
+    }
+
+    if text_start < text.len() {
+        fragments.push(MessageFragment::Text(&text[text_start..]));
+    }
+    fragments
+}
+
+/// Normalized form of a hashtag, so that `#Rust` and `#rust` are the same tag.
+pub fn normalize_hashtag(tag: &str) -> String {
+    tag.to_ascii_lowercase()
+}
+
+/// Normalized hashtags in `text`, without duplicates, in order of first appearance.
+pub fn hashtags(text: &str) -> Vec<String> {
+    let mut tags = vec![];
+    for fragment in split_message(text) {
+        if let MessageFragment::Hashtag(tag) = fragment {
+            let tag = normalize_hashtag(tag);
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    tags
+}
+
 #[nutype(validate(present, max_len = 60))]
 #[derive(AsRef, Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Caption(String);
@@ -86,3 +185,47 @@ impl UserFacingError for PollChoiceDescriptionError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mentions_end_at_punctuation() {
+        use MessageFragment::*;
+
+        assert_eq!(
+            split_message("hi @alice, and @bob."),
+            vec![
+                Text("hi "),
+                Mention("alice"),
+                Text(", and "),
+                Mention("bob"),
+                Text(".")
+            ]
+        );
+        assert_eq!(
+            split_message("@carol! @dave-"),
+            vec![Mention("carol"), Text("! "), Mention("dave"), Text("-")]
+        );
+    }
+
+    #[test]
+    fn mentions_skip_repeated_at_signs_and_email_addresses() {
+        use MessageFragment::*;
+
+        assert_eq!(split_message("@@alice"), vec![Text("@"), Mention("alice")]);
+        assert_eq!(split_message("@ @"), vec![Text("@ @")]);
+        assert_eq!(split_message("a@b"), vec![Text("a@b")]);
+        assert_eq!(
+            split_message("mail alice@example.com"),
+            vec![Text("mail alice@example.com")]
+        );
+    }
+
+    #[test]
+    fn mentions_are_listed_once() {
+        let message = Message::new("@alice @bob @alice, @bob").unwrap();
+        assert_eq!(message.mentions(), vec!["alice", "bob"]);
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+pub mod notification;
 pub mod post;
 pub mod user;
 
@@ -49,22 +50,104 @@ pub mod app_url {
 // public routes
 route!("/account/create" => user::CreateUser);
 route!("/account/login" => user::Login);
+route!("/account/login/complete" => user::CompleteLogin);
+route!("/account/email/confirm" => user::ConfirmEmail);
+route!("/account/password/forgot" => user::RequestPasswordReset);
+route!("/account/password/reset" => user::ResetPassword);
 // route!("/profile/view" => user::ViewProfile);
 
 // authorized routes
 route!("/post/new" => post::NewPost);
+route!("/post/delete" => post::DeletePost);
+route!("/post/edit" => post::EditPost);
+route!("/post/revisions" => post::GetPostRevisions);
+route!("/post/reschedule" => post::ReschedulePost);
+route!("/post/cancel" => post::CancelScheduledPost);
 route!("/post/react" => post::React);
+route!("/post/react/emoji" => post::EmojiReact);
+route!("/post/reactions/emojis" => post::GetReactionEmojis);
 route!("/post/bookmark" => post::Bookmark);
 route!("/post/boost" => post::Boost);
 route!("/post/vote" => post::Vote);
+route!("/post/thread" => post::GetThread);
 route!("/posts/trending" => post::TrendingPosts);
 route!("/posts/home" => post::HomePosts);
 route!("/posts/liked" => post::LikedPosts);
 route!("/posts/bookmarked" => post::BookmarkedPosts);
+route!("/posts/mentions" => post::MentionedPosts);
+route!("/posts/tag" => post::TagTimeline);
+route!("/tags/trending" => post::TrendingTags);
+route!("/posts/search" => post::SearchPosts);
+route!("/posts/scheduled" => post::ListScheduledPosts);
+route!("/messages/conversations" => post::ListConversations);
+route!("/messages/conversation" => post::GetConversation);
+route!("/account/email/resend" => user::ResendEmailConfirmation);
+route!("/account/2fa/enable" => user::EnableTwoFactor);
+route!("/account/2fa/confirm" => user::ConfirmTwoFactor);
+route!("/account/2fa/disable" => user::DisableTwoFactor);
+route!("/account/logout" => user::Logout);
+route!("/account/sessions" => user::ListSessions);
+route!("/account/sessions/revoke" => user::RevokeSession);
+route!("/account/sessions/revoke_others" => user::RevokeAllOtherSessions);
 route!("/profile/me" => user::GetMyProfile);
 route!("/profile/update" => user::UpdateProfile);
 route!("/profile/view" => user::ViewProfile);
 route!("/user/follow" => user::FollowUser);
+route!("/user/followers" => user::ListFollowers);
+route!("/user/following" => user::ListFollowing);
+route!("/user/block" => user::BlockUser);
+route!("/user/unblock" => user::UnblockUser);
+route!("/users/blocked" => user::ListBlocked);
+route!("/user/mute" => user::MuteUser);
+route!("/user/unmute" => user::UnmuteUser);
+route!("/thread/mute" => user::MuteThread);
+route!("/thread/unmute" => user::UnmuteThread);
+route!("/users/search" => user::SearchUsers);
+route!("/notifications/list" => notification::ListNotifications);
+route!("/notifications/read" => notification::MarkNotificationsRead);
+route!("/notifications/unread" => notification::UnreadCount);
+
+/// Opaque position within a paginated listing.
+///
+/// Cursors are issued by the server and should be sent back unchanged to fetch the next page.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Cursor(String);
+
+impl Cursor {
+    pub fn new<T: Into<String>>(raw: T) -> Self {
+        Self(raw.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Page {
+    pub cursor: Option<Cursor>,
+    pub size: Option<u32>,
+}
+
+impl Page {
+    pub const DEFAULT_SIZE: u32 = 30;
+    pub const MAX_SIZE: u32 = 100;
+
+    pub fn after(cursor: Cursor) -> Self {
+        Self {
+            cursor: Some(cursor),
+            size: None,
+        }
+    }
+
+    /// Number of items to return, clamped to `1..=MAX_SIZE`.
+    pub fn size(&self) -> i64 {
+        self.size
+            .unwrap_or(Self::DEFAULT_SIZE)
+            .clamp(1, Self::MAX_SIZE)
+            .into()
+    }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Update<T> {
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Cursor, Page};
+
+use super::Notification;
+
+/// Lists the requesting user's notifications, most recent first.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListNotifications {
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListNotificationsOk {
+    pub notifications: Vec<Notification>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Marks notifications received up to `until` as read, so later ones stay unread.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MarkNotificationsRead {
+    pub until: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MarkNotificationsReadOk {
+    pub unread: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnreadCount;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnreadCountOk {
+    pub unread: i64,
+}
@@ -0,0 +1,5 @@
+mod endpoint;
+mod types;
+
+pub use endpoint::*;
+pub use types::*;
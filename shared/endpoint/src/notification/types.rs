@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uchat_domain::ids::PostId;
+
+use crate::user::PublicUserProfile;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    Follow,
+    Like,
+    Boost,
+    Reply,
+    Vote,
+    DirectMessage,
+}
+
+impl NotificationKind {
+    /// Whether notifications of this kind about the same post are combined, such as
+    /// "5 people liked your post". Replies and direct messages are always listed separately.
+    pub fn is_grouped(&self) -> bool {
+        !matches!(self, Self::Reply | Self::DirectMessage)
+    }
+}
+
+/// One or more notifications of the same kind about the same post.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    /// The post that was liked, boosted or voted on, or the reply or message that was sent.
+    pub post_id: Option<PostId>,
+    /// The most recent users who caused the notification.
+    pub actors: Vec<PublicUserProfile>,
+    /// Number of distinct users who caused the notification, which may exceed `actors`.
+    pub actor_count: i64,
+    pub latest: DateTime<Utc>,
+    pub unread: bool,
+}
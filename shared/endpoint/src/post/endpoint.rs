@@ -1,8 +1,15 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use uchat_domain::ids::{PollChoiceId, PostId};
+use uchat_domain::ids::{PollChoiceId, PostId, UserId};
+
+use crate::{user::PublicUserProfile, Cursor, Page};
 
 use super::{
-    BookmarkAction, BoostAction, Content, LikeStatus, NewPostOptions, PublicPost, VoteCast,
+    BookmarkAction, BoostAction, Content, ConversationPreview, EmojiCount, LikeStatus,
+    NewPostOptions, PostRevision, PostSearchResult, PublicPost, TagCount, ThreadReply,
+    TrendingWindow, VoteCast,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -17,11 +24,83 @@ pub struct NewPostOk {
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct TrendingPosts;
+pub struct DeletePost {
+    pub post_id: PostId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DeletePostOk {
+    pub post_id: PostId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EditPost {
+    pub post_id: PostId,
+    pub content: Content,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EditPostOk {
+    pub post: PublicPost,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetPostRevisions {
+    pub post_id: PostId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetPostRevisionsOk {
+    pub post: PublicPost,
+    /// Previous versions of the post, newest first.
+    pub revisions: Vec<PostRevision>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListScheduledPosts {
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListScheduledPostsOk {
+    /// Scheduled posts, soonest first.
+    pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ReschedulePost {
+    pub post_id: PostId,
+    pub time_posted: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ReschedulePostOk {
+    pub post_id: PostId,
+    pub time_posted: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CancelScheduledPost {
+    pub post_id: PostId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CancelScheduledPostOk {
+    pub post_id: PostId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TrendingPosts {
+    #[serde(default)]
+    pub window: TrendingWindow,
+    pub page: Page,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct TrendingPostsOk {
     pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -48,6 +127,28 @@ pub struct ReactOk {
     pub dislikes: i64,
 }
 
+/// Reacts to a post with an emoji, or removes the reaction when `emoji` is `None`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EmojiReact {
+    pub post_id: PostId,
+    pub emoji: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EmojiReactOk {
+    pub my_emoji: Option<String>,
+    pub emoji_reactions: Vec<EmojiCount>,
+}
+
+/// Lists the emoji which posts can be reacted to with.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetReactionEmojis;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetReactionEmojisOk {
+    pub emojis: Vec<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Boost {
     pub post_id: PostId,
@@ -59,37 +160,141 @@ pub struct BoostOk {
     pub status: BoostAction,
 }
 
+/// Replaces the viewer's votes on a poll. An empty set of choices retracts the vote.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Vote {
     pub post_id: PostId,
-    pub choice_id: PollChoiceId,
+    pub choices: BTreeSet<PollChoiceId>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct VoteOk {
     pub cast: VoteCast,
+    /// Choices the viewer voted for after this request.
+    pub voted: BTreeSet<PollChoiceId>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct HomePosts;
+pub struct HomePosts {
+    pub page: Page,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct HomePostsOk {
     pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct LikedPosts;
+pub struct LikedPosts {
+    pub page: Page,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct LikedPostsOk {
     pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct BookmarkedPosts;
+pub struct BookmarkedPosts {
+    pub page: Page,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct BookmarkedPostsOk {
     pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MentionedPosts {
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MentionedPostsOk {
+    pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TagTimeline {
+    pub tag: String,
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TagTimelineOk {
+    pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TrendingTags {
+    #[serde(default)]
+    pub window: TrendingWindow,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TrendingTagsOk {
+    /// Most used first.
+    pub tags: Vec<TagCount>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SearchPosts {
+    pub query: String,
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SearchPostsOk {
+    pub results: Vec<PostSearchResult>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListConversations {
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListConversationsOk {
+    pub conversations: Vec<ConversationPreview>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetConversation {
+    pub with_user: UserId,
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetConversationOk {
+    pub with_user: PublicUserProfile,
+    pub messages: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetThread {
+    pub post_id: PostId,
+    /// How many levels of replies to include. The server picks a default when not set.
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetThreadOk {
+    /// Posts that the requested post replies to, starting from the thread root.
+    pub ancestors: Vec<PublicPost>,
+    pub post: PublicPost,
+    /// Replies in depth-first order. Only direct replies are paginated.
+    pub replies: Vec<ThreadReply>,
+    pub next_cursor: Option<Cursor>,
+    /// Whether the viewer currently mutes the replies beneath the requested post.
+    pub muted: bool,
 }
@@ -1,5 +1,7 @@
+use std::collections::BTreeSet;
+
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use uchat_domain::{
     ids::{ImageId, PollChoiceId, PostId, UserId},
     Caption, Headline, Message, PollChoiceDescription, PollHeadline, Username,
@@ -50,7 +52,54 @@ pub struct PollChoice {
 pub struct Poll {
     pub headline: PollHeadline,
     pub choices: Vec<PollChoice>,
-    pub voted: Option<PollChoiceId>,
+    #[serde(default)]
+    pub selection: PollSelection,
+    /// Choices the viewer voted for.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub voted: BTreeSet<PollChoiceId>,
+    /// When voting ends; polls without a deadline stay open.
+    #[serde(default)]
+    pub closes_at: Option<DateTime<Utc>>,
+    /// Hide vote counts from everyone but the author until the poll closes.
+    #[serde(default)]
+    pub hide_results: bool,
+}
+
+impl Poll {
+    pub fn max_choices(&self) -> usize {
+        match self.selection {
+            PollSelection::Single => 1,
+            PollSelection::Multiple { max_choices } => max_choices as usize,
+        }
+    }
+
+    pub fn is_closed_at(&self, time: DateTime<Utc>) -> bool {
+        self.closes_at.map_or(false, |closes_at| closes_at <= time)
+    }
+
+    /// Whether vote counts are withheld from `viewer`, who may be the author of the poll.
+    pub fn results_hidden(&self, viewer_is_author: bool, time: DateTime<Utc>) -> bool {
+        self.hide_results && !viewer_is_author && !self.is_closed_at(time)
+    }
+}
+
+/// Reads an explicit `null` the same as a missing field.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+/// How many choices of a poll a user may vote for.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum PollSelection {
+    #[default]
+    Single,
+    Multiple {
+        max_choices: u32,
+    },
 }
 
 impl From<Poll> for Content {
@@ -66,6 +115,21 @@ pub enum Content {
     Poll(Poll),
 }
 
+impl Content {
+    /// Normalized `#tag`s in the chat message, image caption, or poll headline.
+    pub fn hashtags(&self) -> Vec<String> {
+        match self {
+            Self::Chat(chat) => uchat_domain::hashtags(chat.message.as_ref()),
+            Self::Image(image) => image
+                .caption
+                .as_ref()
+                .map(|caption| uchat_domain::hashtags(caption.as_ref()))
+                .unwrap_or_default(),
+            Self::Poll(poll) => uchat_domain::hashtags(poll.headline.as_ref()),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct NewPostOptions {
     pub reply_to: Option<PostId>,
@@ -96,13 +160,28 @@ pub struct PublicPost {
     pub by_user: PublicUserProfile,
     pub content: Content,
     pub time_posted: DateTime<Utc>,
+    /// When the content was last changed, if it ever was.
+    pub edited_at: Option<DateTime<Utc>>,
     pub reply_to: Option<(Username, UserId, PostId)>,
+    /// Users mentioned with `@handle` in the message, ordered by handle.
+    pub mentions: Vec<(Username, UserId)>,
     pub like_status: LikeStatus,
     pub bookmarked: bool,
     pub boosted: bool,
     pub likes: i64,
     pub dislikes: i64,
     pub boosts: i64,
+    /// Emoji reactions to the post, most used first.
+    pub emoji_reactions: Vec<EmojiCount>,
+    /// The emoji the viewer reacted with, if any.
+    pub my_emoji: Option<String>,
+}
+
+/// Number of users who reacted to a post with an emoji.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EmojiCount {
+    pub emoji: String,
+    pub count: i64,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
@@ -135,8 +214,72 @@ impl From<BoostAction> for bool {
     }
 }
 
+/// Content of a post before it was edited.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PostRevision {
+    pub content: Content,
+    /// When this content was replaced.
+    pub revised_at: DateTime<Utc>,
+}
+
+/// The latest message of a direct message conversation.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ConversationPreview {
+    pub with_user: PublicUserProfile,
+    pub last_message: PublicPost,
+}
+
+/// A reply within a thread. Direct replies to the thread's post have a depth of 1.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ThreadReply {
+    pub post: PublicPost,
+    pub depth: u32,
+}
+
+/// Part of a search result snippet. Highlighted fragments matched the search query.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SnippetFragment {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PostSearchResult {
+    pub post: PublicPost,
+    pub snippet: Vec<SnippetFragment>,
+}
+
+/// Number of recent posts using a hashtag.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub posts: i64,
+}
+
+/// How far back to look when ranking trending posts.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum TrendingWindow {
+    Hour,
+    #[default]
+    Day,
+    Week,
+}
+
+impl TrendingWindow {
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            Self::Hour => chrono::Duration::hours(1),
+            Self::Day => chrono::Duration::days(1),
+            Self::Week => chrono::Duration::weeks(1),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
 pub enum VoteCast {
     Yes,
-    AlreadyVoted,
+    /// All previous votes were removed.
+    Retracted,
+    /// The poll is no longer accepting votes.
+    Closed,
 }
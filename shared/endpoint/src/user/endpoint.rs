@@ -4,14 +4,16 @@ use uchat_domain::{ids::*, Password, Username};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::{post::PublicPost, Update};
+use crate::{post::PublicPost, Cursor, Page, Update};
 
-use super::{FollowAction, PublicUserProfile};
+use super::{FollowAction, MuteDuration, PublicUserProfile, SessionInfo};
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct CreateUser {
     pub username: Username,
     pub password: Password,
+    /// Identifies the device creating the account, so its session is kept apart from others.
+    pub device_id: Option<DeviceId>,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -27,10 +29,13 @@ pub struct CreateUserOk {
 pub struct Login {
     pub username: Username,
     pub password: Password,
+    /// Identifies the device logging in, so its session is kept apart from others.
+    pub device_id: Option<DeviceId>,
 }
 
+/// Session details for a completed login.
 #[derive(Clone, Deserialize, Serialize)]
-pub struct LoginOk {
+pub struct LoggedIn {
     pub session_signature: String,
     pub session_id: SessionId,
     pub session_expires: DateTime<Utc>,
@@ -40,6 +45,139 @@ pub struct LoginOk {
     pub user_id: UserId,
 }
 
+#[derive(Clone, Deserialize, Serialize)]
+pub enum LoginOk {
+    LoggedIn(LoggedIn),
+    /// The password was correct, but the user also needs to enter a code from their
+    /// authenticator app (or a recovery code) using [`CompleteLogin`].
+    TwoFactorRequired {
+        pending_token: String,
+    },
+}
+
+/// Finishes a login which needs a second factor.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CompleteLogin {
+    /// Token from [`LoginOk::TwoFactorRequired`], valid for a few minutes.
+    pub pending_token: String,
+    /// Code from the authenticator app, or one of the recovery codes.
+    pub code: String,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct CompleteLoginOk {
+    pub session: LoggedIn,
+}
+
+/// Starts setting up an authenticator app. Two-factor login is only turned on once a code from the
+/// app is entered with [`ConfirmTwoFactor`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EnableTwoFactor;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EnableTwoFactorOk {
+    /// Base32 secret, for entering into the app by hand.
+    pub secret: String,
+    /// `otpauth://` URI, for showing as a QR code.
+    pub otpauth_uri: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ConfirmTwoFactor {
+    pub code: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ConfirmTwoFactorOk {
+    /// Each can be used once in place of a code from the app. They are not shown again.
+    pub recovery_codes: Vec<String>,
+}
+
+/// Turns off two-factor login, given the account password and a current code or a recovery code.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct DisableTwoFactor {
+    pub password: Password,
+    pub code: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DisableTwoFactorOk;
+
+/// Confirms an email address using the token from a confirmation link.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ConfirmEmail {
+    pub token: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ConfirmEmailOk {
+    pub email: String,
+}
+
+/// Sends another confirmation link for the current, unconfirmed email address.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ResendEmailConfirmation;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ResendEmailConfirmationOk;
+
+/// Emails a password reset link, provided `email` is a confirmed address.
+///
+/// The response is the same whether or not a link was sent, so it can't be used to find out which
+/// addresses have accounts.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RequestPasswordReset {
+    pub email: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RequestPasswordResetOk;
+
+/// Chooses a new password using the token from a reset link. Every session of the user is logged
+/// out.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ResetPassword {
+    pub token: String,
+    pub password: Password,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ResetPasswordOk;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Logout;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct LogoutOk;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListSessions;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListSessionsOk {
+    /// Most recently created first.
+    pub sessions: Vec<SessionInfo>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RevokeSession {
+    pub session_id: SessionId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RevokeSessionOk {
+    pub session_id: SessionId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RevokeAllOtherSessions;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RevokeAllOtherSessionsOk {
+    /// Number of sessions which were logged out.
+    pub revoked: u64,
+}
+
 #[derive(Clone, Deserialize, Serialize)]
 pub struct GetMyProfile;
 
@@ -47,6 +185,8 @@ pub struct GetMyProfile;
 pub struct GetMyProfileOk {
     pub display_name: Option<String>,
     pub email: Option<String>,
+    pub email_confirmed: bool,
+    pub two_factor_enabled: bool,
     pub profile_image: Option<Url>,
     pub user_id: UserId,
 }
@@ -67,12 +207,14 @@ pub struct UpdateProfileOk {
 #[derive(Clone, Deserialize, Serialize)]
 pub struct ViewProfile {
     pub for_user: UserId,
+    pub page: Page,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct ViewProfileOk {
     pub profile: PublicUserProfile,
     pub posts: Vec<PublicPost>,
+    pub next_cursor: Option<Cursor>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -85,3 +227,120 @@ pub struct FollowUser {
 pub struct FollowUserOk {
     pub status: FollowAction,
 }
+
+/// Lists the users following `user_id`, most recent follows first.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListFollowers {
+    pub user_id: UserId,
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListFollowersOk {
+    pub users: Vec<PublicUserProfile>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Lists the users that `user_id` follows, most recent follows first.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListFollowing {
+    pub user_id: UserId,
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListFollowingOk {
+    pub users: Vec<PublicUserProfile>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Blocks `user_id`, removing follows between both users.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct BlockUser {
+    pub user_id: UserId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct BlockUserOk {
+    pub user_id: UserId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnblockUser {
+    pub user_id: UserId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnblockUserOk {
+    pub user_id: UserId,
+}
+
+/// Lists the users blocked by the requesting user, most recent blocks first.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListBlocked {
+    pub page: Page,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListBlockedOk {
+    pub users: Vec<PublicUserProfile>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Hides the posts and boosts of `user_id` from the requesting user's feeds.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MuteUser {
+    pub user_id: UserId,
+    pub duration: MuteDuration,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MuteUserOk {
+    pub user_id: UserId,
+    /// `None` when the mute never expires.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnmuteUser {
+    pub user_id: UserId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnmuteUserOk {
+    pub user_id: UserId,
+}
+
+/// Hides the replies beneath `post_id` from the requesting user's feeds.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MuteThread {
+    pub post_id: PostId,
+    pub duration: MuteDuration,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MuteThreadOk {
+    pub post_id: PostId,
+    /// `None` when the mute never expires.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnmuteThread {
+    pub post_id: PostId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UnmuteThreadOk {
+    pub post_id: PostId,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SearchUsers {
+    pub query: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SearchUsersOk {
+    pub users: Vec<PublicUserProfile>,
+}
@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use uchat_domain::{ids::UserId, DisplayName};
+use uchat_domain::{
+    ids::{SessionId, UserId},
+    DisplayName,
+};
 use url::Url;
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -11,6 +14,15 @@ pub struct PublicUserProfile {
     pub profile_image: Option<Url>,
     pub created_at: DateTime<Utc>,
     pub am_following: bool,
+    /// Whether this user follows the viewer.
+    pub follows_me: bool,
+    /// Whether the viewer has blocked this user.
+    pub am_blocking: bool,
+    /// Whether the viewer currently mutes this user.
+    pub am_muting: bool,
+    pub follower_count: i64,
+    pub following_count: i64,
+    pub post_count: i64,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
@@ -27,3 +39,35 @@ impl From<FollowAction> for bool {
         }
     }
 }
+
+/// How long a mute lasts.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum MuteDuration {
+    Day,
+    Week,
+    #[default]
+    Forever,
+}
+
+impl MuteDuration {
+    /// `None` when the mute never expires.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        match self {
+            Self::Day => Some(chrono::Duration::days(1)),
+            Self::Week => Some(chrono::Duration::weeks(1)),
+            Self::Forever => None,
+        }
+    }
+}
+
+/// A device which is logged in to the account.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SessionInfo {
+    pub id: SessionId,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Describes the device, when known.
+    pub device: Option<String>,
+    /// Whether this is the session making the request.
+    pub current: bool,
+}